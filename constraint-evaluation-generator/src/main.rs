@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fs::write;
 
@@ -263,16 +264,29 @@ fn generate_constraint_code(constraints: Constraints) -> TokenStream {
     let (term_constraint_degrees, term_constraints_bfe, term_constraints_xfe) =
         tokenize_circuits(&mut term_constraint_circuits);
 
+    let tasm_constraint_evaluation_code = generate_tasm_constraint_evaluation_code(
+        &init_constraint_circuits,
+        &cons_constraint_circuits,
+        &tran_constraint_circuits,
+        &term_constraint_circuits,
+    );
+
     quote!(
     use ndarray::ArrayView1;
     use twenty_first::shared_math::b_field_element::BFieldElement;
     use twenty_first::shared_math::mpolynomial::Degree;
     use twenty_first::shared_math::x_field_element::XFieldElement;
 
+    use crate::instruction::AnInstruction::*;
+    use crate::instruction::LabelledInstruction;
+    use crate::op_stack::NumberOfWords::*;
+    use crate::op_stack::OpStackElement::*;
     use crate::table::challenges::Challenges;
     use crate::table::challenges::ChallengeId::*;
     use crate::table::extension_table::Evaluable;
     use crate::table::extension_table::Quotientable;
+    use crate::table::extension_table::TasmConstraintEvaluation;
+    use crate::table::extension_table::TasmConstraintEvaluationMemoryLayout;
     use crate::table::master_table::MasterExtTable;
 
     // This file has been auto-generated. Any modifications _will_ be lost.
@@ -417,6 +431,8 @@ fn generate_constraint_code(constraints: Constraints) -> TokenStream {
             [#term_constraint_degrees].to_vec()
         }
     }
+
+    #tasm_constraint_evaluation_code
     )
 }
 
@@ -653,6 +669,230 @@ fn evaluate_single_node<II: InputIndicator>(
     quote!((#evaluated_lhs) #binop (#evaluated_rhs))
 }
 
+/// Generate the Triton assembly that evaluates all constraints, see
+/// [`TasmConstraintEvaluation`](triton_vm::table::extension_table::TasmConstraintEvaluation).
+fn generate_tasm_constraint_evaluation_code(
+    init_constraints: &[ConstraintCircuit<SingleRowIndicator>],
+    cons_constraints: &[ConstraintCircuit<SingleRowIndicator>],
+    tran_constraints: &[ConstraintCircuit<DualRowIndicator>],
+    term_constraints: &[ConstraintCircuit<SingleRowIndicator>],
+) -> TokenStream {
+    let num_constraints = init_constraints.len()
+        + cons_constraints.len()
+        + tran_constraints.len()
+        + term_constraints.len();
+    let mut tasm_generator = TasmGenerator::new(num_constraints);
+    tasm_generator.evaluate_and_store_constraints(init_constraints);
+    tasm_generator.evaluate_and_store_constraints(cons_constraints);
+    tasm_generator.evaluate_and_store_constraints(tran_constraints);
+    tasm_generator.evaluate_and_store_constraints(term_constraints);
+    let evaluations = tasm_generator.evaluations;
+
+    quote!(
+    impl TasmConstraintEvaluation for MasterExtTable {
+        #[allow(unused_variables)]
+        fn air_constraint_evaluation_tasm(
+            mem_layout: TasmConstraintEvaluationMemoryLayout,
+        ) -> Vec<LabelledInstruction> {
+            let TasmConstraintEvaluationMemoryLayout {
+                free_mem_page_ptr,
+                curr_base_row_ptr,
+                curr_ext_row_ptr,
+                next_base_row_ptr,
+                next_ext_row_ptr,
+                challenges_ptr,
+            } = mem_layout;
+            let push_address =
+                |ptr: BFieldElement, offset: u64| Push(ptr + BFieldElement::new(offset));
+
+            let mut instructions = vec![];
+            #(instructions.extend([#(#evaluations),*]);)*
+            instructions
+                .into_iter()
+                .map(LabelledInstruction::Instruction)
+                .collect()
+        }
+    }
+    )
+}
+
+/// The location of a constraint circuit's input in the memory layout of
+/// [`TasmConstraintEvaluation`](triton_vm::table::extension_table::TasmConstraintEvaluation).
+trait TasmInputLocation {
+    /// The identifier of the pointer to the input's row and the input's index in that row.
+    fn row_pointer_and_index(&self) -> (TokenStream, usize);
+}
+
+impl TasmInputLocation for SingleRowIndicator {
+    fn row_pointer_and_index(&self) -> (TokenStream, usize) {
+        match *self {
+            SingleRowIndicator::BaseRow(i) => (quote!(curr_base_row_ptr), i),
+            SingleRowIndicator::ExtRow(i) => (quote!(curr_ext_row_ptr), i),
+        }
+    }
+}
+
+impl TasmInputLocation for DualRowIndicator {
+    fn row_pointer_and_index(&self) -> (TokenStream, usize) {
+        match *self {
+            DualRowIndicator::CurrentBaseRow(i) => (quote!(curr_base_row_ptr), i),
+            DualRowIndicator::CurrentExtRow(i) => (quote!(curr_ext_row_ptr), i),
+            DualRowIndicator::NextBaseRow(i) => (quote!(next_base_row_ptr), i),
+            DualRowIndicator::NextExtRow(i) => (quote!(next_ext_row_ptr), i),
+        }
+    }
+}
+
+/// Generates Triton assembly evaluating constraint circuits on the op stack. Every value is an
+/// extension field element. Nodes that are referenced more than once are evaluated only once and
+/// stored in the scratch space following the evaluated constraints.
+struct TasmGenerator {
+    num_constraints: usize,
+    num_evaluated_constraints: usize,
+    scratch_space_slots: HashMap<usize, usize>,
+    num_used_scratch_space_slots: usize,
+    evaluations: Vec<Vec<TokenStream>>,
+}
+
+impl TasmGenerator {
+    fn new(num_constraints: usize) -> Self {
+        Self {
+            num_constraints,
+            num_evaluated_constraints: 0,
+            scratch_space_slots: HashMap::new(),
+            num_used_scratch_space_slots: 0,
+            evaluations: vec![],
+        }
+    }
+
+    /// The constraints are evaluated in the same order as by the generated Rust code: constraints
+    /// evaluating to base field elements first.
+    fn evaluate_and_store_constraints<II: InputIndicator + TasmInputLocation>(
+        &mut self,
+        constraints: &[ConstraintCircuit<II>],
+    ) {
+        // Node IDs are only unique within one category of constraints.
+        self.scratch_space_slots.clear();
+
+        let (base_constraints, ext_constraints): (Vec<_>, Vec<_>) = constraints
+            .iter()
+            .partition(|constraint| constraint.evaluates_to_base_element());
+        for constraint in base_constraints.into_iter().chain(ext_constraints) {
+            let mut instructions = vec![];
+            self.evaluate_node(constraint, &mut instructions);
+            let free_mem_page_offset = 3 * self.num_evaluated_constraints as u64;
+            instructions.extend(write_xfe(quote!(free_mem_page_ptr), free_mem_page_offset));
+            self.num_evaluated_constraints += 1;
+            self.evaluations.push(instructions);
+        }
+    }
+
+    fn evaluate_node<II: InputIndicator + TasmInputLocation>(
+        &mut self,
+        circuit: &ConstraintCircuit<II>,
+        instructions: &mut Vec<TokenStream>,
+    ) {
+        if let Some(&slot) = self.scratch_space_slots.get(&circuit.id) {
+            let scratch_space_offset = self.scratch_space_offset(slot);
+            instructions.extend(read_xfe(quote!(free_mem_page_ptr), scratch_space_offset));
+            return;
+        }
+
+        match &circuit.expression {
+            BConstant(bfe) => instructions.extend(push_xfe(bfe.lift())),
+            XConstant(xfe) => instructions.extend(push_xfe(*xfe)),
+            Input(input) => {
+                let (row_ptr, index) = input.row_pointer_and_index();
+                instructions.extend(read_xfe(row_ptr, 3 * index as u64));
+            }
+            Challenge(challenge_id) => {
+                let challenge_offset = 3 * challenge_id.index() as u64;
+                instructions.extend(read_xfe(quote!(challenges_ptr), challenge_offset));
+            }
+            BinaryOperation(binop, lhs, rhs) => {
+                let lhs = lhs.as_ref().borrow();
+                let rhs = rhs.as_ref().borrow();
+                self.evaluate_binary_operation(*binop, &lhs, &rhs, instructions);
+            }
+        }
+
+        let is_shared_node = circuit.visited_counter > 1;
+        if is_shared_node && matches!(circuit.expression, BinaryOperation(_, _, _)) {
+            let slot = self.num_used_scratch_space_slots;
+            self.num_used_scratch_space_slots += 1;
+            self.scratch_space_slots.insert(circuit.id, slot);
+
+            let scratch_space_offset = self.scratch_space_offset(slot);
+            instructions.extend([quote!(Dup(ST2)), quote!(Dup(ST2)), quote!(Dup(ST2))]);
+            instructions.extend(write_xfe(quote!(free_mem_page_ptr), scratch_space_offset));
+        }
+    }
+
+    fn evaluate_binary_operation<II: InputIndicator + TasmInputLocation>(
+        &mut self,
+        binop: BinOp,
+        lhs: &ConstraintCircuit<II>,
+        rhs: &ConstraintCircuit<II>,
+        instructions: &mut Vec<TokenStream>,
+    ) {
+        // Multiplication with a base field constant does not need to lift the constant.
+        if let (BinOp::Mul, BConstant(bfe)) = (binop, &rhs.expression) {
+            self.evaluate_node(lhs, instructions);
+            instructions.extend([push_bfe(*bfe), quote!(XbMul)]);
+            return;
+        }
+        if let (BinOp::Mul, BConstant(bfe)) = (binop, &lhs.expression) {
+            self.evaluate_node(rhs, instructions);
+            instructions.extend([push_bfe(*bfe), quote!(XbMul)]);
+            return;
+        }
+
+        self.evaluate_node(lhs, instructions);
+        self.evaluate_node(rhs, instructions);
+        match binop {
+            BinOp::Add => instructions.push(quote!(XxAdd)),
+            BinOp::Mul => instructions.push(quote!(XxMul)),
+            BinOp::Sub => {
+                let minus_one = push_bfe(-BFieldElement::new(1));
+                instructions.extend([minus_one, quote!(XbMul), quote!(XxAdd)]);
+            }
+        }
+    }
+
+    fn scratch_space_offset(&self, slot: usize) -> u64 {
+        3 * (self.num_constraints + slot) as u64
+    }
+}
+
+fn push_bfe(bfe: BFieldElement) -> TokenStream {
+    let bfe = tokenize_bfe(&bfe);
+    quote!(Push(#bfe))
+}
+
+fn push_xfe(xfe: XFieldElement) -> [TokenStream; 3] {
+    let [c0, c1, c2] = xfe.coefficients.map(push_bfe);
+    [c2, c1, c0]
+}
+
+/// Read the extension field element stored at `ptr + offset` onto the stack.
+fn read_xfe(ptr: TokenStream, offset: u64) -> [TokenStream; 3] {
+    let last_coefficient_offset = offset + 2;
+    [
+        quote!(push_address(#ptr, #last_coefficient_offset)),
+        quote!(ReadMem(N3)),
+        quote!(Pop(N1)),
+    ]
+}
+
+/// Move the extension field element on top of the stack to memory address `ptr + offset`.
+fn write_xfe(ptr: TokenStream, offset: u64) -> [TokenStream; 3] {
+    [
+        quote!(push_address(#ptr, #offset)),
+        quote!(WriteMem(N3)),
+        quote!(Pop(N1)),
+    ]
+}
+
 /// Given all substitution rules, generate the code that evaluates them in order.
 /// This includes generating the columns that are to be filled using the substitution rules.
 fn generate_degree_lowering_table_code(substitutions: &AllSubstitutions) -> TokenStream {
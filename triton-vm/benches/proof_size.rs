@@ -1,6 +1,3 @@
-use std::collections::HashMap;
-use std::ops::AddAssign;
-
 use criterion::criterion_group;
 use criterion::criterion_main;
 use criterion::measurement::Measurement;
@@ -9,22 +6,24 @@ use criterion::BenchmarkGroup;
 use criterion::BenchmarkId;
 use criterion::Criterion;
 use criterion::Throughput;
-use itertools::Itertools;
 use strum::Display;
 use strum::EnumCount;
 use strum::EnumIter;
+use twenty_first::shared_math::b_field_element::BFieldElement;
 use twenty_first::shared_math::bfield_codec::BFieldCodec;
 
 use triton_vm::example_programs::FIBONACCI_SEQUENCE;
 use triton_vm::example_programs::VERIFY_SUDOKU;
 use triton_vm::program::Program;
-use triton_vm::proof_stream::ProofStream;
+use triton_vm::proof::Claim;
+use triton_vm::prove;
 use triton_vm::prove_program;
 use triton_vm::stark::Stark;
 use triton_vm::stark::StarkHasher;
 use triton_vm::triton_program;
 use triton_vm::NonDeterminism;
 use triton_vm::Proof;
+use triton_vm::PublicInput;
 use triton_vm::StarkParameters;
 
 /// Ties together a program and its inputs.
@@ -187,50 +186,12 @@ fn log_2_fri_domain_length(parameters: StarkParameters, proof: &Proof) -> u32 {
     fri.domain.length.ilog2()
 }
 
-/// List the sizes of the proof's parts. If the same item type is contained multiple times, the
-/// sizes for that type are accumulated.
-fn break_down_proof_size(proof: &Proof) -> HashMap<String, usize> {
-    let mut proof_size_breakdown = HashMap::new();
-    let proof_stream: ProofStream<StarkHasher> = proof.try_into().unwrap();
-    for proof_item in proof_stream.items.iter() {
-        let item_name = proof_item.to_string();
-        let item_len = proof_item.encode().len();
-        proof_size_breakdown
-            .entry(item_name)
-            .or_insert(0)
-            .add_assign(item_len);
-    }
-    proof_size_breakdown
-}
-
-/// Sort given HashMap by its values in descending order.
-fn sort_hash_map_by_value_descending<K, V>(hash_map: HashMap<K, V>) -> Vec<(K, V)>
-where
-    V: Ord + Copy,
-{
-    let mut vec = hash_map.into_iter().collect_vec();
-    vec.sort_by_key(|&(_, value)| value);
-    vec.reverse();
-    vec
-}
-
 /// Print a tabular breakdown of the proof size.
 fn print_proof_size_breakdown(program_name: &str, proof: &Proof) {
-    let total_proof_size = proof.encode().len();
-    let proof_size_breakdown = break_down_proof_size(proof);
-    let proof_size_breakdown = sort_hash_map_by_value_descending(proof_size_breakdown);
-
+    let inspection = proof.inspect().unwrap();
     println!();
     println!("Proof size breakdown for {program_name}:");
-    println!(
-        "| {:<30} | {:>10} | {:>6} |",
-        "Category", "Size [bfe]", "[%]"
-    );
-    println!("|:{:-<30}-|-{:->10}:|-{:->6}:|", "", "", "");
-    for (category, size) in proof_size_breakdown {
-        let relative_size = (size as f64) / (total_proof_size as f64) * 100.0;
-        println!("| {category:<30} | {size:>10} | {relative_size:>6.2} |");
-    }
+    println!("{inspection}");
     println!();
 }
 
@@ -303,6 +264,66 @@ fn generate_statistics_for_various_programs(criterion: &mut Criterion<ProofSize>
     generate_statistics_for_program(&mut benchmark_group, "sudoku", &program_verify_sudoku());
 }
 
+fn all_fri_folding_factors() -> [usize; 4] {
+    [2, 4, 8, 16]
+}
+
+/// Generate a proof for the given program using the given parameters.
+fn prove_with_parameters(
+    parameters: StarkParameters,
+    program_and_input: &ProgramAndInput,
+) -> (Claim, Proof) {
+    let program = &program_and_input.program;
+    let public_input: PublicInput = program_and_input.public_input.clone().into();
+    let non_determinism: NonDeterminism<BFieldElement> =
+        (&program_and_input.non_determinism).into();
+    let output = program
+        .run(public_input.clone(), non_determinism.clone())
+        .unwrap();
+
+    let claim = Claim {
+        program_digest: program.hash::<StarkHasher>(),
+        input: public_input.individual_tokens,
+        output,
+    };
+    let proof = prove(parameters, &claim, program, non_determinism).unwrap();
+    (claim, proof)
+}
+
+/// Compare the proof sizes for different FRI folding factors.
+fn generate_statistics_for_various_fri_folding_factors(criterion: &mut Criterion<ProofSize>) {
+    let mut benchmark_group = criterion.benchmark_group("proof_size_fri_folding_factor");
+    let program = program_fib(100);
+    for fri_folding_factor in all_fri_folding_factors() {
+        let parameters = StarkParameters::default().with_fri_folding_factor(fri_folding_factor);
+        let program_name = format!("fib_100_fri_folding_factor_{fri_folding_factor}");
+        let (_, proof) = prove_with_parameters(parameters, &program);
+        print_proof_size_breakdown(&program_name, &proof);
+
+        let benchmark_id = BenchmarkId::new("fib_100", fri_folding_factor);
+        benchmark_group.bench_function(benchmark_id, |bencher| {
+            bencher.iter_custom(|num_iterations| {
+                let proof_size = proof.encode().len() as u64 * num_iterations;
+                ProofSize(proof_size as f64)
+            })
+        });
+    }
+}
+
+/// Compare the verification times for different FRI folding factors.
+fn verify_for_various_fri_folding_factors(criterion: &mut Criterion) {
+    let mut benchmark_group = criterion.benchmark_group("verify_fri_folding_factor");
+    let program = program_fib(100);
+    for fri_folding_factor in all_fri_folding_factors() {
+        let parameters = StarkParameters::default().with_fri_folding_factor(fri_folding_factor);
+        let (claim, proof) = prove_with_parameters(parameters, &program);
+        let benchmark_id = BenchmarkId::new("fib_100", fri_folding_factor);
+        benchmark_group.bench_function(benchmark_id, |bencher| {
+            bencher.iter(|| Stark::verify(parameters, &claim, &proof, &mut None).unwrap())
+        });
+    }
+}
+
 fn proof_size_measurements() -> Criterion<ProofSize> {
     Criterion::default()
         .with_measurement(ProofSize(0.0))
@@ -312,6 +333,12 @@ fn proof_size_measurements() -> Criterion<ProofSize> {
 criterion_group!(
     name = benches;
     config =  proof_size_measurements();
-    targets = generate_statistics_for_various_programs
+    targets = generate_statistics_for_various_programs,
+        generate_statistics_for_various_fri_folding_factors
+);
+criterion_group!(
+    name = verification_benches;
+    config = Criterion::default().sample_size(10);
+    targets = verify_for_various_fri_folding_factors
 );
-criterion_main!(benches);
+criterion_main!(benches, verification_benches);
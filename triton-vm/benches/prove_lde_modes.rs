@@ -0,0 +1,133 @@
+use std::alloc::GlobalAlloc;
+use std::alloc::Layout;
+use std::alloc::System;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::BenchmarkId;
+use criterion::Criterion;
+
+use triton_vm::aet::AlgebraicExecutionTrace;
+use triton_vm::example_programs::FIBONACCI_SEQUENCE;
+use triton_vm::progress::ProvingHandle;
+use triton_vm::proof::Claim;
+use triton_vm::stark::LowDegreeExtensionMode;
+use triton_vm::stark::ProverConfig;
+use triton_vm::stark::Stark;
+use triton_vm::stark::StarkHasher;
+use triton_vm::PublicInput;
+use triton_vm::StarkParameters;
+
+const FIBONACCI_INDEX: u64 = 100;
+
+/// Keeps track of the currently allocated and the peak number of allocated bytes.
+struct PeakTrackingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK_ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for PeakTrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let pointer = System.alloc(layout);
+        if !pointer.is_null() {
+            let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK_ALLOCATED.fetch_max(allocated, Ordering::Relaxed);
+        }
+        pointer
+    }
+
+    unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+        System.dealloc(pointer, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: PeakTrackingAllocator = PeakTrackingAllocator;
+
+fn all_modes() -> [LowDegreeExtensionMode; 3] {
+    [
+        LowDegreeExtensionMode::Memoize,
+        LowDegreeExtensionMode::Recompute { num_chunks: 4 },
+        LowDegreeExtensionMode::Recompute { num_chunks: 16 },
+    ]
+}
+
+/// cargo criterion --bench prove_lde_modes
+fn prove_lde_modes(criterion: &mut Criterion) {
+    let (claim, aet) = trace_execution();
+    let parameters = StarkParameters::default();
+
+    let bench_group_name = format!("prove_fib_{FIBONACCI_INDEX}_lde_modes");
+    let mut group = criterion.benchmark_group(bench_group_name);
+    group.sample_size(10);
+    for mode in all_modes() {
+        let config = ProverConfig {
+            low_degree_extension_mode: mode,
+            ..Default::default()
+        };
+        let bench_id = BenchmarkId::new("Prove", format!("{mode:?}"));
+        group.bench_function(bench_id, |bencher| {
+            bencher.iter(|| prove(parameters, &claim, &aet, &config))
+        });
+    }
+    group.finish();
+
+    println!("Peak memory while proving:");
+    for mode in all_modes() {
+        let config = ProverConfig {
+            low_degree_extension_mode: mode,
+            ..Default::default()
+        };
+        let peak_memory = peak_memory_while_proving(parameters, &claim, &aet, &config);
+        let peak_memory_in_mib = peak_memory as f64 / (1 << 20) as f64;
+        println!("{mode:?}: {peak_memory_in_mib:.1} MiB");
+    }
+}
+
+fn prove(
+    parameters: StarkParameters,
+    claim: &Claim,
+    aet: &AlgebraicExecutionTrace,
+    config: &ProverConfig,
+) {
+    let handle = ProvingHandle::new();
+    Stark::prove_with_config(parameters, claim, aet, config, &handle, &mut None).unwrap();
+}
+
+fn peak_memory_while_proving(
+    parameters: StarkParameters,
+    claim: &Claim,
+    aet: &AlgebraicExecutionTrace,
+    config: &ProverConfig,
+) -> usize {
+    let allocated_before_proving = ALLOCATED.load(Ordering::Relaxed);
+    PEAK_ALLOCATED.store(allocated_before_proving, Ordering::Relaxed);
+    prove(parameters, claim, aet, config);
+    PEAK_ALLOCATED.load(Ordering::Relaxed) - allocated_before_proving
+}
+
+fn trace_execution() -> (Claim, AlgebraicExecutionTrace) {
+    let program = FIBONACCI_SEQUENCE.clone();
+    let public_input: PublicInput = vec![FIBONACCI_INDEX].into();
+    let (aet, output) = program
+        .trace_execution(public_input.clone(), [].into())
+        .unwrap();
+
+    let claim = Claim {
+        input: public_input.individual_tokens,
+        program_digest: program.hash::<StarkHasher>(),
+        output,
+    };
+    (claim, aet)
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default();
+    targets = prove_lde_modes
+}
+
+criterion_main!(benches);
@@ -0,0 +1,78 @@
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::BenchmarkId;
+use criterion::Criterion;
+use twenty_first::shared_math::bfield_codec::BFieldCodec;
+
+use triton_vm::aet::AlgebraicExecutionTrace;
+use triton_vm::example_programs::FIBONACCI_SEQUENCE;
+use triton_vm::proof::Claim;
+use triton_vm::stark::Stark;
+use triton_vm::stark::StarkHasher;
+use triton_vm::PublicInput;
+use triton_vm::StarkParameters;
+
+const FIBONACCI_INDEX: u64 = 100;
+
+fn all_parameters() -> [(&'static str, StarkParameters); 2] {
+    let parameters = StarkParameters::default();
+    [
+        ("zero-knowledge", parameters),
+        ("no zero-knowledge", parameters.without_zero_knowledge()),
+    ]
+}
+
+/// cargo criterion --bench prove_zero_knowledge_modes
+fn prove_zero_knowledge_modes(criterion: &mut Criterion) {
+    let (claim, aet) = trace_execution();
+
+    let bench_group_name = format!("prove_fib_{FIBONACCI_INDEX}_zero_knowledge_modes");
+    let mut group = criterion.benchmark_group(bench_group_name);
+    group.sample_size(10);
+    for (mode, parameters) in all_parameters() {
+        let bench_id = BenchmarkId::new("Prove", mode);
+        group.bench_function(bench_id, |bencher| {
+            bencher.iter(|| Stark::prove(parameters, &claim, &aet, &mut None))
+        });
+    }
+    for (mode, parameters) in all_parameters() {
+        let proof = Stark::prove(parameters, &claim, &aet, &mut None);
+        let bench_id = BenchmarkId::new("Verify", mode);
+        group.bench_function(bench_id, |bencher| {
+            bencher.iter(|| Stark::verify(parameters, &claim, &proof, &mut None).unwrap())
+        });
+    }
+    group.finish();
+
+    println!("Proof sizes:");
+    for (mode, parameters) in all_parameters() {
+        let proof = Stark::prove(parameters, &claim, &aet, &mut None);
+        let padded_height = proof.padded_height().unwrap();
+        let fri_domain_length = Stark::derive_fri(parameters, padded_height).domain.length;
+        let proof_size = proof.encode().len();
+        println!("{mode}: {proof_size} bfe, FRI domain length {fri_domain_length}");
+    }
+}
+
+fn trace_execution() -> (Claim, AlgebraicExecutionTrace) {
+    let program = FIBONACCI_SEQUENCE.clone();
+    let public_input: PublicInput = vec![FIBONACCI_INDEX].into();
+    let (aet, output) = program
+        .trace_execution(public_input.clone(), [].into())
+        .unwrap();
+
+    let claim = Claim {
+        input: public_input.individual_tokens,
+        program_digest: program.hash::<StarkHasher>(),
+        output,
+    };
+    (claim, aet)
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default();
+    targets = prove_zero_knowledge_modes
+}
+
+criterion_main!(benches);
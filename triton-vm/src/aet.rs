@@ -9,6 +9,8 @@ use ndarray::Array2;
 use ndarray::Axis;
 use num_traits::One;
 use num_traits::Zero;
+use serde_derive::Deserialize;
+use serde_derive::Serialize;
 use twenty_first::shared_math::b_field_element::BFieldElement;
 use twenty_first::shared_math::digest::Digest;
 use twenty_first::shared_math::digest::DIGEST_LENGTH;
@@ -16,6 +18,11 @@ use twenty_first::shared_math::tip5;
 use twenty_first::shared_math::tip5::Tip5;
 use twenty_first::util_types::algebraic_hasher::SpongeHasher;
 
+use crate::checkpoint::decode_checkpoint;
+use crate::checkpoint::encode_checkpoint;
+use crate::checkpoint::CheckpointKind;
+use crate::error::CheckpointError;
+use crate::error::CheckpointError::*;
 use crate::error::InstructionError;
 use crate::error::InstructionError::InstructionPointerOverflow;
 use crate::instruction::Instruction;
@@ -340,6 +347,135 @@ impl AlgebraicExecutionTrace {
             .push_row(ram_call.to_table_row().view())
             .unwrap();
     }
+
+    /// Serialize the (possibly partially built) trace into a versioned
+    /// [checkpoint](crate::checkpoint). Tracing can be continued from the checkpoint using
+    /// [`resume_from_checkpoint`](Self::resume_from_checkpoint) in conjunction with
+    /// [`Program::continue_trace_execution`].
+    ///
+    /// The checkpoint is deterministic: two traces with identical content produce identical
+    /// checkpoints. The program itself is not part of the checkpoint, only its digest.
+    pub fn checkpoint(&self) -> Vec<u8> {
+        let mut u32_entries = self.u32_entries.iter().map(|(&e, &m)| (e, m)).collect_vec();
+        u32_entries.sort_unstable_by_key(|(entry, _)| {
+            let opcode = entry.instruction.opcode();
+            (
+                opcode,
+                entry.left_operand.value(),
+                entry.right_operand.value(),
+            )
+        });
+        let mut cascade_table_lookup_multiplicities = self
+            .cascade_table_lookup_multiplicities
+            .iter()
+            .map(|(&limb, &multiplicity)| (limb, multiplicity))
+            .collect_vec();
+        cascade_table_lookup_multiplicities.sort_unstable();
+
+        let checkpoint = AetCheckpoint {
+            program_digest: self.program.hash::<StarkHasher>(),
+            instruction_multiplicities: self.instruction_multiplicities.clone(),
+            processor_trace: (&self.processor_trace).into(),
+            op_stack_underflow_trace: (&self.op_stack_underflow_trace).into(),
+            ram_trace: (&self.ram_trace).into(),
+            program_hash_trace: (&self.program_hash_trace).into(),
+            hash_trace: (&self.hash_trace).into(),
+            sponge_trace: (&self.sponge_trace).into(),
+            u32_entries,
+            cascade_table_lookup_multiplicities,
+            lookup_table_lookup_multiplicities: self.lookup_table_lookup_multiplicities.to_vec(),
+        };
+        encode_checkpoint(CheckpointKind::AlgebraicExecutionTrace, &checkpoint)
+    }
+
+    /// Restore a trace previously serialized using [`checkpoint`](Self::checkpoint). The given
+    /// program must be the one whose execution the checkpointed trace records.
+    pub fn resume_from_checkpoint(
+        program: Program,
+        checkpoint: &[u8],
+    ) -> Result<Self, CheckpointError> {
+        let checkpoint: AetCheckpoint =
+            decode_checkpoint(CheckpointKind::AlgebraicExecutionTrace, checkpoint)?;
+        if checkpoint.program_digest != program.hash::<StarkHasher>() {
+            return Err(ProgramDigestMismatch);
+        }
+        if checkpoint.instruction_multiplicities.len() != program.len_bwords() {
+            return Err(InconsistentTraceDimensions);
+        }
+        let lookup_table_lookup_multiplicities = checkpoint
+            .lookup_table_lookup_multiplicities
+            .try_into()
+            .map_err(|_| InconsistentTraceDimensions)?;
+
+        Ok(Self {
+            program,
+            instruction_multiplicities: checkpoint.instruction_multiplicities,
+            processor_trace: checkpoint
+                .processor_trace
+                .into_array(processor_table::BASE_WIDTH)?,
+            op_stack_underflow_trace: checkpoint
+                .op_stack_underflow_trace
+                .into_array(op_stack_table::BASE_WIDTH)?,
+            ram_trace: checkpoint.ram_trace.into_array(ram_table::BASE_WIDTH)?,
+            program_hash_trace: checkpoint
+                .program_hash_trace
+                .into_array(hash_table::BASE_WIDTH)?,
+            hash_trace: checkpoint.hash_trace.into_array(hash_table::BASE_WIDTH)?,
+            sponge_trace: checkpoint.sponge_trace.into_array(hash_table::BASE_WIDTH)?,
+            u32_entries: checkpoint.u32_entries.into_iter().collect(),
+            cascade_table_lookup_multiplicities: checkpoint
+                .cascade_table_lookup_multiplicities
+                .into_iter()
+                .collect(),
+            lookup_table_lookup_multiplicities,
+        })
+    }
+}
+
+/// The serializable representation of an [`AlgebraicExecutionTrace`]. Maps are stored as sorted
+/// lists to make the checkpoint deterministic.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AetCheckpoint {
+    program_digest: Digest,
+    instruction_multiplicities: Vec<u32>,
+    processor_trace: TraceCheckpoint,
+    op_stack_underflow_trace: TraceCheckpoint,
+    ram_trace: TraceCheckpoint,
+    program_hash_trace: TraceCheckpoint,
+    hash_trace: TraceCheckpoint,
+    sponge_trace: TraceCheckpoint,
+    u32_entries: Vec<(U32TableEntry, u64)>,
+    cascade_table_lookup_multiplicities: Vec<(u16, u64)>,
+    lookup_table_lookup_multiplicities: Vec<u64>,
+}
+
+/// A row-major representation of a trace table.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TraceCheckpoint {
+    num_rows: usize,
+    num_columns: usize,
+    elements: Vec<BFieldElement>,
+}
+
+impl From<&Array2<BFieldElement>> for TraceCheckpoint {
+    fn from(trace: &Array2<BFieldElement>) -> Self {
+        Self {
+            num_rows: trace.nrows(),
+            num_columns: trace.ncols(),
+            elements: trace.iter().copied().collect(),
+        }
+    }
+}
+
+impl TraceCheckpoint {
+    /// Restore the trace table, which must have the given number of columns.
+    fn into_array(self, num_columns: usize) -> Result<Array2<BFieldElement>, CheckpointError> {
+        if self.num_columns != num_columns {
+            return Err(InconsistentTraceDimensions);
+        }
+        let shape = (self.num_rows, self.num_columns);
+        Array2::from_shape_vec(shape, self.elements).map_err(|_| InconsistentTraceDimensions)
+    }
 }
 
 #[cfg(test)]
@@ -50,18 +50,32 @@ impl ArithmeticDomain {
         let batch_evaluation_is_possible = self.length >= polynomial.coefficients.len();
         match batch_evaluation_is_possible {
             true => polynomial.fast_coset_evaluate(self.offset, self.generator, self.length),
-            false => self.evaluate_in_every_point_individually(polynomial),
+            false => self.evaluate_reduced_polynomial(polynomial),
         }
     }
 
-    fn evaluate_in_every_point_individually<FF>(&self, polynomial: &Polynomial<FF>) -> Vec<FF>
+    /// Evaluate a polynomial with more coefficients than the domain has elements. Every domain
+    /// value `x` satisfies `x^length = offset^length`, which allows reducing the polynomial to one
+    /// with at most `length` coefficients that agrees with the original on the domain.
+    fn evaluate_reduced_polynomial<FF>(&self, polynomial: &Polynomial<FF>) -> Vec<FF>
     where
         FF: FiniteField + MulAssign<BFieldElement> + From<BFieldElement>,
     {
-        self.domain_values()
-            .iter()
-            .map(|&v| polynomial.evaluate(&v.into()))
-            .collect()
+        if self.length == 0 {
+            return vec![];
+        }
+
+        let offset_pow_length = self.offset.mod_pow_u32(self.length as u32);
+        let mut reduced_coefficients = vec![FF::zero(); self.length];
+        let mut weight = FF::one();
+        for coefficients in polynomial.coefficients.chunks(self.length) {
+            for (reduced, &coefficient) in reduced_coefficients.iter_mut().zip(coefficients) {
+                *reduced += weight * coefficient;
+            }
+            weight *= offset_pow_length;
+        }
+        let reduced_polynomial = Polynomial::new(reduced_coefficients);
+        reduced_polynomial.fast_coset_evaluate(self.offset, self.generator, self.length)
     }
 
     pub fn interpolate<FF>(&self, values: &[FF]) -> Polynomial<FF>
@@ -97,6 +111,21 @@ impl ArithmeticDomain {
         domain_values
     }
 
+    /// The sub-domain made up of every `num_chunks`-th element of this domain, starting with the
+    /// element at index `chunk_index`. The `num_chunks` many such chunks are disjoint and together
+    /// make up this domain. The number of chunks must be a power of 2 not exceeding the domain's
+    /// length.
+    #[must_use]
+    pub fn interleaved_chunk(&self, chunk_index: usize, num_chunks: usize) -> Self {
+        assert!(num_chunks.is_power_of_two() && num_chunks <= self.length);
+        assert!(chunk_index < num_chunks);
+        Self {
+            offset: self.domain_value(chunk_index as u32),
+            generator: self.generator.mod_pow_u32(num_chunks as u32),
+            length: self.length / num_chunks,
+        }
+    }
+
     #[must_use]
     pub(crate) fn halve(&self) -> Self {
         assert!(self.length >= 2);
@@ -132,6 +161,16 @@ mod tests {
         }
     }
 
+    prop_compose! {
+        fn arbitrary_small_domain()(
+            length in (0_usize..7).prop_map(|x| 1 << x),
+        )(
+            domain in arbitrary_domain_of_length(length),
+        ) -> ArithmeticDomain {
+            domain
+        }
+    }
+
     prop_compose! {
         fn arbitrary_halveable_domain()(
             length in (2_usize..17).prop_map(|x| 1 << x),
@@ -182,6 +221,40 @@ mod tests {
         domain.evaluate(&polynomial);
     }
 
+    #[proptest]
+    fn evaluating_polynomial_with_more_coefficients_than_domain_length_is_correct(
+        #[strategy(arbitrary_small_domain())] domain: ArithmeticDomain,
+        #[strategy(arbitrary_polynomial())] polynomial: Polynomial<XFieldElement>,
+    ) {
+        let domain_values = domain.domain_values();
+        let expected_values = domain_values
+            .iter()
+            .map(|&x| polynomial.evaluate(&x.lift()))
+            .collect_vec();
+        prop_assert_eq!(expected_values, domain.evaluate(&polynomial));
+    }
+
+    #[proptest]
+    fn interleaved_chunks_partition_the_domain(
+        #[strategy(arbitrary_domain())] domain: ArithmeticDomain,
+        #[strategy(0_u32..17)] log2_num_chunks: u32,
+    ) {
+        let num_chunks = 1 << log2_num_chunks;
+        prop_assume!(num_chunks <= domain.length);
+
+        let domain_values = domain.domain_values();
+        for chunk_index in 0..num_chunks {
+            let chunk = domain.interleaved_chunk(chunk_index, num_chunks);
+            let expected_values = domain_values
+                .iter()
+                .skip(chunk_index)
+                .step_by(num_chunks)
+                .copied()
+                .collect_vec();
+            prop_assert_eq!(expected_values, chunk.domain_values());
+        }
+    }
+
     #[test]
     fn domain_values() {
         let x_cubed_coefficients = [0, 0, 0, 1].map(BFieldElement::new).to_vec();
@@ -0,0 +1,120 @@
+//! Checkpoints allow pausing a long-running execution of Triton VM and resuming it later, possibly
+//! on a different machine. See [`VMState::checkpoint`][vm_state] and
+//! [`AlgebraicExecutionTrace::checkpoint`][aet].
+//!
+//! Every checkpoint starts with a fixed header, followed by the payload:
+//!
+//! | bytes | content                                              |
+//! |------:|:-----------------------------------------------------|
+//! | 0..4  | the magic bytes `TVCP`                               |
+//! | 4..6  | the format version, little-endian                    |
+//! | 6     | the [kind](CheckpointKind) of the checkpointed value |
+//! | 7..   | the [bincode]-serialized payload                     |
+//!
+//! [vm_state]: crate::vm::VMState::checkpoint
+//! [aet]: crate::aet::AlgebraicExecutionTrace::checkpoint
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::CheckpointError;
+use crate::error::CheckpointError::*;
+
+/// The magic bytes every checkpoint starts with.
+pub const CHECKPOINT_MAGIC: [u8; 4] = *b"TVCP";
+
+/// The version of the checkpoint format. Bumped whenever the layout of any checkpointed value
+/// changes in a backwards-incompatible way.
+pub const CHECKPOINT_VERSION: u16 = 1;
+
+const HEADER_LEN: usize = CHECKPOINT_MAGIC.len() + 2 + 1;
+
+/// The type of value contained in a checkpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CheckpointKind {
+    VMState,
+    AlgebraicExecutionTrace,
+}
+
+impl CheckpointKind {
+    fn to_byte(self) -> u8 {
+        match self {
+            CheckpointKind::VMState => 0,
+            CheckpointKind::AlgebraicExecutionTrace => 1,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Result<Self, CheckpointError> {
+        match byte {
+            0 => Ok(CheckpointKind::VMState),
+            1 => Ok(CheckpointKind::AlgebraicExecutionTrace),
+            _ => Err(UnknownKind(byte)),
+        }
+    }
+}
+
+pub(crate) fn encode_checkpoint<T: Serialize>(kind: CheckpointKind, payload: &T) -> Vec<u8> {
+    let mut checkpoint = Vec::with_capacity(HEADER_LEN);
+    checkpoint.extend_from_slice(&CHECKPOINT_MAGIC);
+    checkpoint.extend_from_slice(&CHECKPOINT_VERSION.to_le_bytes());
+    checkpoint.push(kind.to_byte());
+    bincode::serialize_into(&mut checkpoint, payload)
+        .expect("serializing into a vector of bytes must succeed");
+    checkpoint
+}
+
+pub(crate) fn decode_checkpoint<T: DeserializeOwned>(
+    expected_kind: CheckpointKind,
+    checkpoint: &[u8],
+) -> Result<T, CheckpointError> {
+    if checkpoint.len() < HEADER_LEN {
+        return Err(TooShort);
+    }
+    let (header, payload) = checkpoint.split_at(HEADER_LEN);
+    if header[..CHECKPOINT_MAGIC.len()] != CHECKPOINT_MAGIC {
+        return Err(BadMagic);
+    }
+    let version = u16::from_le_bytes([header[4], header[5]]);
+    if version != CHECKPOINT_VERSION {
+        return Err(UnsupportedVersion(version));
+    }
+    let kind = CheckpointKind::from_byte(header[6])?;
+    if kind != expected_kind {
+        return Err(UnexpectedKind(expected_kind, kind));
+    }
+    Ok(bincode::deserialize(payload)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::let_assert;
+
+    use super::*;
+
+    #[test]
+    fn checkpoint_of_wrong_kind_is_rejected() {
+        let checkpoint = encode_checkpoint(CheckpointKind::VMState, &42_u64);
+        let decoded =
+            decode_checkpoint::<u64>(CheckpointKind::AlgebraicExecutionTrace, &checkpoint);
+        let_assert!(Err(UnexpectedKind(_, CheckpointKind::VMState)) = decoded);
+    }
+
+    #[test]
+    fn checkpoint_of_unknown_version_is_rejected() {
+        let mut checkpoint = encode_checkpoint(CheckpointKind::VMState, &42_u64);
+        checkpoint[4] ^= 0xff;
+        let decoded = decode_checkpoint::<u64>(CheckpointKind::VMState, &checkpoint);
+        let_assert!(Err(UnsupportedVersion(_)) = decoded);
+    }
+
+    #[test]
+    fn checkpoint_without_magic_is_rejected() {
+        let mut checkpoint = encode_checkpoint(CheckpointKind::VMState, &42_u64);
+        checkpoint[0] = b'X';
+        let decoded = decode_checkpoint::<u64>(CheckpointKind::VMState, &checkpoint);
+        let_assert!(Err(BadMagic) = decoded);
+
+        let decoded = decode_checkpoint::<u64>(CheckpointKind::VMState, &[]);
+        let_assert!(Err(TooShort) = decoded);
+    }
+}
@@ -0,0 +1,663 @@
+//! Execution coverage of Triton assembly programs.
+//!
+//! A [`Coverage`] aggregates the [instruction multiplicities] of one or many executions of the
+//! same [`Program`] and reports them per instruction, per basic block, and per label. It also
+//! identifies code that was never executed and `skiz` branches that were never taken. Reports can
+//! be exported in the [lcov] tracefile format or as JSON.
+//!
+//! Line numbers in reports count from 1. For coverage collected using [`Coverage::from_source`],
+//! they refer to the lines of the source code. Otherwise, they refer to the program's listing as
+//! produced by its [`Display`](std::fmt::Display) implementation.
+//!
+//! [instruction multiplicities]: AlgebraicExecutionTrace::instruction_multiplicities
+//! [lcov]: https://github.com/linux-test-project/lcov
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use itertools::Itertools;
+use serde_derive::Serialize;
+
+use crate::aet::AlgebraicExecutionTrace;
+use crate::instruction::AnInstruction;
+use crate::instruction::Instruction;
+use crate::instruction::LabelledInstruction;
+use crate::parser::parse;
+use crate::parser::to_labelled_instructions;
+use crate::parser::InstructionToken;
+use crate::parser::ParseError;
+use crate::program::Program;
+use crate::table::table_column::MasterBaseTableColumn;
+use crate::table::table_column::ProcessorBaseTableColumn;
+
+/// Execution counts of a [`Program`], aggregated over any number of runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coverage {
+    program: Program,
+    num_runs: usize,
+
+    /// The number of times the instruction at each address has been executed. Addresses holding
+    /// an instruction's argument always have count 0.
+    instruction_multiplicities: Vec<u64>,
+
+    /// The observed outcomes of every executed `skiz` instruction, keyed by its address.
+    skiz_outcomes: HashMap<usize, SkizOutcomes>,
+
+    /// The source line of every instruction, in program order, if the source code is known.
+    source_lines: Option<Vec<usize>>,
+}
+
+/// How often a `skiz` instruction executed or skipped its successor.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub struct SkizOutcomes {
+    /// The number of times the top of the stack was non-zero, executing the next instruction.
+    pub num_executed_next: u64,
+
+    /// The number of times the top of the stack was zero, skipping the next instruction.
+    pub num_skipped_next: u64,
+}
+
+/// One of the two branches of a `skiz` instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum SkizBranch {
+    /// The branch taken if the top of the stack is non-zero.
+    ExecuteNext,
+
+    /// The branch taken if the top of the stack is zero.
+    SkipNext,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct InstructionCoverage {
+    pub address: usize,
+    pub line: usize,
+    pub instruction: String,
+
+    /// The closest label at or before the instruction, if any.
+    pub label: Option<String>,
+    pub execution_count: u64,
+}
+
+/// A maximal sequence of instructions that is always executed from start to end.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct BasicBlockCoverage {
+    pub start_address: usize,
+
+    /// The first address after the basic block.
+    pub end_address: usize,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub label: Option<String>,
+    pub execution_count: u64,
+}
+
+/// The coverage of all instructions between a label and the next label. Reported lines are
+/// those of the first instruction after the label, since not every label appears in the
+/// program's listing.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct LabelCoverage {
+    pub label: String,
+    pub address: usize,
+    pub line: usize,
+
+    /// The number of times the first instruction after the label has been executed.
+    pub execution_count: u64,
+    pub num_instructions: usize,
+    pub num_executed_instructions: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct SkizBranchCoverage {
+    pub address: usize,
+    pub line: usize,
+    pub label: Option<String>,
+    pub outcomes: SkizOutcomes,
+
+    /// The branch that has never been taken.
+    pub never_taken: SkizBranch,
+}
+
+/// The complete coverage report, as exported by [`Coverage::to_json`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct CoverageReport {
+    pub num_runs: usize,
+    pub num_instructions: usize,
+    pub num_executed_instructions: usize,
+    pub instructions: Vec<InstructionCoverage>,
+    pub basic_blocks: Vec<BasicBlockCoverage>,
+    pub labels: Vec<LabelCoverage>,
+    pub never_taken_skiz_branches: Vec<SkizBranchCoverage>,
+}
+
+/// An instruction of the program alongside its line in the source code or program's listing.
+struct ListedInstruction {
+    address: usize,
+    line: usize,
+    instruction: AnInstruction<String>,
+    label: Option<String>,
+}
+
+impl SkizOutcomes {
+    fn never_taken_branch(&self) -> Option<SkizBranch> {
+        match (self.num_executed_next, self.num_skipped_next) {
+            (0, 0) => None,
+            (0, _) => Some(SkizBranch::ExecuteNext),
+            (_, 0) => Some(SkizBranch::SkipNext),
+            _ => None,
+        }
+    }
+}
+
+impl Coverage {
+    pub fn new(program: Program) -> Self {
+        let instruction_multiplicities = vec![0; program.len_bwords()];
+        Self {
+            program,
+            num_runs: 0,
+            instruction_multiplicities,
+            skiz_outcomes: HashMap::new(),
+            source_lines: None,
+        }
+    }
+
+    /// Collect coverage for the program with the given source code. In contrast to
+    /// [`new`](Self::new), reported lines refer to the source code, making the reports
+    /// suitable for annotating the source file.
+    pub fn from_source(source_code: &str) -> Result<Self, ParseError<'_>> {
+        let tokens = parse(source_code)?;
+        let source_lines = tokens
+            .iter()
+            .filter(|token| matches!(token, InstructionToken::Instruction(..)))
+            .map(|token| line_number(source_code, token.token_str()))
+            .collect();
+        let program = Program::new(&to_labelled_instructions(&tokens));
+
+        let mut coverage = Self::new(program);
+        coverage.source_lines = Some(source_lines);
+        Ok(coverage)
+    }
+
+    /// Add the execution counts of one run of the program.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the trace does not belong to the program this coverage is collected for.
+    pub fn add_trace(&mut self, aet: &AlgebraicExecutionTrace) {
+        assert_eq!(
+            self.program, aet.program,
+            "trace must belong to the covered program"
+        );
+        self.add_instruction_multiplicities(&aet.instruction_multiplicities);
+        self.add_skiz_outcomes(aet);
+    }
+
+    /// Add the [instruction multiplicities] of one run of the program. Since the multiplicities
+    /// do not carry any information about the outcome of `skiz` instructions, prefer
+    /// [`add_trace`](Self::add_trace) if the full trace is available.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of multiplicities does not match the length of the program.
+    ///
+    /// [instruction multiplicities]: AlgebraicExecutionTrace::instruction_multiplicities
+    pub fn add_instruction_multiplicities(&mut self, instruction_multiplicities: &[u32]) {
+        assert_eq!(
+            self.instruction_multiplicities.len(),
+            instruction_multiplicities.len()
+        );
+        for (count, &multiplicity) in self
+            .instruction_multiplicities
+            .iter_mut()
+            .zip_eq(instruction_multiplicities)
+        {
+            *count += u64::from(multiplicity);
+        }
+        self.num_runs += 1;
+    }
+
+    fn add_skiz_outcomes(&mut self, aet: &AlgebraicExecutionTrace) {
+        let ip_column = ProcessorBaseTableColumn::IP.base_table_index();
+        let ci_column = ProcessorBaseTableColumn::CI.base_table_index();
+        let skiz_opcode = Instruction::Skiz.opcode_b();
+
+        for rows in aet.processor_trace.rows().into_iter().tuple_windows() {
+            let (row, next_row) = rows;
+            if row[ci_column] != skiz_opcode {
+                continue;
+            }
+            let address = row[ip_column].value() as usize;
+            let next_address = next_row[ip_column].value() as usize;
+            let outcomes = self.skiz_outcomes.entry(address).or_default();
+            match next_address == address + Instruction::Skiz.size() {
+                true => outcomes.num_executed_next += 1,
+                false => outcomes.num_skipped_next += 1,
+            }
+        }
+    }
+
+    /// Combine the execution counts of another coverage of the same program into this one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the other coverage was collected for a different program.
+    pub fn merge(&mut self, other: &Coverage) {
+        assert_eq!(
+            self.program, other.program,
+            "coverage must be of same program"
+        );
+        for (count, &other_count) in self
+            .instruction_multiplicities
+            .iter_mut()
+            .zip_eq(&other.instruction_multiplicities)
+        {
+            *count += other_count;
+        }
+        for (&address, other_outcomes) in &other.skiz_outcomes {
+            let outcomes = self.skiz_outcomes.entry(address).or_default();
+            outcomes.num_executed_next += other_outcomes.num_executed_next;
+            outcomes.num_skipped_next += other_outcomes.num_skipped_next;
+        }
+        self.num_runs += other.num_runs;
+    }
+
+    pub fn num_runs(&self) -> usize {
+        self.num_runs
+    }
+
+    /// The share of instructions that has been executed at least once, between 0 and 1.
+    pub fn instruction_coverage_ratio(&self) -> f64 {
+        let instructions = self.instructions();
+        if instructions.is_empty() {
+            return 1.0;
+        }
+        let num_executed = instructions.iter().filter(|i| i.execution_count > 0);
+        num_executed.count() as f64 / instructions.len() as f64
+    }
+
+    fn listed_instructions(&self) -> Vec<ListedInstruction> {
+        let mut labels = self.program.labels_by_address().into_iter().peekable();
+        let mut listed_instructions = vec![];
+        let mut address = 0;
+        let mut label = None;
+        for (line_index, labelled_instruction) in
+            self.program.labelled_instructions().into_iter().enumerate()
+        {
+            let LabelledInstruction::Instruction(instruction) = labelled_instruction else {
+                continue;
+            };
+            while let Some((_, next_label)) = labels.next_if(|&(a, _)| a <= address as u64) {
+                label = Some(next_label);
+            }
+            let line = match &self.source_lines {
+                Some(source_lines) => source_lines[listed_instructions.len()],
+                None => line_index + 1,
+            };
+            let instruction_size = instruction.size();
+            listed_instructions.push(ListedInstruction {
+                address,
+                line,
+                instruction,
+                label: label.clone(),
+            });
+            address += instruction_size;
+        }
+        listed_instructions
+    }
+
+    pub fn instructions(&self) -> Vec<InstructionCoverage> {
+        self.listed_instructions()
+            .into_iter()
+            .map(|listed| InstructionCoverage {
+                address: listed.address,
+                line: listed.line,
+                instruction: listed.instruction.to_string(),
+                label: listed.label,
+                execution_count: self.instruction_multiplicities[listed.address],
+            })
+            .collect()
+    }
+
+    /// All instructions that have never been executed.
+    pub fn never_executed_instructions(&self) -> Vec<InstructionCoverage> {
+        self.instructions()
+            .into_iter()
+            .filter(|instruction| instruction.execution_count == 0)
+            .collect()
+    }
+
+    pub fn basic_blocks(&self) -> Vec<BasicBlockCoverage> {
+        let listed_instructions = self.listed_instructions();
+        let mut is_leader = vec![false; listed_instructions.len()];
+        if let Some(first) = is_leader.first_mut() {
+            *first = true;
+        }
+        for (index, listed) in listed_instructions.iter().enumerate() {
+            let label_changes = index > 0 && listed.label != listed_instructions[index - 1].label;
+            if label_changes {
+                is_leader[index] = true;
+            }
+            let successor_is_leader = match listed.instruction {
+                AnInstruction::Skiz => {
+                    if let Some(skip_target) = is_leader.get_mut(index + 2) {
+                        *skip_target = true;
+                    }
+                    true
+                }
+                AnInstruction::Call(_) | AnInstruction::Return => true,
+                AnInstruction::Recurse | AnInstruction::Halt => true,
+                _ => false,
+            };
+            if let Some(successor) = is_leader.get_mut(index + 1) {
+                *successor |= successor_is_leader;
+            }
+        }
+
+        let mut basic_blocks: Vec<BasicBlockCoverage> = vec![];
+        for (listed, is_leader) in listed_instructions.iter().zip_eq(is_leader) {
+            let end_address = listed.address + listed.instruction.size();
+            match basic_blocks.last_mut() {
+                Some(block) if !is_leader => {
+                    block.end_address = end_address;
+                    block.end_line = listed.line;
+                }
+                _ => basic_blocks.push(BasicBlockCoverage {
+                    start_address: listed.address,
+                    end_address,
+                    start_line: listed.line,
+                    end_line: listed.line,
+                    label: listed.label.clone(),
+                    execution_count: self.instruction_multiplicities[listed.address],
+                }),
+            }
+        }
+        basic_blocks
+    }
+
+    pub fn labels(&self) -> Vec<LabelCoverage> {
+        let instructions = self.instructions();
+        let instructions_by_label = instructions
+            .iter()
+            .filter(|instruction| instruction.label.is_some())
+            .group_by(|instruction| instruction.label.clone());
+
+        let mut labels = vec![];
+        for (label, instructions) in &instructions_by_label {
+            let instructions = instructions.collect_vec();
+            let first_instruction = instructions[0];
+            labels.push(LabelCoverage {
+                label: label.unwrap(),
+                address: first_instruction.address,
+                line: first_instruction.line,
+                execution_count: first_instruction.execution_count,
+                num_instructions: instructions.len(),
+                num_executed_instructions: instructions
+                    .iter()
+                    .filter(|instruction| instruction.execution_count > 0)
+                    .count(),
+            });
+        }
+        labels
+    }
+
+    /// All executed `skiz` instructions for which one of the two branches has never been taken.
+    /// Requires the coverage to be collected using [`add_trace`](Self::add_trace).
+    pub fn never_taken_skiz_branches(&self) -> Vec<SkizBranchCoverage> {
+        self.listed_instructions()
+            .into_iter()
+            .filter_map(|listed| {
+                let outcomes = *self.skiz_outcomes.get(&listed.address)?;
+                let never_taken = outcomes.never_taken_branch()?;
+                Some(SkizBranchCoverage {
+                    address: listed.address,
+                    line: listed.line,
+                    label: listed.label,
+                    outcomes,
+                    never_taken,
+                })
+            })
+            .collect()
+    }
+
+    pub fn report(&self) -> CoverageReport {
+        let instructions = self.instructions();
+        let num_executed_instructions = instructions
+            .iter()
+            .filter(|instruction| instruction.execution_count > 0)
+            .count();
+        CoverageReport {
+            num_runs: self.num_runs,
+            num_instructions: instructions.len(),
+            num_executed_instructions,
+            instructions,
+            basic_blocks: self.basic_blocks(),
+            labels: self.labels(),
+            never_taken_skiz_branches: self.never_taken_skiz_branches(),
+        }
+    }
+
+    /// The [coverage report](Self::report) as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.report()).unwrap()
+    }
+
+    /// The coverage in the [lcov] tracefile format. Since programs do not know the file they were
+    /// parsed from, the name of the source file has to be supplied.
+    ///
+    /// Line numbers only refer to the source file if the coverage was collected using
+    /// [`from_source`](Self::from_source). Otherwise, they refer to the program's listing, and
+    /// the given file should contain that listing.
+    ///
+    /// [lcov]: https://github.com/linux-test-project/lcov
+    pub fn to_lcov(&self, source_file: &str) -> String {
+        let mut lcov = String::new();
+        writeln!(lcov, "TN:").unwrap();
+        writeln!(lcov, "SF:{source_file}").unwrap();
+
+        let labels = self.labels();
+        for label in &labels {
+            writeln!(lcov, "FN:{},{}", label.line, label.label).unwrap();
+        }
+        for label in &labels {
+            writeln!(lcov, "FNDA:{},{}", label.execution_count, label.label).unwrap();
+        }
+        let num_hit_labels = labels.iter().filter(|l| l.execution_count > 0).count();
+        writeln!(lcov, "FNF:{}", labels.len()).unwrap();
+        writeln!(lcov, "FNH:{num_hit_labels}").unwrap();
+
+        let instructions = self.listed_instructions();
+        let skiz_instructions = instructions
+            .iter()
+            .filter(|listed| listed.instruction == AnInstruction::Skiz);
+        let mut num_branches = 0;
+        let mut num_hit_branches = 0;
+        for listed in skiz_instructions {
+            let was_executed = self.instruction_multiplicities[listed.address] > 0;
+            let outcomes = self.skiz_outcomes.get(&listed.address);
+            let branch_counts = [
+                outcomes.map(|o| o.num_executed_next),
+                outcomes.map(|o| o.num_skipped_next),
+            ];
+            for (branch_index, count) in branch_counts.into_iter().enumerate() {
+                let count = match (was_executed, count) {
+                    (false, _) => "-".to_string(),
+                    (true, count) => count.unwrap_or_default().to_string(),
+                };
+                let (line, block) = (listed.line, listed.address);
+                writeln!(lcov, "BRDA:{line},{block},{branch_index},{count}").unwrap();
+                num_branches += 1;
+                if count != "-" && count != "0" {
+                    num_hit_branches += 1;
+                }
+            }
+        }
+        writeln!(lcov, "BRF:{num_branches}").unwrap();
+        writeln!(lcov, "BRH:{num_hit_branches}").unwrap();
+
+        // Several instructions can share a line. Such a line counts as executed as often as the
+        // most executed instruction on it.
+        let line_execution_counts = instructions
+            .iter()
+            .map(|listed| (listed.line, self.instruction_multiplicities[listed.address]))
+            .into_grouping_map()
+            .max();
+        let line_execution_counts = line_execution_counts.into_iter().sorted().collect_vec();
+        let mut num_hit_lines = 0;
+        for &(line, execution_count) in &line_execution_counts {
+            writeln!(lcov, "DA:{line},{execution_count}").unwrap();
+            if execution_count > 0 {
+                num_hit_lines += 1;
+            }
+        }
+        writeln!(lcov, "LF:{}", line_execution_counts.len()).unwrap();
+        writeln!(lcov, "LH:{num_hit_lines}").unwrap();
+        writeln!(lcov, "end_of_record").unwrap();
+        lcov
+    }
+}
+
+/// The 1-based line of the given source code on which the given token starts. The token must be a
+/// sub-slice of the source code.
+fn line_number(source_code: &str, token: &str) -> usize {
+    let offset = token.as_ptr() as usize - source_code.as_ptr() as usize;
+    source_code[..offset].matches('\n').count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::assert;
+    use assert2::let_assert;
+
+    use crate::triton_program;
+
+    use super::*;
+
+    fn program_with_partial_coverage() -> Program {
+        triton_program! {
+            read_io 1
+            dup 0 skiz call positive
+            push 1 skiz call always
+            halt
+
+            positive:
+                pop 1 return
+            always:
+                return
+            never_called:
+                push 42 pop 1 return
+        }
+    }
+
+    fn coverage_for_inputs(inputs: &[u64]) -> Coverage {
+        let program = program_with_partial_coverage();
+        let mut coverage = Coverage::new(program.clone());
+        for &input in inputs {
+            let (aet, _) = program
+                .trace_execution(vec![input].into(), [].into())
+                .unwrap();
+            coverage.add_trace(&aet);
+        }
+        coverage
+    }
+
+    #[test]
+    fn never_executed_instructions_are_reported() {
+        let coverage = coverage_for_inputs(&[1]);
+        let never_executed = coverage.never_executed_instructions();
+        let never_executed_labels = never_executed
+            .iter()
+            .map(|instruction| instruction.label.as_deref())
+            .collect_vec();
+        assert!(never_executed.len() == 3);
+        assert!(never_executed_labels == vec![Some("never_called"); 3]);
+    }
+
+    #[test]
+    fn never_taken_skiz_branches_are_reported() {
+        let coverage = coverage_for_inputs(&[0]);
+        let never_taken = coverage.never_taken_skiz_branches();
+        let never_taken = never_taken.iter().map(|s| s.never_taken).collect_vec();
+        assert!(never_taken == vec![SkizBranch::ExecuteNext, SkizBranch::SkipNext]);
+
+        let coverage = coverage_for_inputs(&[0, 1]);
+        let never_taken = coverage.never_taken_skiz_branches();
+        let never_taken = never_taken.iter().map(|s| s.never_taken).collect_vec();
+        assert!(never_taken == vec![SkizBranch::SkipNext]);
+    }
+
+    #[test]
+    fn coverage_of_multiple_runs_is_sum_of_individual_runs() {
+        let mut coverage = coverage_for_inputs(&[0]);
+        coverage.merge(&coverage_for_inputs(&[1, 1]));
+        let combined_coverage = coverage_for_inputs(&[0, 1, 1]);
+        assert!(3 == coverage.num_runs());
+        assert!(combined_coverage == coverage);
+    }
+
+    #[test]
+    fn label_coverage_counts_executed_instructions() {
+        let coverage = coverage_for_inputs(&[0]);
+        let labels = coverage.labels();
+        let label_names = labels.iter().map(|l| l.label.as_str()).collect_vec();
+        assert!(vec!["positive", "always", "never_called"] == label_names);
+
+        let execution_counts = labels.iter().map(|l| l.execution_count).collect_vec();
+        assert!(vec![0, 1, 0] == execution_counts);
+    }
+
+    #[test]
+    fn basic_blocks_start_after_control_flow() {
+        let coverage = coverage_for_inputs(&[1]);
+        let basic_blocks = coverage.basic_blocks();
+        let first_block = &basic_blocks[0];
+        assert!(0 == first_block.start_address);
+        assert!(first_block.end_line == 3);
+
+        let execution_counts = basic_blocks.iter().map(|b| b.execution_count);
+        let execution_counts = execution_counts.collect_vec();
+        assert!(vec![1, 1, 1, 1, 1, 1, 1, 0] == execution_counts);
+    }
+
+    #[test]
+    fn lcov_export_contains_all_sections() {
+        let lcov = coverage_for_inputs(&[1]).to_lcov("test.tasm");
+        assert!(lcov.starts_with("TN:\nSF:test.tasm\n"));
+        assert!(lcov.contains("FNDA:0,never_called"));
+        assert!(lcov.contains("BRF:4"));
+        assert!(lcov.contains("LF:14"));
+        assert!(lcov.contains("LH:11"));
+        assert!(lcov.ends_with("end_of_record\n"));
+    }
+
+    #[test]
+    fn lines_of_coverage_from_source_refer_to_source_code() {
+        let source_code = "
+            // comment
+            push 1 skiz
+            call foo halt
+
+            foo: // called once
+                return
+            bar:
+                push 2 pop 1 return
+        ";
+        let mut coverage = Coverage::from_source(source_code).unwrap();
+        let program = coverage.program.clone();
+        let (aet, _) = program.trace_execution([].into(), [].into()).unwrap();
+        coverage.add_trace(&aet);
+
+        let lines = coverage.instructions().iter().map(|i| i.line).collect_vec();
+        assert!(vec![3, 3, 4, 4, 7, 9, 9, 9] == lines);
+
+        let lcov = coverage.to_lcov("test.tasm");
+        assert!(lcov.contains("FN:7,foo"));
+        assert!(lcov.contains("DA:3,1\nDA:4,1\nDA:7,1\nDA:9,0\nLF:4\nLH:3\n"));
+    }
+
+    #[test]
+    fn json_export_is_valid_json() {
+        let json = coverage_for_inputs(&[1]).to_json();
+        let_assert!(Ok(value) = serde_json::from_str::<serde_json::Value>(&json));
+        assert!(value["num_instructions"] == 14);
+        assert!(value["num_executed_instructions"] == 11);
+    }
+}
@@ -5,8 +5,11 @@ use std::num::TryFromIntError;
 
 use thiserror::Error;
 use twenty_first::shared_math::bfield_codec::BFieldCodec;
+use twenty_first::shared_math::digest::Digest;
 use twenty_first::shared_math::digest::DIGEST_LENGTH;
+use twenty_first::shared_math::x_field_element::XFieldElement;
 
+use crate::checkpoint::CheckpointKind;
 use crate::instruction::Instruction;
 use crate::proof_item::ProofItem;
 use crate::proof_stream::ProofStream;
@@ -114,6 +117,21 @@ pub enum ProofStreamError {
 
     #[error("decoding error: {0}")]
     DecodingError(#[from] <ProofStream<StarkHasher> as BFieldCodec>::Error),
+
+    #[error("proof item decoding error: {0}")]
+    ItemDecodingError(#[from] <ProofItem as BFieldCodec>::Error),
+
+    #[error("failed to read proof: {0}")]
+    ReadError(#[from] std::io::Error),
+
+    #[error("proof item of length {0} exceeds the maximum length of {1}")]
+    ItemTooLong(usize, usize),
+
+    #[error("proof of length {0} cannot hold {1} items")]
+    InconsistentProofLength(u64, u64),
+
+    #[error("proof contains non-canonical field element {0}")]
+    NonCanonicalFieldElement(u64),
 }
 
 #[non_exhaustive]
@@ -125,6 +143,9 @@ pub enum FriValidationError {
     #[error("Merkle tree authentication failed")]
     BadMerkleAuthenticationPath,
 
+    #[error("revealed codeword does not agree with folded codeword of previous round")]
+    FoldedCodewordMismatch,
+
     #[error("computed and received codeword of last round do not match")]
     LastCodewordMismatch,
 
@@ -134,8 +155,126 @@ pub enum FriValidationError {
     #[error("received codeword of last round does not correspond to its commitment")]
     BadMerkleRootForLastCodeword,
 
+    #[error("proof-of-work is insufficient")]
+    InsufficientProofOfWork,
+
     #[error("proof stream error: {0}")]
     ProofStreamError(#[from] ProofStreamError),
+
+    /// Only returned if [verbose errors](crate::stark::VerifierConfig::verbose_errors) are enabled.
+    #[error("{source} ({context})")]
+    WithContext {
+        source: Box<FriValidationError>,
+        context: FriValidationErrorContext,
+    },
+}
+
+impl FriValidationError {
+    pub(crate) fn with_context(self, context: FriValidationErrorContext) -> Self {
+        let source = Box::new(self);
+        Self::WithContext { source, context }
+    }
+}
+
+/// Details about a failed [FRI validation](FriValidationError). Values “received” are taken
+/// from the proof, values “computed” are derived by the verifier.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FriValidationErrorContext {
+    RevealedLeaves {
+        round: usize,
+        expected: usize,
+        received: usize,
+    },
+    MerkleAuthentication {
+        round: usize,
+        merkle_root: Digest,
+        leaf_indices: Vec<usize>,
+    },
+    FoldedCodeword {
+        round: usize,
+        query: usize,
+        index: usize,
+        received: XFieldElement,
+        computed: XFieldElement,
+    },
+    LastCodeword {
+        query: usize,
+        index: usize,
+        received: XFieldElement,
+        computed: XFieldElement,
+    },
+    LastCodewordMerkleRoot {
+        received: Digest,
+        computed: Digest,
+    },
+    LastRoundPolynomialDegree {
+        max_degree: usize,
+        degree: isize,
+    },
+    ProofOfWork {
+        nonce: u64,
+        num_grinding_bits: usize,
+    },
+}
+
+impl Display for FriValidationErrorContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        use FriValidationErrorContext::*;
+        match self {
+            RevealedLeaves {
+                round,
+                expected,
+                received,
+            } => write!(
+                f,
+                "round {round}: expected {expected} revealed leaves, received {received}"
+            ),
+            MerkleAuthentication {
+                round,
+                merkle_root,
+                leaf_indices,
+            } => write!(
+                f,
+                "round {round}: Merkle root {merkle_root}, leaf indices {leaf_indices:?}"
+            ),
+            FoldedCodeword {
+                round,
+                query,
+                index,
+                received,
+                computed,
+            } => write!(
+                f,
+                "round {round}, query {query}, index {index}: \
+                received {received}, computed {computed}"
+            ),
+            LastCodeword {
+                query,
+                index,
+                received,
+                computed,
+            } => write!(
+                f,
+                "last round, query {query}, index {index}: received {received}, computed {computed}"
+            ),
+            LastCodewordMerkleRoot { received, computed } => write!(
+                f,
+                "last round: received Merkle root {received}, computed Merkle root {computed}"
+            ),
+            LastRoundPolynomialDegree { max_degree, degree } => write!(
+                f,
+                "last round: polynomial has degree {degree}, but maximal degree is {max_degree}"
+            ),
+            ProofOfWork {
+                nonce,
+                num_grinding_bits,
+            } => write!(
+                f,
+                "nonce {nonce} does not have {num_grinding_bits} bits of proof-of-work"
+            ),
+        }
+    }
 }
 
 #[non_exhaustive]
@@ -180,7 +319,7 @@ pub enum CanonicalRepresentationError {
 }
 
 #[non_exhaustive]
-#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[derive(Debug, Error)]
 pub enum ProvingError {
     #[error("claimed program digest does not match actual program digest")]
     ProgramDigestMismatch,
@@ -190,6 +329,21 @@ pub enum ProvingError {
 
     #[error("error while running Triton VM: {0}")]
     VMError(#[from] VMError),
+
+    #[error("proof generation was cancelled")]
+    Cancelled,
+
+    #[error("number of chunks must be a power of 2, but is {0}")]
+    InvalidNumberOfChunks(usize),
+
+    #[error("cannot use scratch directory for master tables: {0}")]
+    ScratchDirectoryError(#[from] std::io::Error),
+
+    #[error("failed to prove instance {index} of the batch: {source}")]
+    BatchInstanceFailure {
+        index: usize,
+        source: Box<ProvingError>,
+    },
 }
 
 #[non_exhaustive]
@@ -225,11 +379,170 @@ pub enum VerificationError {
     #[error("the number of received extension table rows does not match the parameters")]
     IncorrectNumberOfExtTableRows,
 
+    #[error("the proof's zero-knowledge mode does not match the parameters")]
+    ZeroKnowledgeMismatch,
+
+    #[error("the proof's padded height does not match the expected padded height")]
+    PaddedHeightMismatch,
+
+    #[error("the proof was generated for a different version of the AIR")]
+    AirVersionMismatch,
+
     #[error("proof stream error: {0}")]
     ProofStreamError(#[from] ProofStreamError),
 
     #[error("FRI validation error: {0}")]
     FriValidationError(#[from] FriValidationError),
+
+    #[error("proof {index} of the batch is invalid: {source}")]
+    BatchProofFailure {
+        index: usize,
+        source: Box<VerificationError>,
+    },
+
+    /// Only returned if [verbose errors](crate::stark::VerifierConfig::verbose_errors) are enabled.
+    #[error("{source} ({context})")]
+    WithContext {
+        source: Box<VerificationError>,
+        context: VerificationErrorContext,
+    },
+}
+
+impl VerificationError {
+    pub(crate) fn with_context(self, context: VerificationErrorContext) -> Self {
+        let source = Box::new(self);
+        Self::WithContext { source, context }
+    }
+}
+
+/// Details about a failed [verification](VerificationError). Values “received” are taken from
+/// the proof, values “computed” are derived by the verifier.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerificationErrorContext {
+    ZeroKnowledge {
+        expected: bool,
+        received: bool,
+    },
+    NumberOfElements {
+        expected: usize,
+        received: usize,
+    },
+    OutOfDomainQuotientValue {
+        received: XFieldElement,
+        computed: XFieldElement,
+    },
+    MerkleAuthentication {
+        merkle_root: Digest,
+        row_indices: Vec<usize>,
+    },
+    CombinationCodeword {
+        query: usize,
+        row_index: usize,
+        received: XFieldElement,
+        computed: XFieldElement,
+    },
+}
+
+impl Display for VerificationErrorContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        use VerificationErrorContext::*;
+        match self {
+            ZeroKnowledge { expected, received } => {
+                write!(f, "expected zero-knowledge {expected}, received {received}")
+            }
+            NumberOfElements { expected, received } => {
+                write!(f, "expected {expected} elements, received {received}")
+            }
+            OutOfDomainQuotientValue { received, computed } => {
+                write!(f, "received {received}, computed {computed}")
+            }
+            MerkleAuthentication {
+                merkle_root,
+                row_indices,
+            } => write!(f, "Merkle root {merkle_root}, row indices {row_indices:?}"),
+            CombinationCodeword {
+                query,
+                row_index,
+                received,
+                computed,
+            } => write!(
+                f,
+                "query {query}, row {row_index}: received {received}, computed {computed}"
+            ),
+        }
+    }
+}
+
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum CheckpointError {
+    #[error("checkpoint is too short to contain a header")]
+    TooShort,
+
+    #[error("checkpoint does not start with the expected magic bytes")]
+    BadMagic,
+
+    #[error("checkpoint format version {0} is not supported")]
+    UnsupportedVersion(u16),
+
+    #[error("checkpoint is of unknown kind {0}")]
+    UnknownKind(u8),
+
+    #[error("expected checkpoint of kind {0:?}, but got {1:?}")]
+    UnexpectedKind(CheckpointKind, CheckpointKind),
+
+    #[error("checkpointed trace belongs to a different program")]
+    ProgramDigestMismatch,
+
+    #[error("checkpointed trace has inconsistent dimensions")]
+    InconsistentTraceDimensions,
+
+    #[error("decoding error: {0}")]
+    DecodingError(#[from] bincode::Error),
+}
+
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum ProofBundleError {
+    #[error("proof bundle is too short to contain a header")]
+    TooShort,
+
+    #[error("proof bundle does not start with the expected magic bytes")]
+    BadMagic,
+
+    #[error("proof bundle format version {0} is not supported")]
+    UnsupportedVersion(u16),
+
+    #[error("proof bundle's AIR version is not a valid digest")]
+    NonCanonicalAirVersion,
+
+    #[error(
+        "proof bundle was generated for AIR version {found}, but this is AIR version {expected}"
+    )]
+    AirVersionMismatch { expected: Digest, found: Digest },
+
+    #[error("decoding error: {0}")]
+    DecodingError(#[from] bincode::Error),
+
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+}
+
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum JournalError {
+    #[error("the execution journal is not enabled")]
+    JournalDisabled,
+
+    #[error("the execution journal contains no step to undo")]
+    NoStepToUndo,
+
+    #[error("cycle {0} precedes the first cycle recorded in the execution journal")]
+    CycleNotInJournal(u32),
+
+    #[error("instruction error: {0}")]
+    InstructionError(#[from] InstructionError),
 }
 
 #[non_exhaustive]
@@ -2,6 +2,7 @@ use std::marker::PhantomData;
 
 use itertools::Itertools;
 use num_traits::One;
+use num_traits::Zero;
 use rayon::iter::*;
 use twenty_first::shared_math::b_field_element::BFieldElement;
 use twenty_first::shared_math::other::log_2_ceil;
@@ -17,31 +18,45 @@ use twenty_first::util_types::merkle_tree_maker::MerkleTreeMaker;
 use crate::arithmetic_domain::ArithmeticDomain;
 use crate::error::FriValidationError;
 use crate::error::FriValidationError::*;
+use crate::error::FriValidationErrorContext;
+use crate::error::ProvingError;
 use crate::profiler::prof_start;
 use crate::profiler::prof_stop;
 use crate::profiler::TritonProfiler;
+use crate::progress::ProvingHandle;
 use crate::proof_item::FriResponse;
 use crate::proof_item::ProofItem;
 use crate::proof_stream::ProofStream;
+use crate::proof_stream::VerifierProofStream;
 use crate::stark::MTMaker;
+use crate::stark::VerifierConfig;
 
 type Result<T> = core::result::Result<T, FriValidationError>;
 pub type AuthenticationStructure = Vec<Digest>;
 
+/// The FRI low-degree test. In every round, the codeword is folded by the `folding_factor`: the
+/// values on each coset of the `folding_factor`-th roots of unity are combined into a single
+/// value of the next round's codeword. The Merkle tree of a round that gets folded has one leaf
+/// per such coset, such that one authentication path opens all values needed for one folding.
 #[derive(Debug, Clone, Copy)]
 pub struct Fri<H: AlgebraicHasher> {
     pub expansion_factor: usize,
+    pub folding_factor: usize,
     pub num_collinearity_checks: usize,
+    pub num_grinding_bits: usize,
     pub domain: ArithmeticDomain,
     _hasher: PhantomData<H>,
 }
 
 struct FriProver<'stream, H: AlgebraicHasher> {
     proof_stream: &'stream mut ProofStream<H>,
+    handle: &'stream ProvingHandle,
     rounds: Vec<ProverRound<H>>,
     first_round_domain: ArithmeticDomain,
     num_rounds: usize,
+    folding_factor: usize,
     num_collinearity_checks: usize,
+    num_grinding_bits: usize,
     first_round_collinearity_check_indices: Vec<usize>,
 }
 
@@ -52,16 +67,19 @@ struct ProverRound<H: AlgebraicHasher> {
 }
 
 impl<'stream, H: AlgebraicHasher> FriProver<'stream, H> {
-    fn commit(&mut self, codeword: &[XFieldElement]) {
+    fn commit(&mut self, codeword: &[XFieldElement]) -> std::result::Result<(), ProvingError> {
+        self.handle.enter_fri_round(0, self.num_rounds)?;
         self.commit_to_first_round(codeword);
-        for _ in 0..self.num_rounds {
+        for round_number in 1..=self.num_rounds {
+            self.handle.enter_fri_round(round_number, self.num_rounds)?;
             self.commit_to_next_round();
         }
         self.send_last_codeword();
+        Ok(())
     }
 
     fn commit_to_first_round(&mut self, codeword: &[XFieldElement]) {
-        let first_round = ProverRound::new(self.first_round_domain, codeword);
+        let first_round = self.new_round(self.first_round_domain, codeword);
         self.commit_to_round(&first_round);
         self.store_round(first_round);
     }
@@ -85,9 +103,19 @@ impl<'stream, H: AlgebraicHasher> FriProver<'stream, H> {
     fn construct_next_round(&mut self) -> ProverRound<H> {
         let previous_round = self.rounds.last().unwrap();
         let folding_challenge = self.proof_stream.sample_scalars(1)[0];
-        let codeword = previous_round.split_and_fold(folding_challenge);
-        let domain = previous_round.domain.halve();
-        ProverRound::new(domain, &codeword)
+        let codeword = previous_round.split_and_fold(self.folding_factor, folding_challenge);
+        let domain = fold_domain(previous_round.domain, self.folding_factor);
+        self.new_round(domain, &codeword)
+    }
+
+    /// Rounds that get folded commit to cosets, the last round commits to individual elements.
+    fn new_round(&self, domain: ArithmeticDomain, codeword: &[XFieldElement]) -> ProverRound<H> {
+        let round_number = self.rounds.len();
+        let digests = match round_number < self.num_rounds {
+            true => cosets_as_digests::<H>(codeword, self.folding_factor),
+            false => codeword_as_digests(codeword),
+        };
+        ProverRound::new(domain, codeword, MTMaker::from_digests(&digests))
     }
 
     fn send_last_codeword(&mut self) {
@@ -97,15 +125,17 @@ impl<'stream, H: AlgebraicHasher> FriProver<'stream, H> {
     }
 
     fn query(&mut self) {
+        if self.num_grinding_bits > 0 {
+            self.proof_stream.grind(self.num_grinding_bits);
+        }
         self.sample_first_round_collinearity_check_indices();
 
-        let initial_a_indices = self.first_round_collinearity_check_indices.clone();
-        self.authentically_reveal_codeword_of_round_at_indices(0, &initial_a_indices);
-
-        let num_rounds_that_have_a_next_round = self.rounds.len() - 1;
-        for round_number in 0..num_rounds_that_have_a_next_round {
-            let b_indices = self.collinearity_check_b_indices_for_round(round_number);
-            self.authentically_reveal_codeword_of_round_at_indices(round_number, &b_indices);
+        if self.num_rounds == 0 {
+            let initial_a_indices = self.first_round_collinearity_check_indices.clone();
+            self.authentically_reveal_codeword_of_round_at_indices(0, &initial_a_indices);
+        }
+        for round_number in 0..self.num_rounds {
+            self.authentically_reveal_cosets_of_round(round_number);
         }
     }
 
@@ -118,15 +148,24 @@ impl<'stream, H: AlgebraicHasher> FriProver<'stream, H> {
 
     fn all_top_level_collinearity_check_indices(&self) -> Vec<usize> {
         let a_indices = self.first_round_collinearity_check_indices.clone();
-        let b_indices = self.collinearity_check_b_indices_for_round(0);
-        a_indices.into_iter().chain(b_indices).collect()
+        if self.num_rounds == 0 {
+            return a_indices;
+        }
+
+        let num_cosets = self.first_round_domain.length / self.folding_factor;
+        a_indices
+            .into_iter()
+            .flat_map(|a_index| {
+                coset_indices(a_index % num_cosets, num_cosets, self.folding_factor)
+            })
+            .collect()
     }
 
-    fn collinearity_check_b_indices_for_round(&self, round_number: usize) -> Vec<usize> {
-        let domain_length = self.rounds[round_number].domain.length;
+    fn coset_indices_for_round(&self, round_number: usize) -> Vec<usize> {
+        let num_cosets = self.rounds[round_number].domain.length / self.folding_factor;
         self.first_round_collinearity_check_indices
             .iter()
-            .map(|&a_index| (a_index + domain_length / 2) % domain_length)
+            .map(|&a_index| a_index % num_cosets)
             .collect()
     }
 
@@ -148,63 +187,101 @@ impl<'stream, H: AlgebraicHasher> FriProver<'stream, H> {
         let proof_item = ProofItem::FriResponse(fri_response);
         self.proof_stream.enqueue(proof_item)
     }
+
+    fn authentically_reveal_cosets_of_round(&mut self, round_number: usize) {
+        let coset_indices_for_round = self.coset_indices_for_round(round_number);
+        let round = &self.rounds[round_number];
+        let num_cosets = round.domain.length / self.folding_factor;
+        let revealed_leaves = coset_indices_for_round
+            .iter()
+            .flat_map(|&coset_index| coset_indices(coset_index, num_cosets, self.folding_factor))
+            .map(|i| round.codeword[i])
+            .collect_vec();
+
+        let auth_structure = round
+            .merkle_tree
+            .get_authentication_structure(&coset_indices_for_round);
+
+        let fri_response = FriResponse {
+            auth_structure,
+            revealed_leaves,
+        };
+        let proof_item = ProofItem::FriResponse(fri_response);
+        self.proof_stream.enqueue(proof_item)
+    }
 }
 
 impl<H: AlgebraicHasher> ProverRound<H> {
-    fn new(domain: ArithmeticDomain, codeword: &[XFieldElement]) -> Self {
+    fn new(
+        domain: ArithmeticDomain,
+        codeword: &[XFieldElement],
+        merkle_tree: MerkleTree<H>,
+    ) -> Self {
         debug_assert_eq!(domain.length, codeword.len());
         Self {
             domain,
             codeword: codeword.to_vec(),
-            merkle_tree: Self::merkle_tree_from_codeword(codeword),
+            merkle_tree,
         }
     }
 
-    fn merkle_tree_from_codeword(codeword: &[XFieldElement]) -> MerkleTree<H> {
-        let digests = codeword_as_digests(codeword);
-        MTMaker::from_digests(&digests)
-    }
-
-    fn split_and_fold(&self, folding_challenge: XFieldElement) -> Vec<XFieldElement> {
-        let one = XFieldElement::one();
-        let two_inverse = XFieldElement::from(2).inverse();
-
+    fn split_and_fold(
+        &self,
+        folding_factor: usize,
+        folding_challenge: XFieldElement,
+    ) -> Vec<XFieldElement> {
         let domain_points = self.domain.domain_values();
         let domain_point_inverses = BFieldElement::batch_inversion(domain_points);
 
-        let n = self.codeword.len();
-        (0..n / 2)
+        let num_cosets = self.codeword.len() / folding_factor;
+        (0..num_cosets)
             .into_par_iter()
-            .map(|i| {
-                let scaled_offset_inv = folding_challenge * domain_point_inverses[i];
-                let left_summand = (one + scaled_offset_inv) * self.codeword[i];
-                let right_summand = (one - scaled_offset_inv) * self.codeword[n / 2 + i];
-                (left_summand + right_summand) * two_inverse
+            .map(|coset_index| {
+                let coset = coset_indices(coset_index, num_cosets, folding_factor)
+                    .map(|i| (self.codeword[i], domain_point_inverses[i]));
+                fold_coset(coset, folding_factor, folding_challenge)
             })
             .collect()
     }
 }
 
-struct FriVerifier<'stream, H: AlgebraicHasher> {
-    proof_stream: &'stream mut ProofStream<H>,
+struct FriVerifier<'stream, H: AlgebraicHasher, S: VerifierProofStream> {
+    proof_stream: &'stream mut S,
     rounds: Vec<VerifierRound>,
     first_round_domain: ArithmeticDomain,
     last_round_codeword: Vec<XFieldElement>,
     last_round_max_degree: usize,
     num_rounds: usize,
+    folding_factor: usize,
     num_collinearity_checks: usize,
+    num_grinding_bits: usize,
     first_round_collinearity_check_indices: Vec<usize>,
+    verbose_errors: bool,
+    _hasher: PhantomData<H>,
 }
 
 struct VerifierRound {
     domain: ArithmeticDomain,
     partial_codeword_a: Vec<XFieldElement>,
-    partial_codeword_b: Vec<XFieldElement>,
+    revealed_cosets: Vec<Vec<XFieldElement>>,
     merkle_root: Digest,
     folding_challenge: Option<XFieldElement>,
 }
 
-impl<'stream, H: AlgebraicHasher> FriVerifier<'stream, H> {
+impl<'stream, H: AlgebraicHasher, S: VerifierProofStream> FriVerifier<'stream, H, S> {
+    /// Fail with the given error, attaching context if
+    /// [verbose errors](VerifierConfig::verbose_errors) are enabled.
+    fn fail<T>(
+        &self,
+        error: FriValidationError,
+        context: impl FnOnce() -> FriValidationErrorContext,
+    ) -> Result<T> {
+        match self.verbose_errors {
+            true => Err(error.with_context(context())),
+            false => Err(error),
+        }
+    }
+
     fn initialize(&mut self) -> Result<()> {
         self.initialize_verification_rounds()?;
         self.receive_last_round_codeword()
@@ -237,7 +314,7 @@ impl<'stream, H: AlgebraicHasher> FriVerifier<'stream, H> {
 
     fn construct_next_round(&mut self) -> Result<VerifierRound> {
         let previous_round = self.rounds.last().unwrap();
-        let domain = previous_round.domain.halve();
+        let domain = fold_domain(previous_round.domain, self.folding_factor);
         self.construct_round_with_domain(domain)
     }
 
@@ -248,7 +325,7 @@ impl<'stream, H: AlgebraicHasher> FriVerifier<'stream, H> {
         let verifier_round = VerifierRound {
             domain,
             partial_codeword_a: vec![],
-            partial_codeword_b: vec![],
+            revealed_cosets: vec![],
             merkle_root,
             folding_challenge,
         };
@@ -282,10 +359,31 @@ impl<'stream, H: AlgebraicHasher> FriVerifier<'stream, H> {
     }
 
     fn compute_last_round_folded_partial_codeword(&mut self) -> Result<()> {
+        self.receive_proof_of_work()?;
         self.sample_first_round_collinearity_check_indices();
         self.receive_authentic_partially_revealed_codewords()?;
-        self.successively_fold_partial_codeword_of_each_round();
-        Ok(())
+        self.successively_fold_partial_codeword_of_each_round()
+    }
+
+    fn receive_proof_of_work(&mut self) -> Result<()> {
+        if self.num_grinding_bits == 0 {
+            return Ok(());
+        }
+
+        let nonce = self.proof_stream.dequeue()?.as_proof_of_work()?;
+        let num_grinding_bits = self.num_grinding_bits;
+        match self
+            .proof_stream
+            .has_sufficient_proof_of_work(num_grinding_bits)
+        {
+            true => Ok(()),
+            false => self.fail(InsufficientProofOfWork, || {
+                FriValidationErrorContext::ProofOfWork {
+                    nonce,
+                    num_grinding_bits,
+                }
+            }),
+        }
     }
 
     fn sample_first_round_collinearity_check_indices(&mut self) {
@@ -296,13 +394,13 @@ impl<'stream, H: AlgebraicHasher> FriVerifier<'stream, H> {
     }
 
     fn receive_authentic_partially_revealed_codewords(&mut self) -> Result<()> {
-        let auth_structure = self.receive_partial_codeword_a_for_first_round()?;
-        self.authenticate_partial_codeword_a_for_first_round(&auth_structure)?;
-
-        let num_rounds_that_have_a_next_round = self.rounds.len() - 1;
-        for round_number in 0..num_rounds_that_have_a_next_round {
-            let auth_structure = self.receive_partial_codeword_b_for_round(round_number)?;
-            self.authenticate_partial_codeword_b_for_round(round_number, &auth_structure)?;
+        if self.num_rounds == 0 {
+            let auth_structure = self.receive_partial_codeword_a_for_first_round()?;
+            self.authenticate_partial_codeword_a_for_first_round(&auth_structure)?;
+        }
+        for round_number in 0..self.num_rounds {
+            let auth_structure = self.receive_cosets_for_round(round_number)?;
+            self.authenticate_cosets_for_round(round_number, &auth_structure)?;
         }
         Ok(())
     }
@@ -314,30 +412,46 @@ impl<'stream, H: AlgebraicHasher> FriVerifier<'stream, H> {
             revealed_leaves,
         } = fri_response;
 
-        self.assert_enough_leaves_were_received(&revealed_leaves)?;
+        self.assert_enough_leaves_were_received(0, &revealed_leaves, 1)?;
         self.rounds[0].partial_codeword_a = revealed_leaves;
         Ok(auth_structure)
     }
 
-    fn receive_partial_codeword_b_for_round(
-        &mut self,
-        round_number: usize,
-    ) -> Result<AuthenticationStructure> {
+    fn receive_cosets_for_round(&mut self, round_number: usize) -> Result<AuthenticationStructure> {
         let fri_response = self.proof_stream.dequeue()?.as_fri_response()?;
         let FriResponse {
             auth_structure,
             revealed_leaves,
         } = fri_response;
 
-        self.assert_enough_leaves_were_received(&revealed_leaves)?;
-        self.rounds[round_number].partial_codeword_b = revealed_leaves;
+        self.assert_enough_leaves_were_received(
+            round_number,
+            &revealed_leaves,
+            self.folding_factor,
+        )?;
+        self.rounds[round_number].revealed_cosets = revealed_leaves
+            .chunks(self.folding_factor)
+            .map(|coset| coset.to_vec())
+            .collect();
         Ok(auth_structure)
     }
 
-    fn assert_enough_leaves_were_received(&self, leaves: &[XFieldElement]) -> Result<()> {
-        match self.num_collinearity_checks == leaves.len() {
+    fn assert_enough_leaves_were_received(
+        &self,
+        round_number: usize,
+        leaves: &[XFieldElement],
+        num_leaves_per_check: usize,
+    ) -> Result<()> {
+        let expected = self.num_collinearity_checks * num_leaves_per_check;
+        match expected == leaves.len() {
             true => Ok(()),
-            false => Err(IncorrectNumberOfRevealedLeaves),
+            false => self.fail(IncorrectNumberOfRevealedLeaves, || {
+                FriValidationErrorContext::RevealedLeaves {
+                    round: round_number,
+                    expected,
+                    received: leaves.len(),
+                }
+            }),
         }
     }
 
@@ -361,22 +475,32 @@ impl<'stream, H: AlgebraicHasher> FriVerifier<'stream, H> {
             auth_structure,
         ) {
             true => Ok(()),
-            false => Err(BadMerkleAuthenticationPath),
+            false => self.fail(BadMerkleAuthenticationPath, || {
+                FriValidationErrorContext::MerkleAuthentication {
+                    round: 0,
+                    merkle_root: round.merkle_root,
+                    leaf_indices: indices.to_vec(),
+                }
+            }),
         }
     }
 
-    fn authenticate_partial_codeword_b_for_round(
+    fn authenticate_cosets_for_round(
         &self,
         round_number: usize,
         auth_structure: &AuthenticationStructure,
     ) -> Result<()> {
         let round = &self.rounds[round_number];
 
-        let revealed_leaves = &round.partial_codeword_b;
-        let revealed_digests = codeword_as_digests(revealed_leaves);
+        let revealed_cosets = &round.revealed_cosets;
+        let revealed_digests = revealed_cosets
+            .iter()
+            .map(|coset| coset_as_digest::<H>(coset))
+            .collect_vec();
 
-        let merkle_tree_height = round.domain.length.ilog2() as usize;
-        let indices = &self.collinearity_check_b_indices_for_round(round_number);
+        let num_cosets = round.domain.length / self.folding_factor;
+        let merkle_tree_height = num_cosets.ilog2() as usize;
+        let indices = &self.coset_indices_for_round(round_number);
 
         match MerkleTree::<H>::verify_authentication_structure(
             round.merkle_root,
@@ -386,50 +510,94 @@ impl<'stream, H: AlgebraicHasher> FriVerifier<'stream, H> {
             auth_structure,
         ) {
             true => Ok(()),
-            false => Err(BadMerkleAuthenticationPath),
+            false => self.fail(BadMerkleAuthenticationPath, || {
+                FriValidationErrorContext::MerkleAuthentication {
+                    round: round_number,
+                    merkle_root: round.merkle_root,
+                    leaf_indices: indices.to_vec(),
+                }
+            }),
         }
     }
 
-    fn successively_fold_partial_codeword_of_each_round(&mut self) {
-        let num_rounds_that_have_a_next_round = self.rounds.len() - 1;
-        for round_number in 0..num_rounds_that_have_a_next_round {
+    /// Folds the revealed cosets of each round. Except for the first round, the revealed cosets
+    /// must agree with the values obtained by folding the previous round.
+    fn successively_fold_partial_codeword_of_each_round(&mut self) -> Result<()> {
+        for round_number in 0..self.num_rounds {
+            let revealed_partial_codeword_a =
+                self.revealed_partial_codeword_a_of_round(round_number);
+            let round = &mut self.rounds[round_number];
+            if round_number == 0 {
+                round.partial_codeword_a = revealed_partial_codeword_a;
+            } else if round.partial_codeword_a != revealed_partial_codeword_a {
+                return self.fail(FoldedCodewordMismatch, || {
+                    let folded_partial_codeword_a = &self.rounds[round_number].partial_codeword_a;
+                    let query = position_of_first_difference(
+                        &revealed_partial_codeword_a,
+                        folded_partial_codeword_a,
+                    );
+                    let a_indices = self.collinearity_check_a_indices_for_round(round_number);
+                    FriValidationErrorContext::FoldedCodeword {
+                        round: round_number,
+                        query,
+                        index: a_indices[query],
+                        received: revealed_partial_codeword_a[query],
+                        computed: folded_partial_codeword_a[query],
+                    }
+                });
+            }
+
             let folded_partial_codeword = self.fold_partial_codeword_of_round(round_number);
             let next_round = &mut self.rounds[round_number + 1];
             next_round.partial_codeword_a = folded_partial_codeword;
         }
+        Ok(())
     }
 
-    fn fold_partial_codeword_of_round(&self, round_number: usize) -> Vec<XFieldElement> {
+    /// The values at the collinearity check's “a” indices, taken from the revealed cosets.
+    fn revealed_partial_codeword_a_of_round(&self, round_number: usize) -> Vec<XFieldElement> {
         let round = &self.rounds[round_number];
+        let num_cosets = round.domain.length / self.folding_factor;
         let a_indices = self.collinearity_check_a_indices_for_round(round_number);
-        let b_indices = self.collinearity_check_b_indices_for_round(round_number);
-        let partial_codeword_a = &round.partial_codeword_a;
-        let partial_codeword_b = &round.partial_codeword_b;
+        a_indices
+            .into_iter()
+            .zip_eq(&round.revealed_cosets)
+            .map(|(a_index, coset)| coset[a_index / num_cosets])
+            .collect()
+    }
+
+    fn fold_partial_codeword_of_round(&self, round_number: usize) -> Vec<XFieldElement> {
+        let round = &self.rounds[round_number];
+        let coset_indices_for_round = self.coset_indices_for_round(round_number);
+        let num_cosets = round.domain.length / self.folding_factor;
         let domain = round.domain;
+        let folding_factor = self.folding_factor;
         let folding_challenge = round.folding_challenge.unwrap();
 
-        (0..self.num_collinearity_checks)
+        coset_indices_for_round
             .into_par_iter()
-            .map(|i| {
-                let point_a_x = domain.domain_value(a_indices[i] as u32).lift();
-                let point_b_x = domain.domain_value(b_indices[i] as u32).lift();
-                let point_a = (point_a_x, partial_codeword_a[i]);
-                let point_b = (point_b_x, partial_codeword_b[i]);
-                Polynomial::get_colinear_y(point_a, point_b, folding_challenge)
+            .zip_eq(round.revealed_cosets.par_iter())
+            .map(|(coset_index, coset)| {
+                let coset_points = coset_indices(coset_index, num_cosets, folding_factor)
+                    .map(|i| domain.domain_value(i as u32))
+                    .collect();
+                let coset_point_inverses = BFieldElement::batch_inversion(coset_points);
+                let coset = coset.iter().copied().zip(coset_point_inverses);
+                fold_coset(coset, folding_factor, folding_challenge)
             })
             .collect()
     }
 
-    fn collinearity_check_a_indices_for_round(&self, round_number: usize) -> Vec<usize> {
+    fn coset_indices_for_round(&self, round_number: usize) -> Vec<usize> {
         let domain_length = self.rounds[round_number].domain.length;
-        let a_offset = 0;
-        self.collinearity_check_indices_with_offset_and_modulus(a_offset, domain_length)
+        let num_cosets = domain_length / self.folding_factor;
+        self.collinearity_check_indices_with_offset_and_modulus(0, num_cosets)
     }
 
-    fn collinearity_check_b_indices_for_round(&self, round_number: usize) -> Vec<usize> {
+    fn collinearity_check_a_indices_for_round(&self, round_number: usize) -> Vec<usize> {
         let domain_length = self.rounds[round_number].domain.length;
-        let b_offset = domain_length / 2;
-        self.collinearity_check_indices_with_offset_and_modulus(b_offset, domain_length)
+        let a_offset = 0;
+        self.collinearity_check_indices_with_offset_and_modulus(a_offset, domain_length)
     }
 
     fn collinearity_check_indices_with_offset_and_modulus(
@@ -450,9 +618,13 @@ impl<'stream, H: AlgebraicHasher> FriVerifier<'stream, H> {
     }
 
     fn assert_last_round_codeword_matches_last_round_commitment(&self) -> Result<()> {
-        match self.last_round_merkle_root() == self.last_round_codeword_merkle_root() {
+        let received = self.last_round_merkle_root();
+        let computed = self.last_round_codeword_merkle_root();
+        match received == computed {
             true => Ok(()),
-            false => Err(BadMerkleRootForLastCodeword),
+            false => self.fail(BadMerkleRootForLastCodeword, || {
+                FriValidationErrorContext::LastCodewordMerkleRoot { received, computed }
+            }),
         }
     }
 
@@ -471,7 +643,20 @@ impl<'stream, H: AlgebraicHasher> FriVerifier<'stream, H> {
         let partial_received_codeword = self.received_last_round_codeword_at_indices_a();
         match partial_received_codeword == partial_folded_codeword {
             true => Ok(()),
-            false => Err(LastCodewordMismatch),
+            false => self.fail(LastCodewordMismatch, || {
+                let query = position_of_first_difference(
+                    &partial_received_codeword,
+                    partial_folded_codeword,
+                );
+                let last_round_number = self.rounds.len() - 1;
+                let a_indices = self.collinearity_check_a_indices_for_round(last_round_number);
+                FriValidationErrorContext::LastCodeword {
+                    query,
+                    index: a_indices[query],
+                    received: partial_received_codeword[query],
+                    computed: partial_folded_codeword[query],
+                }
+            }),
         }
     }
 
@@ -489,8 +674,14 @@ impl<'stream, H: AlgebraicHasher> FriVerifier<'stream, H> {
     }
 
     fn assert_last_round_codeword_corresponds_to_low_degree_polynomial(&self) -> Result<()> {
-        if self.last_round_polynomial().degree() > self.last_round_max_degree as isize {
-            return Err(LastRoundPolynomialHasTooHighDegree);
+        let degree = self.last_round_polynomial().degree();
+        if degree > self.last_round_max_degree as isize {
+            return self.fail(LastRoundPolynomialHasTooHighDegree, || {
+                FriValidationErrorContext::LastRoundPolynomialDegree {
+                    max_degree: self.last_round_max_degree,
+                    degree,
+                }
+            });
         }
         Ok(())
     }
@@ -501,21 +692,21 @@ impl<'stream, H: AlgebraicHasher> FriVerifier<'stream, H> {
     }
 
     fn first_round_partially_revealed_codeword(&self) -> Vec<(usize, XFieldElement)> {
-        let partial_codeword_a = self.rounds[0].partial_codeword_a.clone();
-        let partial_codeword_b = self.rounds[0].partial_codeword_b.clone();
-
-        let indices_a = self.collinearity_check_a_indices_for_round(0).into_iter();
-
-        let first_round_codeword_b_has_been_revealed = self.num_rounds > 0;
-        let indices_b = match first_round_codeword_b_has_been_revealed {
-            true => self.collinearity_check_b_indices_for_round(0).into_iter(),
-            false => vec![].into_iter(),
-        };
-
-        let codeword_a = indices_a.zip_eq(partial_codeword_a);
-        let codeword_b = indices_b.zip_eq(partial_codeword_b);
+        if self.num_rounds == 0 {
+            let indices_a = self.collinearity_check_a_indices_for_round(0);
+            let partial_codeword_a = self.rounds[0].partial_codeword_a.clone();
+            return indices_a.into_iter().zip_eq(partial_codeword_a).collect();
+        }
 
-        codeword_a.chain(codeword_b).collect()
+        let num_cosets = self.first_round_domain.length / self.folding_factor;
+        let coset_indices_for_round = self.coset_indices_for_round(0);
+        coset_indices_for_round
+            .into_iter()
+            .zip_eq(&self.rounds[0].revealed_cosets)
+            .flat_map(|(coset_index, coset)| {
+                coset_indices(coset_index, num_cosets, self.folding_factor).zip_eq(coset.clone())
+            })
+            .collect()
     }
 }
 
@@ -532,32 +723,72 @@ impl<H: AlgebraicHasher> Fri<H> {
         Self {
             domain,
             expansion_factor,
+            folding_factor: 2,
             num_collinearity_checks,
+            num_grinding_bits: 0,
             _hasher: PhantomData,
         }
     }
 
+    /// Set the factor by which the codeword is folded in each round. Must be a power of 2 greater
+    /// than 1. The default is 2.
+    #[must_use]
+    pub fn with_folding_factor(mut self, folding_factor: usize) -> Self {
+        assert!(folding_factor > 1);
+        assert!(folding_factor.is_power_of_two());
+        self.folding_factor = folding_factor;
+        self
+    }
+
+    /// Set the number of bits of proof-of-work the prover has to perform before the indices of the
+    /// collinearity checks are sampled. The default is 0, _i.e._, no proof-of-work.
+    #[must_use]
+    pub fn with_num_grinding_bits(mut self, num_grinding_bits: usize) -> Self {
+        self.num_grinding_bits = num_grinding_bits;
+        self
+    }
+
     /// Create a FRI proof and return indices of revealed elements of round 0.
     pub fn prove(
         &self,
         codeword: &[XFieldElement],
         proof_stream: &mut ProofStream<H>,
     ) -> Vec<usize> {
-        let mut prover = self.prover(proof_stream);
+        let handle = ProvingHandle::new();
+        self.prove_with_handle(codeword, proof_stream, &handle)
+            .expect("proving without cancellation must succeed")
+    }
 
-        prover.commit(codeword);
+    /// Like [`prove`](Self::prove), but reports each round to the given handle and stops early
+    /// if the handle is [cancelled](ProvingHandle::cancel).
+    pub fn prove_with_handle(
+        &self,
+        codeword: &[XFieldElement],
+        proof_stream: &mut ProofStream<H>,
+        handle: &ProvingHandle,
+    ) -> std::result::Result<Vec<usize>, ProvingError> {
+        let mut prover = self.prover(proof_stream, handle);
+
+        prover.commit(codeword)?;
         prover.query();
 
-        prover.all_top_level_collinearity_check_indices()
+        Ok(prover.all_top_level_collinearity_check_indices())
     }
 
-    fn prover<'stream>(&'stream self, proof_stream: &'stream mut ProofStream<H>) -> FriProver<H> {
+    fn prover<'stream>(
+        &'stream self,
+        proof_stream: &'stream mut ProofStream<H>,
+        handle: &'stream ProvingHandle,
+    ) -> FriProver<H> {
         FriProver {
             proof_stream,
+            handle,
             rounds: vec![],
             first_round_domain: self.domain,
             num_rounds: self.num_rounds(),
+            folding_factor: self.folding_factor,
             num_collinearity_checks: self.num_collinearity_checks,
+            num_grinding_bits: self.num_grinding_bits,
             first_round_collinearity_check_indices: vec![],
         }
     }
@@ -566,11 +797,22 @@ impl<H: AlgebraicHasher> Fri<H> {
     /// Returns the indices and revealed elements of the codeword at the top level of the FRI proof.
     pub fn verify(
         &self,
-        proof_stream: &mut ProofStream<H>,
+        proof_stream: &mut impl VerifierProofStream,
+        maybe_profiler: &mut Option<TritonProfiler>,
+    ) -> Result<Vec<(usize, XFieldElement)>> {
+        let config = VerifierConfig::default();
+        self.verify_with_config(proof_stream, &config, maybe_profiler)
+    }
+
+    /// Like [`verify`](Self::verify), but uses the given verifier configuration.
+    pub fn verify_with_config(
+        &self,
+        proof_stream: &mut impl VerifierProofStream,
+        config: &VerifierConfig,
         maybe_profiler: &mut Option<TritonProfiler>,
     ) -> Result<Vec<(usize, XFieldElement)>> {
         prof_start!(maybe_profiler, "init");
-        let mut verifier = self.verifier(proof_stream);
+        let mut verifier = self.verifier(proof_stream, config);
         verifier.initialize()?;
         prof_stop!(maybe_profiler, "init");
 
@@ -585,10 +827,11 @@ impl<H: AlgebraicHasher> Fri<H> {
         Ok(verifier.first_round_partially_revealed_codeword())
     }
 
-    fn verifier<'stream>(
+    fn verifier<'stream, S: VerifierProofStream>(
         &'stream self,
-        proof_stream: &'stream mut ProofStream<H>,
-    ) -> FriVerifier<H> {
+        proof_stream: &'stream mut S,
+        config: &VerifierConfig,
+    ) -> FriVerifier<H, S> {
         FriVerifier {
             proof_stream,
             rounds: vec![],
@@ -596,25 +839,33 @@ impl<H: AlgebraicHasher> Fri<H> {
             last_round_codeword: vec![],
             last_round_max_degree: self.last_round_max_degree(),
             num_rounds: self.num_rounds(),
+            folding_factor: self.folding_factor,
             num_collinearity_checks: self.num_collinearity_checks,
+            num_grinding_bits: self.num_grinding_bits,
             first_round_collinearity_check_indices: vec![],
+            verbose_errors: config.verbose_errors,
+            _hasher: PhantomData,
         }
     }
 
     pub fn num_rounds(&self) -> usize {
         let first_round_code_dimension = self.first_round_max_degree() + 1;
-        let max_num_rounds = log_2_ceil(first_round_code_dimension as u128);
+        let log_2_of_first_round_code_dimension = log_2_ceil(first_round_code_dimension as u128);
 
         // Skip rounds for which Merkle tree verification cost exceeds arithmetic cost,
         // because more than half the codeword's locations are queried.
-        let num_rounds_checking_all_locations = self.num_collinearity_checks.ilog2() as u64;
-        let num_rounds_checking_most_locations = num_rounds_checking_all_locations + 1;
+        let log_2_of_num_collinearity_checks = self.num_collinearity_checks.ilog2() as u64;
+        let log_2_of_last_round_min_code_dimension = log_2_of_num_collinearity_checks + 1;
 
-        max_num_rounds.saturating_sub(num_rounds_checking_most_locations) as usize
+        let max_num_halvings = log_2_of_first_round_code_dimension
+            .saturating_sub(log_2_of_last_round_min_code_dimension);
+        let log_2_of_folding_factor = self.folding_factor.ilog2() as u64;
+        (max_num_halvings / log_2_of_folding_factor) as usize
     }
 
     pub fn last_round_max_degree(&self) -> usize {
-        self.first_round_max_degree() >> self.num_rounds()
+        let log_2_of_folding_factor = self.folding_factor.ilog2() as usize;
+        self.first_round_max_degree() >> (self.num_rounds() * log_2_of_folding_factor)
     }
 
     pub fn first_round_max_degree(&self) -> usize {
@@ -623,10 +874,81 @@ impl<H: AlgebraicHasher> Fri<H> {
     }
 }
 
+/// The domain of the next round, _i.e._, the `folding_factor`-th powers of the given domain.
+fn fold_domain(domain: ArithmeticDomain, folding_factor: usize) -> ArithmeticDomain {
+    let num_halvings = folding_factor.ilog2();
+    (0..num_halvings).fold(domain, |domain, _| domain.halve())
+}
+
+/// The indices of the coset with the given index in a codeword that is folded by the
+/// `folding_factor`. The codeword consists of `num_cosets` many such cosets.
+fn coset_indices(
+    coset_index: usize,
+    num_cosets: usize,
+    folding_factor: usize,
+) -> impl Iterator<Item = usize> {
+    (0..folding_factor).map(move |i| coset_index + i * num_cosets)
+}
+
+/// Fold the values of a codeword on one coset into a single value of the next round's codeword.
+/// Each value comes with the inverse of its domain point. The result is the evaluation of the
+/// polynomial of degree less than `folding_factor` that interpolates the coset in the folding
+/// challenge.
+fn fold_coset(
+    coset: impl IntoIterator<Item = (XFieldElement, BFieldElement)>,
+    folding_factor: usize,
+    folding_challenge: XFieldElement,
+) -> XFieldElement {
+    let one = XFieldElement::one();
+    let folding_factor_inverse = BFieldElement::new(folding_factor as u64).inverse();
+
+    let weighted_sum = coset
+        .into_iter()
+        .map(|(value, domain_point_inverse)| {
+            let scaled_challenge = folding_challenge * domain_point_inverse;
+            let weight = (0..folding_factor).fold(XFieldElement::zero(), |weight, _| {
+                weight * scaled_challenge + one
+            });
+            weight * value
+        })
+        .fold(XFieldElement::zero(), |sum, summand| sum + summand);
+    weighted_sum * folding_factor_inverse
+}
+
 fn codeword_as_digests(codeword: &[XFieldElement]) -> Vec<Digest> {
     codeword.par_iter().map(|&xfe| xfe.into()).collect()
 }
 
+fn cosets_as_digests<H: AlgebraicHasher>(
+    codeword: &[XFieldElement],
+    folding_factor: usize,
+) -> Vec<Digest> {
+    let num_cosets = codeword.len() / folding_factor;
+    (0..num_cosets)
+        .into_par_iter()
+        .map(|coset_index| {
+            let coset = coset_indices(coset_index, num_cosets, folding_factor)
+                .map(|i| codeword[i])
+                .collect_vec();
+            coset_as_digest::<H>(&coset)
+        })
+        .collect()
+}
+
+fn coset_as_digest<H: AlgebraicHasher>(coset: &[XFieldElement]) -> Digest {
+    let elements = coset.iter().flat_map(|xfe| xfe.coefficients).collect_vec();
+    H::hash_varlen(&elements)
+}
+
+/// The first position at which the two codewords of equal length differ, or 0 if they agree.
+fn position_of_first_difference(codeword: &[XFieldElement], other: &[XFieldElement]) -> usize {
+    codeword
+        .iter()
+        .zip_eq(other)
+        .position(|(element, other_element)| element != other_element)
+        .unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
     use std::cmp::max;
@@ -635,6 +957,7 @@ mod tests {
     use assert2::assert;
     use assert2::let_assert;
     use itertools::Itertools;
+    use proptest::collection::vec;
     use proptest::prelude::*;
     use proptest_arbitrary_interop::arb;
     use rand::prelude::*;
@@ -667,6 +990,7 @@ mod tests {
         )(
             log_2_expansion_factor in Just(log_2_expansion_factor),
             log_2_domain_length in log_2_expansion_factor..=18,
+            log_2_folding_factor in 1_usize..=4,
             num_collinearity_checks in 1_usize..=320,
             offset in arb(),
         ) -> Fri<Tip5> {
@@ -682,6 +1006,7 @@ mod tests {
 
             let fri_domain = ArithmeticDomain::of_length(domain_length).with_offset(offset);
             Fri::new(fri_domain, expansion_factor, num_collinearity_checks)
+                .with_folding_factor(1 << log_2_folding_factor)
         }
     }
 
@@ -709,11 +1034,15 @@ mod tests {
 
     #[proptest]
     fn num_rounds_are_reasonable(#[strategy(arbitrary_fri())] fri: Fri<Tip5>) {
-        let expected_last_round_max_degree = fri.first_round_max_degree() >> fri.num_rounds();
+        let log_2_of_folding_factor = fri.folding_factor.ilog2() as usize;
+        let num_halvings = fri.num_rounds() * log_2_of_folding_factor;
+        let expected_last_round_max_degree = fri.first_round_max_degree() >> num_halvings;
         prop_assert_eq!(expected_last_round_max_degree, fri.last_round_max_degree());
         if fri.num_rounds() > 0 {
-            prop_assert!(fri.num_collinearity_checks <= expected_last_round_max_degree);
-            prop_assert!(expected_last_round_max_degree < 2 * fri.num_collinearity_checks);
+            let folding_factor = fri.folding_factor;
+            let num_collinearity_checks = fri.num_collinearity_checks;
+            prop_assert!(num_collinearity_checks <= expected_last_round_max_degree);
+            prop_assert!(expected_last_round_max_degree < folding_factor * num_collinearity_checks);
         }
     }
 
@@ -806,6 +1135,40 @@ mod tests {
         Fri::<Tip5>::new(domain, expansion_factor, num_collinearity_checks);
     }
 
+    #[test]
+    #[should_panic]
+    fn folding_factor_not_a_power_of_two_is_rejected() {
+        let _ = smallest_fri().with_folding_factor(3);
+    }
+
+    #[proptest]
+    fn folding_by_folding_factor_equals_successive_folding_by_two(
+        #[strategy(1_usize..=4)] log_2_folding_factor: usize,
+        #[strategy(#log_2_folding_factor..=10)] _log_2_domain_length: usize,
+        #[strategy(vec(arb(), 1 << #_log_2_domain_length))] codeword: Vec<XFieldElement>,
+        #[strategy(arb())] offset: BFieldElement,
+        #[strategy(arb())] folding_challenge: XFieldElement,
+    ) {
+        let domain = ArithmeticDomain::of_length(codeword.len()).with_offset(offset);
+        let folding_factor = 1 << log_2_folding_factor;
+        let folded_codeword =
+            prover_round(domain, &codeword).split_and_fold(folding_factor, folding_challenge);
+
+        let mut round = prover_round(domain, &codeword);
+        let mut challenge = folding_challenge;
+        for _ in 0..log_2_folding_factor {
+            let codeword = round.split_and_fold(2, challenge);
+            round = prover_round(round.domain.halve(), &codeword);
+            challenge = challenge * challenge;
+        }
+        prop_assert_eq!(folded_codeword, round.codeword);
+    }
+
+    fn prover_round(domain: ArithmeticDomain, codeword: &[XFieldElement]) -> ProverRound<Tip5> {
+        let digests = codeword_as_digests(codeword);
+        ProverRound::new(domain, codeword, MTMaker::from_digests(&digests))
+    }
+
     #[proptest]
     #[should_panic]
     fn domain_size_smaller_than_expansion_factor_is_rejected(
@@ -866,6 +1229,64 @@ mod tests {
         };
     }
 
+    #[proptest(cases = 20)]
+    fn verbose_validation_failure_reports_received_and_computed_merkle_root(
+        #[strategy(arbitrary_fri())] fri: Fri<Tip5>,
+        #[strategy(arbitrary_polynomial())] polynomial: Polynomial<XFieldElement>,
+        rng_seed: u64,
+    ) {
+        let codeword = fri.domain.evaluate(&polynomial);
+        let mut proof_stream = ProofStream::new();
+        fri.prove(&codeword, &mut proof_stream);
+
+        let proof_stream = prepare_proof_stream_for_verification(proof_stream);
+        let mut proof_stream =
+            modify_last_round_codeword_in_proof_stream_using_seed(proof_stream, rng_seed);
+
+        let config = VerifierConfig {
+            verbose_errors: true,
+        };
+        let verdict = fri.verify_with_config(&mut proof_stream, &config, &mut None);
+        let_assert!(Err(WithContext { source, context }) = verdict);
+        assert!(let BadMerkleRootForLastCodeword = *source);
+        let_assert!(
+            FriValidationErrorContext::LastCodewordMerkleRoot { received, computed } = context
+        );
+        assert!(received != computed);
+    }
+
+    #[test]
+    fn tampering_with_proof_of_work_results_in_validation_failure() {
+        let domain = ArithmeticDomain::of_length(1 << 10);
+        let expansion_factor = 4;
+        let num_collinearity_checks = 8;
+        let fri = Fri::<Tip5>::new(domain, expansion_factor, num_collinearity_checks)
+            .with_num_grinding_bits(10);
+
+        let coefficients = [1, 0, 0, 2].map(|c| c.into()).to_vec();
+        let polynomial = Polynomial::<XFieldElement>::new(coefficients);
+        let codeword = fri.domain.evaluate(&polynomial);
+        let mut proof_stream = ProofStream::new();
+        fri.prove(&codeword, &mut proof_stream);
+
+        let mut proof_stream = prepare_proof_stream_for_verification(proof_stream);
+        let verdict = fri.verify(&mut proof_stream.clone(), &mut None);
+        assert!(let Ok(_) = verdict);
+
+        let mut proof_items = proof_stream.items.iter_mut();
+        let_assert!(Some(nonce) = proof_items.find_map(proof_of_work_filter()));
+        *nonce += 1;
+        let_assert!(Err(err) = fri.verify(&mut proof_stream, &mut None));
+        assert!(let InsufficientProofOfWork = err);
+    }
+
+    fn proof_of_work_filter() -> fn(&mut ProofItem) -> Option<&mut u64> {
+        |proof_item| match proof_item {
+            ProofOfWork(nonce) => Some(nonce),
+            _ => None,
+        }
+    }
+
     #[must_use]
     fn prepare_proof_stream_for_verification<H: AlgebraicHasher>(
         mut proof_stream: ProofStream<H>,
@@ -0,0 +1,266 @@
+//! Public input and output that is part of the [`Claim`] only through its [`Digest`].
+//!
+//! The [`Claim`] usually contains the program's entire public input and output, all of which the
+//! verifier has to process. For programs with large public input or output, it is cheaper to only
+//! put the digests into the claim: the evaluation arguments for public input and output then run
+//! over the 5 elements of each digest, independent of the size of the actual input and output.
+//!
+//! The program itself is responsible for binding the actual input and output to the digests. To
+//! this end, it
+//! - obtains the actual input from secret input using the [`read_hashed_input`] subroutine, which
+//!   stores it in RAM, hashes it using the Sponge instructions, and asserts that the result equals
+//!   the digest read from public input, and
+//! - hashes its actual output from RAM using the [`write_hashed_output`] subroutine, which writes
+//!   the resulting digest to public output.
+//!
+//! Both subroutines use the same memory layout: the length of the input or output, followed by
+//! its elements. The corresponding digest is [`io_digest`].
+//!
+//! [`Claim`]: crate::proof::Claim
+
+use twenty_first::shared_math::b_field_element::BFieldElement;
+use twenty_first::shared_math::bfield_codec::BFieldCodec;
+use twenty_first::shared_math::digest::Digest;
+use twenty_first::shared_math::tip5;
+use twenty_first::util_types::algebraic_hasher::AlgebraicHasher;
+
+use crate::instruction::LabelledInstruction;
+use crate::stark::StarkHasher;
+use crate::triton_asm;
+
+/// The digest committing to the given public input or output. Equals the digest computed by the
+/// [`read_hashed_input`] and [`write_hashed_output`] subroutines.
+pub fn io_digest(io: &[BFieldElement]) -> Digest {
+    StarkHasher::hash_varlen(&io.to_vec().encode())
+}
+
+/// The public input to read by [`read_hashed_input`]. Note that the order of the digest's
+/// elements is reversed.
+pub(crate) fn digest_as_public_input(digest: Digest) -> Vec<BFieldElement> {
+    digest.values().into_iter().rev().collect()
+}
+
+/// The public output written by [`write_hashed_output`].
+pub(crate) fn digest_as_public_output(digest: Digest) -> Vec<BFieldElement> {
+    digest.values().to_vec()
+}
+
+/// The secret input required by the [`read_hashed_input`] subroutine to read the given input.
+/// Must precede any other secret input the program
+/// [divines](crate::instruction::AnInstruction::Divine).
+pub fn secret_input_for_hashed_input(input: &[BFieldElement]) -> Vec<BFieldElement> {
+    let padded_input = pad(&input.to_vec().encode());
+    let num_chunks = padded_input.len() / tip5::RATE;
+
+    // Instruction `divine` pushes the divined elements in order, _i.e._, the last element of each
+    // chunk ends up on top of the stack. Instruction `sponge_absorb` absorbs the top of the stack
+    // first.
+    let chunks = padded_input
+        .chunks(tip5::RATE)
+        .flat_map(|chunk| chunk.iter().rev().copied());
+    [BFieldElement::new(num_chunks as u64)]
+        .into_iter()
+        .chain(chunks)
+        .collect()
+}
+
+/// Pad the given elements to a multiple of the Sponge's rate, the same way
+/// [`hash_varlen`](AlgebraicHasher::hash_varlen) does.
+fn pad(elements: &[BFieldElement]) -> Vec<BFieldElement> {
+    let num_padding_zeros = (tip5::RATE - (elements.len() + 1) % tip5::RATE) % tip5::RATE;
+    let padding = [BFieldElement::new(1)]
+        .into_iter()
+        .chain(std::iter::repeat(BFieldElement::new(0)).take(num_padding_zeros));
+    elements.iter().copied().chain(padding).collect()
+}
+
+/// Triton assembly of the subroutine `read_hashed_input`. It reads the input from secret input,
+/// stores its length at `ram_address` followed by its elements, and asserts that its
+/// [digest](io_digest) equals the digest read from public input. The secret input must start
+/// with the output of [`secret_input_for_hashed_input`].
+///
+/// Uses the Sponge state. The memory following the input is overwritten up to the next multiple
+/// of 10 elements.
+///
+/// ```text
+/// BEFORE: _
+/// AFTER:  _
+/// ```
+pub fn read_hashed_input(ram_address: BFieldElement) -> Vec<LabelledInstruction> {
+    triton_asm!(
+        read_hashed_input:
+            divine 1                // _ num_chunks
+            push {ram_address}      // _ num_chunks ram_pointer
+            sponge_init
+            call read_hashed_input_loop
+            pop 2                   // _
+            sponge_squeeze          // _ [garbage; 5] [digest; 5]
+            read_io 5               // _ [garbage; 5] [digest; 5] [public_digest; 5]
+            assert_vector           // _ [garbage; 5] [digest; 5]
+            pop 5 pop 5             // _
+            return
+
+        // BEFORE: _ num_chunks ram_pointer
+        // AFTER:  _ 0 ram_pointer'
+        read_hashed_input_loop:
+            dup 1 push 0 eq skiz return
+            divine 5 divine 5       // _ num_chunks ram_pointer [chunk; 10]
+            {&triton_asm![dup 9; 10]}
+            sponge_absorb           // _ num_chunks ram_pointer [chunk; 10]
+            dup 10                  // _ num_chunks ram_pointer [chunk; 10] ram_pointer
+            write_mem 5 write_mem 5 // _ num_chunks ram_pointer ram_pointer'
+            swap 1 pop 1            // _ num_chunks ram_pointer'
+            swap 1 push -1 add      // _ ram_pointer' (num_chunks - 1)
+            swap 1                  // _ (num_chunks - 1) ram_pointer'
+            recurse
+    )
+}
+
+/// Triton assembly of the subroutine `write_hashed_output`. It writes the [digest](io_digest) of
+/// the output stored at `ram_address` to public output. The output is stored as its length
+/// followed by its elements.
+///
+/// Uses the Sponge state. Overwrites the 10 memory cells following the output with the padding
+/// required for hashing.
+///
+/// ```text
+/// BEFORE: _
+/// AFTER:  _
+/// ```
+pub fn write_hashed_output(ram_address: BFieldElement) -> Vec<LabelledInstruction> {
+    let first_element_address = ram_address + BFieldElement::new(1);
+    triton_asm!(
+        write_hashed_output:
+            push {ram_address}
+            read_mem 1 pop 1        // _ len
+            {&triton_asm![push 0; 9]}
+            push 1                  // _ len [0; 9] 1
+            dup 10
+            push {first_element_address}
+            add                     // _ len [0; 9] 1 padding_address
+            write_mem 5 write_mem 5
+            pop 1                   // _ len
+
+            // num_chunks = (len + 1 + 1 + 9) / 10
+            push 10 swap 1
+            push 1 add              // _ 10 (len + 1)
+            div_mod pop 1           // _ ((len + 1) / 10)
+            push 1 add              // _ num_chunks
+            push {ram_address}      // _ num_chunks ram_pointer
+            sponge_init
+            call write_hashed_output_loop
+            pop 2                   // _
+            sponge_squeeze          // _ [garbage; 5] [digest; 5]
+            write_io 5
+            pop 5                   // _
+            return
+
+        // BEFORE: _ num_chunks ram_pointer
+        // AFTER:  _ 0 ram_pointer'
+        write_hashed_output_loop:
+            dup 1 push 0 eq skiz return
+            dup 0 push 9 add        // _ num_chunks ram_pointer (ram_pointer + 9)
+            read_mem 5 read_mem 5   // _ num_chunks ram_pointer [chunk; 10] (ram_pointer - 1)
+            pop 1
+            sponge_absorb           // _ num_chunks ram_pointer
+            push 10 add             // _ num_chunks ram_pointer'
+            swap 1 push -1 add      // _ ram_pointer' (num_chunks - 1)
+            swap 1                  // _ (num_chunks - 1) ram_pointer'
+            recurse
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::assert;
+    use assert2::let_assert;
+    use proptest::collection::vec;
+    use proptest_arbitrary_interop::arb;
+    use test_strategy::proptest;
+
+    use crate::proof::Claim;
+    use crate::shared_tests::*;
+    use crate::stark::Stark;
+    use crate::triton_program;
+    use crate::NonDeterminism;
+    use crate::Program;
+    use crate::PublicInput;
+
+    use super::*;
+
+    fn ram_address() -> BFieldElement {
+        BFieldElement::new(1 << 20)
+    }
+
+    fn echo_program() -> Program {
+        triton_program!(
+            call read_hashed_input
+            call write_hashed_output
+            halt
+            {&read_hashed_input(ram_address())}
+            {&write_hashed_output(ram_address())}
+        )
+    }
+
+    fn public_input(input: &[BFieldElement]) -> PublicInput {
+        PublicInput::new(digest_as_public_input(io_digest(input)))
+    }
+
+    #[proptest(cases = 30)]
+    fn hashed_output_of_echo_program_commits_to_input(
+        #[strategy(vec(arb(), 0..35))] input: Vec<BFieldElement>,
+    ) {
+        let non_determinism = NonDeterminism::from(secret_input_for_hashed_input(&input));
+        let_assert!(Ok(output) = echo_program().run(public_input(&input), non_determinism));
+        assert!(digest_as_public_output(io_digest(&input)) == output);
+    }
+
+    #[test]
+    fn hashed_input_is_stored_in_ram() {
+        let input = (10..25).map(BFieldElement::new).collect::<Vec<_>>();
+        let last_element_address = ram_address() + BFieldElement::new(input.len() as u64);
+        let program = triton_program!(
+            call read_hashed_input
+            push {ram_address()} read_mem 1 pop 1 write_io 1
+            push {last_element_address} read_mem 1 pop 1 write_io 1
+            halt
+            {&read_hashed_input(ram_address())}
+        );
+
+        let non_determinism = NonDeterminism::from(secret_input_for_hashed_input(&input));
+        let_assert!(Ok(output) = program.run(public_input(&input), non_determinism));
+        assert!(vec![BFieldElement::new(15), BFieldElement::new(24)] == output);
+    }
+
+    #[test]
+    fn secret_input_not_matching_public_digest_crashes_vm() {
+        let input = (0..12).map(BFieldElement::new).collect::<Vec<_>>();
+        let mut secret_input = secret_input_for_hashed_input(&input);
+        secret_input[3] += BFieldElement::new(1);
+
+        let non_determinism = NonDeterminism::from(secret_input);
+        let_assert!(Err(_) = echo_program().run(public_input(&input), non_determinism));
+    }
+
+    #[test]
+    fn claim_with_hashed_io_can_be_verified_knowing_only_the_digests() {
+        let input = (0..100).map(BFieldElement::new).collect::<Vec<_>>();
+        let non_determinism = NonDeterminism::from(secret_input_for_hashed_input(&input));
+        let (parameters, claim, proof) = prove_with_low_security_level(
+            &echo_program(),
+            public_input(&input),
+            non_determinism,
+            &mut None,
+        );
+
+        let program_digest = echo_program().hash::<StarkHasher>();
+        let input_digest = io_digest(&input);
+        let verifiers_claim = Claim::with_hashed_io(program_digest, input_digest, input_digest);
+        assert!(claim == verifiers_claim);
+        let_assert!(Ok(()) = Stark::verify(parameters, &verifiers_claim, &proof, &mut None));
+
+        let other_input_digest = io_digest(&input[1..]);
+        let wrong_claim = Claim::with_hashed_io(program_digest, other_input_digest, input_digest);
+        let_assert!(Err(_) = Stark::verify(parameters, &wrong_claim, &proof, &mut None));
+    }
+}
@@ -0,0 +1,238 @@
+//! A delta-based journal of Triton VM's execution. Instead of storing entire [`VMState`]s, the
+//! journal records only what changed in each step, allowing to step backwards through long
+//! executions cheaply. See [`VMState::step_back`] and [`VMState::seek_to_cycle`].
+
+use strum::EnumCount;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+use twenty_first::shared_math::b_field_element::BFIELD_ZERO;
+use twenty_first::shared_math::digest::Digest;
+use twenty_first::shared_math::tip5;
+
+use crate::instruction::AnInstruction::*;
+use crate::instruction::Instruction;
+use crate::op_stack::OpStackElement;
+use crate::vm::VMState;
+
+/// The number of op stack elements an instruction can access. Elements deeper in the stack are
+/// never modified by a single step.
+const NUM_ACCESSIBLE_OP_STACK_ELEMENTS: usize = OpStackElement::COUNT;
+
+/// The changes of all steps executed since the journal was enabled, in order of execution.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ExecutionJournal {
+    steps: Vec<StepDelta>,
+
+    /// RAM cells written to during the step currently being executed, alongside their previous
+    /// values. Moved into the step's [`StepDelta`] once the step has been executed successfully.
+    pending_ram_writes: Vec<(BFieldElement, Option<BFieldElement>)>,
+}
+
+/// Everything required to undo one step of Triton VM.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepDelta {
+    /// The instruction pointer before the step.
+    pub instruction_pointer: usize,
+
+    /// The length of the op stack before the step.
+    pub op_stack_len: usize,
+
+    /// The op stack slots changed by the step, as pairs of (index into
+    /// [`OpStack::stack`](crate::op_stack::OpStack::stack), previous value).
+    pub op_stack_slots: Vec<(usize, BFieldElement)>,
+
+    /// The RAM cells written by the step, as pairs of (address, previous value). A previous value
+    /// of `None` indicates that the cell had never been written to before.
+    pub ram_cells: Vec<(BFieldElement, Option<BFieldElement>)>,
+
+    /// The length of the jump stack before the step.
+    pub jump_stack_len: usize,
+
+    /// The jump stack entry removed by the step, if any.
+    pub popped_jump_stack_entry: Option<(BFieldElement, BFieldElement)>,
+
+    /// The previous Sponge state, if the step changed it.
+    pub sponge_state: Option<Option<[BFieldElement; tip5::STATE_SIZE]>>,
+
+    /// The public input consumed by the step.
+    pub consumed_public_input: Vec<BFieldElement>,
+
+    /// The length of the public output before the step.
+    pub public_output_len: usize,
+
+    /// The secret individual tokens consumed by the step.
+    pub consumed_secret_individual_tokens: Vec<BFieldElement>,
+
+    /// The secret digests consumed by the step.
+    pub consumed_secret_digests: Vec<Digest>,
+}
+
+/// The parts of a [`VMState`] captured right before a step that are needed to compute the step's
+/// [`StepDelta`].
+pub(crate) struct StepStart {
+    instruction_pointer: usize,
+    op_stack_len: usize,
+    op_stack_top: Vec<BFieldElement>,
+    jump_stack_len: usize,
+    jump_stack_top: Option<(BFieldElement, BFieldElement)>,
+    sponge_state: Option<[BFieldElement; tip5::STATE_SIZE]>,
+    consumed_public_input: Vec<BFieldElement>,
+    public_output_len: usize,
+    consumed_secret_individual_tokens: Vec<BFieldElement>,
+    consumed_secret_digests: Vec<Digest>,
+}
+
+impl ExecutionJournal {
+    /// The number of steps recorded in the journal.
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// The recorded changes, in order of execution.
+    pub fn steps(&self) -> &[StepDelta] {
+        &self.steps
+    }
+
+    pub(crate) fn record_ram_write(
+        &mut self,
+        ram_pointer: BFieldElement,
+        previous_value: Option<BFieldElement>,
+    ) {
+        self.pending_ram_writes.push((ram_pointer, previous_value));
+    }
+
+    pub(crate) fn discard_pending_changes(&mut self) {
+        self.pending_ram_writes.clear();
+    }
+
+    pub(crate) fn record_step(&mut self, step_start: StepStart, state: &VMState) {
+        let op_stack_len = step_start.op_stack_len;
+        let op_stack_window_start = op_stack_len - step_start.op_stack_top.len();
+        let op_stack_slots = (op_stack_window_start..op_stack_len)
+            .zip(step_start.op_stack_top)
+            .filter(|&(index, value)| state.op_stack.stack.get(index) != Some(&value))
+            .collect();
+
+        let popped_jump_stack_entry = match state.jump_stack.len() < step_start.jump_stack_len {
+            true => step_start.jump_stack_top,
+            false => None,
+        };
+        let sponge_state = match state.sponge_state == step_start.sponge_state {
+            true => None,
+            false => Some(step_start.sponge_state),
+        };
+
+        let step_delta = StepDelta {
+            instruction_pointer: step_start.instruction_pointer,
+            op_stack_len,
+            op_stack_slots,
+            ram_cells: self.pending_ram_writes.drain(..).collect(),
+            jump_stack_len: step_start.jump_stack_len,
+            popped_jump_stack_entry,
+            sponge_state,
+            consumed_public_input: step_start.consumed_public_input,
+            public_output_len: step_start.public_output_len,
+            consumed_secret_individual_tokens: step_start.consumed_secret_individual_tokens,
+            consumed_secret_digests: step_start.consumed_secret_digests,
+        };
+        self.steps.push(step_delta);
+    }
+
+    pub(crate) fn pop_step(&mut self) -> Option<StepDelta> {
+        self.steps.pop()
+    }
+}
+
+impl StepStart {
+    /// Capture the parts of the given state that the upcoming execution of `instruction` might
+    /// change irrecoverably.
+    pub(crate) fn capture(state: &VMState, instruction: Instruction) -> Self {
+        let op_stack_len = state.op_stack.stack.len();
+        let op_stack_window_start = op_stack_len.saturating_sub(NUM_ACCESSIBLE_OP_STACK_ELEMENTS);
+        let op_stack_top = state.op_stack.stack[op_stack_window_start..].to_vec();
+
+        let (num_public_input, num_secret_tokens, num_secret_digests) = match instruction {
+            ReadIo(n) => (n.num_words(), 0, 0),
+            Divine(n) => (0, n.num_words(), 0),
+            DivineSibling => (0, 0, 1),
+            _ => (0, 0, 0),
+        };
+        let consumed_public_input = state
+            .public_input
+            .iter()
+            .take(num_public_input)
+            .copied()
+            .collect();
+        let consumed_secret_individual_tokens = state
+            .secret_individual_tokens
+            .iter()
+            .take(num_secret_tokens)
+            .copied()
+            .collect();
+        let consumed_secret_digests = state
+            .secret_digests
+            .iter()
+            .take(num_secret_digests)
+            .copied()
+            .collect();
+
+        Self {
+            instruction_pointer: state.instruction_pointer,
+            op_stack_len,
+            op_stack_top,
+            jump_stack_len: state.jump_stack.len(),
+            jump_stack_top: state.jump_stack.last().copied(),
+            sponge_state: state.sponge_state,
+            consumed_public_input,
+            public_output_len: state.public_output.len(),
+            consumed_secret_individual_tokens,
+            consumed_secret_digests,
+        }
+    }
+}
+
+impl StepDelta {
+    /// Revert the changes recorded in this delta on the given state, which must be the state
+    /// right after the step this delta was recorded for.
+    pub(crate) fn undo(self, state: &mut VMState) {
+        state.instruction_pointer = self.instruction_pointer;
+        state.cycle_count -= 1;
+        state.halting = false;
+
+        let op_stack = &mut state.op_stack.stack;
+        op_stack.resize(self.op_stack_len, BFIELD_ZERO);
+        for (index, value) in self.op_stack_slots {
+            op_stack[index] = value;
+        }
+
+        for (ram_pointer, previous_value) in self.ram_cells.into_iter().rev() {
+            match previous_value {
+                Some(value) => state.ram.insert(ram_pointer, value),
+                None => state.ram.remove(&ram_pointer),
+            };
+        }
+
+        state.jump_stack.truncate(self.jump_stack_len);
+        if let Some(entry) = self.popped_jump_stack_entry {
+            state.jump_stack.push(entry);
+        }
+
+        if let Some(sponge_state) = self.sponge_state {
+            state.sponge_state = sponge_state;
+        }
+
+        for element in self.consumed_public_input.into_iter().rev() {
+            state.public_input.push_front(element);
+        }
+        state.public_output.truncate(self.public_output_len);
+        for element in self.consumed_secret_individual_tokens.into_iter().rev() {
+            state.secret_individual_tokens.push_front(element);
+        }
+        for digest in self.consumed_secret_digests.into_iter().rev() {
+            state.secret_digests.push_front(digest);
+        }
+    }
+}
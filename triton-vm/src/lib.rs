@@ -158,25 +158,38 @@ pub use crate::program::Program;
 pub use crate::program::PublicInput;
 pub use crate::proof::Claim;
 pub use crate::proof::Proof;
+pub use crate::proof_bundle::ProofBundle;
 pub use crate::stark::Stark;
 use crate::stark::StarkHasher;
 pub use crate::stark::StarkParameters;
 
 pub mod aet;
 pub mod arithmetic_domain;
+pub mod checkpoint;
+pub mod coverage;
 pub mod error;
 pub mod example_programs;
 pub mod fri;
+pub mod hashed_io;
 pub mod instruction;
+pub mod journal;
 pub mod op_stack;
 pub mod parser;
+pub mod profile_export;
 pub mod profiler;
 pub mod program;
+pub mod progress;
 pub mod proof;
+pub mod proof_bundle;
+pub mod proof_inspection;
 pub mod proof_item;
+pub mod proof_reader;
 pub mod proof_stream;
+pub mod recursive_verifier;
+pub mod security;
 pub mod stark;
 pub mod table;
+pub mod transcript;
 pub mod vm;
 
 #[cfg(test)]
@@ -589,6 +602,12 @@ pub fn verify(parameters: StarkParameters, claim: &Claim, proof: &Proof) -> bool
     Stark::verify(parameters, claim, proof, &mut None).is_ok()
 }
 
+/// Verify a [`ProofBundle`], _e.g._, one loaded using [`ProofBundle::from_bytes`].
+#[must_use]
+pub fn verify_bundle(bundle: &ProofBundle) -> bool {
+    bundle.verify().is_ok()
+}
+
 #[cfg(test)]
 mod tests {
     use assert2::assert;
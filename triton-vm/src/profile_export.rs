@@ -0,0 +1,207 @@
+//! Exporters turning the result of [`Program::profile`](crate::program::Program::profile) into
+//! formats understood by common visualization tools:
+//! - the [collapsed stack format][collapsed], consumed by, _e.g._, [`inferno`][inferno] or
+//!   [`flamegraph.pl`][flamegraph], and
+//! - the [Chrome trace event format][chrome], understood by, _e.g._, `chrome://tracing` or
+//!   [Perfetto][perfetto].
+//!
+//! [collapsed]: https://github.com/brendangregg/FlameGraph#2-fold-stacks
+//! [inferno]: https://github.com/jonhoo/inferno
+//! [flamegraph]: https://github.com/brendangregg/FlameGraph
+//! [chrome]: https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU
+//! [perfetto]: https://ui.perfetto.dev
+
+use serde_json::json;
+use serde_json::Value;
+
+use crate::program::ProfileLine;
+use crate::table::master_table::TableId;
+
+/// The quantity by which the lines of a profile are weighed when exporting them.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProfileWeight {
+    /// The number of cycles spent in the call, _i.e._, the number of rows added to the
+    /// Processor Table.
+    #[default]
+    ProcessorCycles,
+
+    /// The number of rows the call added to the given table.
+    TableRows(TableId),
+}
+
+impl ProfileWeight {
+    /// The weight of the given line.
+    pub fn of(self, line: &ProfileLine) -> usize {
+        match self {
+            ProfileWeight::ProcessorCycles => line.cycle_count() as usize,
+            ProfileWeight::TableRows(table) => line.table_heights.height_of_table(table),
+        }
+    }
+}
+
+/// Convert a profile into the collapsed stack format. Every line of the output consists of the
+/// semicolon-separated call stack, starting with the profile's final line `total`, followed by the
+/// _self_ weight of the call, _i.e._, the weight not attributed to any nested call.
+///
+/// Calls with the same call stack are merged into one output line, retaining the order of their
+/// first occurrence. Call stacks with a self weight of 0 are omitted.
+pub fn to_collapsed_stacks(profile: &[ProfileLine], weight: ProfileWeight) -> String {
+    let Some((total, calls)) = profile.split_last() else {
+        return String::new();
+    };
+
+    let mut self_weights: Vec<(String, usize)> = vec![];
+    let mut add_self_weight = |stack: String, self_weight: usize| match self_weights
+        .iter_mut()
+        .find(|(s, _)| s == &stack)
+    {
+        Some((_, existing_weight)) => *existing_weight += self_weight,
+        None => self_weights.push((stack, self_weight)),
+    };
+
+    let root_weight = weight.of(total);
+    let top_level_weight = direct_callees(calls, 0).map(|line| weight.of(line)).sum();
+    add_self_weight(
+        total.label.clone(),
+        root_weight.saturating_sub(top_level_weight),
+    );
+
+    let mut stack = vec![total.label.clone()];
+    for (line_number, line) in calls.iter().enumerate() {
+        stack.truncate(line.call_depth + 1);
+        stack.push(line.label.clone());
+
+        let remaining_lines = &calls[line_number + 1..];
+        let callee_weight: usize = direct_callees(remaining_lines, line.call_depth + 1)
+            .map(|callee| weight.of(callee))
+            .sum();
+        let self_weight = weight.of(line).saturating_sub(callee_weight);
+        add_self_weight(stack.join(";"), self_weight);
+    }
+
+    self_weights
+        .into_iter()
+        .filter(|&(_, self_weight)| self_weight > 0)
+        .map(|(stack, self_weight)| format!("{stack} {self_weight}\n"))
+        .collect()
+}
+
+/// Convert a profile into a Chrome trace event file. Every call becomes one complete event, using
+/// processor cycles as timestamps. The rows the call added to each table are recorded in the
+/// event's arguments.
+pub fn to_chrome_trace(profile: &[ProfileLine]) -> String {
+    let trace_events: Vec<Value> = profile.iter().map(chrome_trace_event).collect();
+    let trace = json!({
+        "traceEvents": trace_events,
+        "displayTimeUnit": "ns",
+    });
+    serde_json::to_string_pretty(&trace).unwrap()
+}
+
+fn chrome_trace_event(line: &ProfileLine) -> Value {
+    let table_heights = &line.table_heights;
+    json!({
+        "name": line.label,
+        "ph": "X",
+        "ts": line.start_cycle,
+        "dur": line.cycle_count(),
+        "pid": 0,
+        "tid": 0,
+        "args": {
+            "call_depth": line.call_depth,
+            "call_has_returned": line.call_has_returned,
+            "program": table_heights.program,
+            "processor": table_heights.processor,
+            "op_stack": table_heights.op_stack,
+            "ram": table_heights.ram,
+            "hash": table_heights.hash,
+            "sponge": table_heights.sponge,
+            "cascade": table_heights.cascade,
+            "lookup": table_heights.lookup,
+            "u32": table_heights.u32,
+        },
+    })
+}
+
+/// The lines at the given call depth up until the first line of lower call depth.
+fn direct_callees(lines: &[ProfileLine], call_depth: usize) -> impl Iterator<Item = &ProfileLine> {
+    lines
+        .iter()
+        .take_while(move |line| line.call_depth >= call_depth)
+        .filter(move |line| line.call_depth == call_depth)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::assert;
+    use assert2::let_assert;
+
+    use crate::triton_program;
+
+    use super::*;
+
+    fn profile_with_nested_calls() -> Vec<ProfileLine> {
+        let program = triton_program! {
+            call outer call outer halt
+            outer:
+                push 1 pop 1 call inner call inner return
+            inner:
+                push 2 pop 1 return
+        };
+        let (_, profile) = program.profile([].into(), [].into()).unwrap();
+        profile
+    }
+
+    #[test]
+    fn collapsed_stacks_of_nested_calls() {
+        let profile = profile_with_nested_calls();
+        let collapsed_stacks = to_collapsed_stacks(&profile, ProfileWeight::ProcessorCycles);
+        let lines = collapsed_stacks.lines().collect::<Vec<_>>();
+        assert!(["total 3", "total;outer 10", "total;outer;inner 12"] == lines.as_slice());
+    }
+
+    #[test]
+    fn self_weights_of_collapsed_stacks_sum_to_total_weight() {
+        let profile = profile_with_nested_calls();
+        let weight = ProfileWeight::ProcessorCycles;
+        let collapsed_stacks = to_collapsed_stacks(&profile, weight);
+        let sum_of_self_weights: usize = collapsed_stacks
+            .lines()
+            .map(|line| line.rsplit_once(' ').unwrap().1.parse::<usize>().unwrap())
+            .sum();
+        assert!(weight.of(profile.last().unwrap()) == sum_of_self_weights);
+    }
+
+    #[test]
+    fn collapsed_stacks_can_be_weighed_by_table_rows() {
+        let program = triton_program! {
+            call hasher halt
+            hasher:
+                push 0 push 0 push 0 push 0 push 0
+                push 0 push 0 push 0 push 0 push 0
+                hash pop 5 return
+        };
+        let (_, profile) = program.profile([].into(), [].into()).unwrap();
+        let weight = ProfileWeight::TableRows(TableId::HashTable);
+        let collapsed_stacks = to_collapsed_stacks(&profile, weight);
+
+        let_assert!(Some(hasher_line) = collapsed_stacks.lines().nth(1));
+        let expected_num_rows = profile[0].table_heights.hash;
+        assert!(format!("total;hasher {expected_num_rows}") == hasher_line);
+    }
+
+    #[test]
+    fn chrome_trace_contains_one_event_per_profile_line() {
+        let profile = profile_with_nested_calls();
+        let chrome_trace = to_chrome_trace(&profile);
+        let_assert!(Ok(trace) = serde_json::from_str::<Value>(&chrome_trace));
+        let_assert!(Some(events) = trace["traceEvents"].as_array());
+        assert!(profile.len() == events.len());
+
+        for (line, event) in profile.iter().zip(events) {
+            assert!(line.label == event["name"]);
+            assert!(line.start_cycle == event["ts"]);
+            assert!(line.cycle_count() == event["dur"]);
+        }
+    }
+}
@@ -6,15 +6,18 @@ use std::fmt::Formatter;
 use std::fmt::Result as FmtResult;
 use std::hash::Hash;
 use std::io::Cursor;
+use std::ops::Sub;
 
 use arbitrary::Arbitrary;
 use get_size::GetSize;
 use itertools::Itertools;
 use serde_derive::Deserialize;
 use serde_derive::Serialize;
+use strum::IntoEnumIterator;
 use twenty_first::shared_math::b_field_element::BFieldElement;
 use twenty_first::shared_math::bfield_codec::BFieldCodec;
 use twenty_first::shared_math::digest::Digest;
+use twenty_first::shared_math::tip5;
 use twenty_first::util_types::algebraic_hasher::AlgebraicHasher;
 
 use crate::aet::AlgebraicExecutionTrace;
@@ -27,6 +30,11 @@ use crate::instruction::TypeHint;
 use crate::parser::parse;
 use crate::parser::to_labelled_instructions;
 use crate::parser::ParseError;
+use crate::table::hash_table::HashTable;
+use crate::table::hash_table::PermutationTrace;
+use crate::table::master_table::TableId;
+use crate::table::u32_table::U32TableEntry;
+use crate::vm::CoProcessorCall;
 use crate::vm::VMState;
 
 type Result<T> = std::result::Result<T, VMError>;
@@ -454,14 +462,32 @@ impl Program {
     ///
     /// Returns the [`AlgebraicExecutionTrace`] and the terminal [`VMState`] if execution succeeds.
     pub fn trace_execution_of_state(
+        &self,
+        state: VMState,
+    ) -> Result<(AlgebraicExecutionTrace, VMState)> {
+        let aet = AlgebraicExecutionTrace::new(self.clone());
+        self.continue_trace_execution(state, aet, None)
+    }
+
+    /// Continue tracing the execution of a [`Program`] from a given [`VMState`] and the
+    /// [`AlgebraicExecutionTrace`] recorded so far, for example, after both have been restored from
+    /// their respective [checkpoints](crate::checkpoint). Tracing stops once the VM halts or, if
+    /// `stop_at_cycle` is given, once the VM's cycle count reaches or exceeds it.
+    ///
+    /// Returns the [`AlgebraicExecutionTrace`] and the [`VMState`] at the time tracing stopped.
+    pub fn continue_trace_execution(
         &self,
         mut state: VMState,
+        mut aet: AlgebraicExecutionTrace,
+        stop_at_cycle: Option<u32>,
     ) -> Result<(AlgebraicExecutionTrace, VMState)> {
-        let mut aet = AlgebraicExecutionTrace::new(self.clone());
         assert_eq!(self.instructions, state.program);
+        assert_eq!(self, &aet.program);
         assert_eq!(self.len_bwords(), aet.instruction_multiplicities.len());
 
-        while !state.halting {
+        let reached_stop_cycle =
+            |state: &VMState| stop_at_cycle.is_some_and(|cycle| state.cycle_count >= cycle);
+        while !state.halting && !reached_stop_cycle(&state) {
             if let Err(err) = aet.record_state(&state) {
                 return Err(VMError::new(err, state));
             };
@@ -479,7 +505,10 @@ impl Program {
 
     /// Run Triton VM with the given public and secret input, but record the number of cycles spent
     /// in each callable block of instructions. This function returns a Result wrapping a program
-    /// profiler report, which is a Vec of [`ProfileLine`]s.
+    /// profiler report, which is a Vec of [`ProfileLine`]s. Besides the number of cycles, each
+    /// line records the number of rows the call added to each of Triton VM's tables. Use
+    /// [`ProfileSummary`] to find out which table determines the padded height, and which calls
+    /// contribute to it the most.
     ///
     /// See also [`run`][run] and [`trace_execution`][trace_execution].
     ///
@@ -490,7 +519,7 @@ impl Program {
         public_input: PublicInput,
         non_determinism: NonDeterminism<BFieldElement>,
     ) -> Result<(Vec<BFieldElement>, Vec<ProfileLine>)> {
-        let mut profiler = VMProfiler::new();
+        let mut profiler = VMProfiler::new(self);
         let mut state = VMState::new(self, public_input, non_determinism);
         while !state.halting {
             if let Ok(Instruction::Call(address)) = state.current_instruction() {
@@ -501,15 +530,26 @@ impl Program {
                 profiler.exit_span_at_cycle(state.cycle_count);
             }
 
-            if let Err(err) = state.step() {
-                return Err(VMError::new(err, state));
+            let co_processor_calls = match state.step() {
+                Ok(calls) => calls,
+                Err(err) => return Err(VMError::new(err, state)),
             };
+            profiler.record_co_processor_calls(co_processor_calls);
         }
 
         let report = profiler.report_at_cycle(state.cycle_count);
         Ok((state.public_output, report))
     }
 
+    /// All labels of the program alongside their addresses, sorted by address.
+    pub(crate) fn labels_by_address(&self) -> Vec<(u64, String)> {
+        self.address_to_label
+            .iter()
+            .map(|(&address, label)| (address, label.clone()))
+            .sorted()
+            .collect()
+    }
+
     /// The label for the given address, or a deterministic, unique substitute if no label is found.
     /// Uniqueness is relevant for printing and subsequent parsing, avoiding duplicate labels.
     pub fn label_for_address(&self, address: u64) -> String {
@@ -521,17 +561,47 @@ impl Program {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 struct VMProfiler {
-    call_stack: Vec<usize>,
+    /// The indices of the currently open profile lines, alongside the table heights at the time
+    /// the respective call was made.
+    call_stack: Vec<(usize, VMTableHeights)>,
     profile: Vec<ProfileLine>,
+
+    /// The heights of the co-processor tables so far. The height of the Processor Table is not
+    /// tracked: it follows from the cycle count.
+    table_heights: VMTableHeights,
+
+    /// The distinct 16-bit limbs looked up so far. Each contributes one row to the Cascade Table.
+    cascade_table_limbs: HashSet<u16>,
+
+    /// The distinct entries of the U32 Table so far. Repeated entries do not add any rows.
+    u32_entries: HashSet<U32TableEntry>,
 }
 
 impl VMProfiler {
-    fn new() -> Self {
+    fn new(program: &Program) -> Self {
+        // Program attestation adds rows to the Hash Table and the Cascade Table before the first
+        // instruction is executed.
+        let aet = AlgebraicExecutionTrace::new(program.clone());
+        let table_heights = VMTableHeights {
+            program: aet.program_table_length(),
+            hash: aet.program_hash_trace.nrows(),
+            cascade: aet.cascade_table_length(),
+            lookup: aet.lookup_table_length(),
+            ..VMTableHeights::default()
+        };
+        let cascade_table_limbs = aet
+            .cascade_table_lookup_multiplicities
+            .into_keys()
+            .collect();
+
         VMProfiler {
             call_stack: vec![],
             profile: vec![],
+            table_heights,
+            cascade_table_limbs,
+            u32_entries: HashSet::new(),
         }
     }
 
@@ -542,16 +612,69 @@ impl VMProfiler {
         let profile_line = ProfileLine::new(label, cycle).at_call_depth(call_stack_len);
 
         self.profile.push(profile_line);
-        self.call_stack.push(line_number);
+        self.call_stack
+            .push((line_number, self.table_heights_at_cycle(cycle)));
     }
 
     fn exit_span_at_cycle(&mut self, cycle: u32) {
         let maybe_top_of_call_stack = self.call_stack.pop();
-        if let Some(line_number) = maybe_top_of_call_stack {
-            self.profile[line_number].return_at_cycle(cycle);
+        if let Some((line_number, start_heights)) = maybe_top_of_call_stack {
+            let table_heights = self.table_heights_at_cycle(cycle) - start_heights;
+            let line = &mut self.profile[line_number];
+            line.return_at_cycle(cycle);
+            line.table_heights = table_heights;
         };
     }
 
+    fn record_co_processor_calls(&mut self, co_processor_calls: Vec<CoProcessorCall>) {
+        for call in co_processor_calls {
+            self.record_co_processor_call(call);
+        }
+    }
+
+    /// Mirrors [`AlgebraicExecutionTrace::record_co_processor_call`], but only counts rows.
+    fn record_co_processor_call(&mut self, call: CoProcessorCall) {
+        match call {
+            CoProcessorCall::SpongeStateReset => self.table_heights.sponge += 1,
+            CoProcessorCall::Tip5Trace(Instruction::Hash, trace) => {
+                self.record_cascade_table_lookups(&trace);
+                self.table_heights.hash += trace.len();
+            }
+            CoProcessorCall::Tip5Trace(_, trace) => {
+                self.record_cascade_table_lookups(&trace);
+                self.table_heights.sponge += trace.len();
+            }
+            CoProcessorCall::U32Call(u32_entry) => {
+                if self.u32_entries.insert(u32_entry) {
+                    self.table_heights.u32 += u32_entry.table_length_contribution() as usize;
+                }
+            }
+            CoProcessorCall::OpStackCall(_) => self.table_heights.op_stack += 1,
+            CoProcessorCall::RamCall(_) => self.table_heights.ram += 1,
+        }
+    }
+
+    fn record_cascade_table_lookups(&mut self, trace: &PermutationTrace) {
+        // The last row in the trace is the permutation's result: no lookups are performed for it.
+        let rows_for_which_lookups_are_performed = trace.iter().dropping_back(1);
+        for row in rows_for_which_lookups_are_performed {
+            for &state_element in &row[..tip5::NUM_SPLIT_AND_LOOKUP] {
+                for limb in HashTable::base_field_element_into_16_bit_limbs(state_element) {
+                    if self.cascade_table_limbs.insert(limb) {
+                        self.table_heights.cascade += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    fn table_heights_at_cycle(&self, cycle: u32) -> VMTableHeights {
+        VMTableHeights {
+            processor: cycle as usize,
+            ..self.table_heights
+        }
+    }
+
     fn report_at_cycle(mut self, cycle: u32) -> Vec<ProfileLine> {
         self.stop_all_at_cycle(cycle);
         self.add_total(cycle);
@@ -559,14 +682,18 @@ impl VMProfiler {
     }
 
     fn stop_all_at_cycle(&mut self, cycle: u32) {
-        for &line_number in self.call_stack.iter() {
-            self.profile[line_number].stop_at_cycle(cycle);
+        let table_heights = self.table_heights_at_cycle(cycle);
+        for &(line_number, start_heights) in self.call_stack.iter() {
+            let line = &mut self.profile[line_number];
+            line.stop_at_cycle(cycle);
+            line.table_heights = table_heights - start_heights;
         }
     }
 
     fn add_total(&mut self, cycle: u32) {
         let mut line = ProfileLine::new("total", 0);
         line.return_at_cycle(cycle);
+        line.table_heights = self.table_heights_at_cycle(cycle);
         self.profile.push(line);
     }
 }
@@ -579,6 +706,11 @@ pub struct ProfileLine {
     pub start_cycle: u32,
     pub stop_cycle: u32,
     pub call_has_returned: bool,
+
+    /// The number of rows added to each table between the start and the stop of this line. For
+    /// the line `total`, these are the heights of the tables at the end of execution, including
+    /// rows that do not originate from any instruction, like those for program attestation.
+    pub table_heights: VMTableHeights,
 }
 
 impl ProfileLine {
@@ -589,6 +721,7 @@ impl ProfileLine {
             start_cycle,
             stop_cycle: 0,
             call_has_returned: false,
+            table_heights: VMTableHeights::default(),
         }
     }
 
@@ -624,6 +757,167 @@ impl Display for ProfileLine {
     }
 }
 
+/// The number of rows in each of Triton VM's tables, or the number of rows some part of an
+/// execution adds to them. The Jump Stack Table is omitted since its height always equals the
+/// height of the Processor Table.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct VMTableHeights {
+    pub program: usize,
+    pub processor: usize,
+    pub op_stack: usize,
+    pub ram: usize,
+
+    /// Rows in the Hash Table caused by instruction `hash` and by program attestation.
+    pub hash: usize,
+
+    /// Rows in the Hash Table caused by the Sponge instructions.
+    pub sponge: usize,
+    pub cascade: usize,
+    pub lookup: usize,
+    pub u32: usize,
+}
+
+impl VMTableHeights {
+    /// The number of rows in the given table. Both the `hash` and the `sponge` rows count towards
+    /// the Hash Table. The heights of the Jump Stack Table and the Processor Table are identical.
+    /// The Degree Lowering Table has no rows of its own.
+    pub fn height_of_table(&self, table: TableId) -> usize {
+        match table {
+            TableId::ProgramTable => self.program,
+            TableId::ProcessorTable => self.processor,
+            TableId::OpStackTable => self.op_stack,
+            TableId::RamTable => self.ram,
+            TableId::JumpStackTable => self.processor,
+            TableId::HashTable => self.hash + self.sponge,
+            TableId::CascadeTable => self.cascade,
+            TableId::LookupTable => self.lookup,
+            TableId::U32Table => self.u32,
+            TableId::DegreeLoweringTable => 0,
+        }
+    }
+
+    /// The table with the most rows. In case of a tie, the table that comes first in the order of
+    /// [`TableId`] is returned.
+    pub fn tallest_table(&self) -> TableId {
+        let mut tallest_table = TableId::ProgramTable;
+        for table in TableId::iter() {
+            if self.height_of_table(table) > self.height_of_table(tallest_table) {
+                tallest_table = table;
+            }
+        }
+        tallest_table
+    }
+
+    /// The height all tables are padded to if these are the heights of the unpadded tables.
+    pub fn padded_height(&self) -> usize {
+        self.height_of_table(self.tallest_table())
+            .next_power_of_two()
+    }
+}
+
+impl Sub for VMTableHeights {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            program: self.program.saturating_sub(rhs.program),
+            processor: self.processor.saturating_sub(rhs.processor),
+            op_stack: self.op_stack.saturating_sub(rhs.op_stack),
+            ram: self.ram.saturating_sub(rhs.ram),
+            hash: self.hash.saturating_sub(rhs.hash),
+            sponge: self.sponge.saturating_sub(rhs.sponge),
+            cascade: self.cascade.saturating_sub(rhs.cascade),
+            lookup: self.lookup.saturating_sub(rhs.lookup),
+            u32: self.u32.saturating_sub(rhs.u32),
+        }
+    }
+}
+
+impl Display for VMTableHeights {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "program: {}, ", self.program)?;
+        write!(f, "processor: {}, ", self.processor)?;
+        write!(f, "op_stack: {}, ", self.op_stack)?;
+        write!(f, "ram: {}, ", self.ram)?;
+        write!(f, "hash: {}, ", self.hash)?;
+        write!(f, "sponge: {}, ", self.sponge)?;
+        write!(f, "cascade: {}, ", self.cascade)?;
+        write!(f, "lookup: {}, ", self.lookup)?;
+        write!(f, "u32: {}", self.u32)
+    }
+}
+
+/// Attributes the padded height of an execution to the labels called during that execution.
+///
+/// The rows of nested calls count towards every enclosing call as well. Hence, the contributions
+/// of labels at different call depths can add up to more than the height of the tallest table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileSummary {
+    /// The heights of all tables at the end of execution.
+    pub table_heights: VMTableHeights,
+
+    /// The table that determines the padded height.
+    pub tallest_table: TableId,
+    pub padded_height: usize,
+
+    /// For each called label, the number of rows its calls added to the tallest table, summed
+    /// over all calls. Sorted by contribution, largest first.
+    pub contributions: Vec<(String, usize)>,
+}
+
+impl ProfileSummary {
+    /// Summarize a profile as returned by [`Program::profile`]. The last line of the profile must
+    /// be the line `total`.
+    pub fn new(profile: &[ProfileLine]) -> Self {
+        let (total, calls) = match profile.split_last() {
+            Some((total, calls)) => (total.table_heights, calls),
+            None => (VMTableHeights::default(), profile),
+        };
+        let tallest_table = total.tallest_table();
+
+        let mut contributions: Vec<(String, usize)> = vec![];
+        for line in calls {
+            let num_rows = line.table_heights.height_of_table(tallest_table);
+            match contributions
+                .iter_mut()
+                .find(|(label, _)| label == &line.label)
+            {
+                Some((_, contribution)) => *contribution += num_rows,
+                None => contributions.push((line.label.clone(), num_rows)),
+            }
+        }
+        contributions.sort_by(|(_, left), (_, right)| right.cmp(left));
+
+        Self {
+            table_heights: total,
+            tallest_table,
+            padded_height: total.padded_height(),
+            contributions,
+        }
+    }
+}
+
+impl Display for ProfileSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let tallest_table = self.tallest_table;
+        let tallest_height = self.table_heights.height_of_table(tallest_table);
+        let padded_height = self.padded_height;
+        writeln!(f, "table heights: {}", self.table_heights)?;
+        writeln!(
+            f,
+            "padded height {padded_height} is determined by {tallest_table} ({tallest_height} rows)"
+        )?;
+        for (label, num_rows) in &self.contributions {
+            let share = match tallest_height {
+                0 => 0.0,
+                _ => 100.0 * *num_rows as f64 / tallest_height as f64,
+            };
+            writeln!(f, "  {label}: {num_rows} rows ({share:.1}%)")?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone, Default, Debug, PartialEq, Eq, BFieldCodec, Arbitrary)]
 pub struct PublicInput {
     pub individual_tokens: Vec<BFieldElement>,
@@ -794,6 +1088,7 @@ mod tests {
     use assert2::assert;
     use assert2::let_assert;
     use itertools::Itertools;
+    use ndarray::Array2;
     use proptest::prelude::*;
     use proptest_arbitrary_interop::arb;
     use rand::thread_rng;
@@ -801,6 +1096,7 @@ mod tests {
     use test_strategy::proptest;
     use twenty_first::shared_math::tip5::Tip5;
 
+    use crate::error::CheckpointError;
     use crate::error::InstructionError;
     use crate::example_programs::CALCULATE_NEW_MMR_PEAKS_FROM_APPEND_WITH_SAFE_LISTS;
     use crate::triton_program;
@@ -957,6 +1253,91 @@ mod tests {
         }
     }
 
+    #[test]
+    fn interrupted_and_resumed_trace_execution_is_identical_to_uninterrupted_execution() {
+        let program = CALCULATE_NEW_MMR_PEAKS_FROM_APPEND_WITH_SAFE_LISTS.clone();
+        let initial_state = VMState::new(&program, [].into(), [].into());
+        let (reference_aet, reference_state) = program
+            .trace_execution_of_state(initial_state.clone())
+            .unwrap();
+        let interruption_cycle = reference_state.cycle_count / 2;
+
+        let aet = AlgebraicExecutionTrace::new(program.clone());
+        let (aet, state) = program
+            .continue_trace_execution(initial_state, aet, Some(interruption_cycle))
+            .unwrap();
+        assert!(interruption_cycle == state.cycle_count);
+        assert!(!state.halting);
+
+        let state_checkpoint = state.checkpoint();
+        let aet_checkpoint = aet.checkpoint();
+        drop((state, aet));
+
+        let state = VMState::resume_from_checkpoint(&state_checkpoint).unwrap();
+        let aet = AlgebraicExecutionTrace::resume_from_checkpoint(program.clone(), &aet_checkpoint);
+        let aet = aet.unwrap();
+        let (resumed_aet, resumed_state) =
+            program.continue_trace_execution(state, aet, None).unwrap();
+
+        assert!(reference_state == resumed_state);
+        assert!(reference_aet.checkpoint() == resumed_aet.checkpoint());
+        assert!(reference_aet.padded_height() == resumed_aet.padded_height());
+    }
+
+    #[test]
+    fn continuing_trace_execution_past_stop_cycle_stops_immediately() {
+        let program = CALCULATE_NEW_MMR_PEAKS_FROM_APPEND_WITH_SAFE_LISTS.clone();
+        let initial_state = VMState::new(&program, [].into(), [].into());
+        let aet = AlgebraicExecutionTrace::new(program.clone());
+        let (aet, state) = program
+            .continue_trace_execution(initial_state, aet, Some(10))
+            .unwrap();
+
+        let (_, state) = program
+            .continue_trace_execution(state, aet, Some(5))
+            .unwrap();
+        assert!(10 == state.cycle_count);
+        assert!(!state.halting);
+    }
+
+    #[test]
+    fn checkpoint_of_algebraic_execution_trace_with_inconsistent_dimensions_is_rejected() {
+        let program = triton_program!(push 1 pop 1 halt);
+        let state = VMState::new(&program, [].into(), [].into());
+        let aet = AlgebraicExecutionTrace::new(program.clone());
+        let (aet, _) = program.continue_trace_execution(state, aet, None).unwrap();
+
+        let mut aet_with_short_multiplicities = aet.clone();
+        aet_with_short_multiplicities
+            .instruction_multiplicities
+            .pop();
+        let checkpoint = aet_with_short_multiplicities.checkpoint();
+        let resumed = AlgebraicExecutionTrace::resume_from_checkpoint(program.clone(), &checkpoint);
+        let_assert!(Err(CheckpointError::InconsistentTraceDimensions) = resumed);
+
+        let mut aet_with_narrow_trace = aet;
+        aet_with_narrow_trace.processor_trace = Array2::default([3, 1]);
+        let checkpoint = aet_with_narrow_trace.checkpoint();
+        let resumed = AlgebraicExecutionTrace::resume_from_checkpoint(program, &checkpoint);
+        let_assert!(Err(CheckpointError::InconsistentTraceDimensions) = resumed);
+    }
+
+    #[test]
+    fn checkpoint_of_vm_state_cannot_be_resumed_as_algebraic_execution_trace() {
+        let program = triton_program!(halt);
+        let state_checkpoint = VMState::new(&program, [].into(), [].into()).checkpoint();
+        let aet = AlgebraicExecutionTrace::resume_from_checkpoint(program, &state_checkpoint);
+        let_assert!(Err(CheckpointError::UnexpectedKind(_, _)) = aet);
+    }
+
+    #[test]
+    fn checkpoint_of_algebraic_execution_trace_cannot_be_resumed_for_different_program() {
+        let aet_checkpoint = AlgebraicExecutionTrace::new(triton_program!(halt)).checkpoint();
+        let other_program = triton_program!(nop halt);
+        let aet = AlgebraicExecutionTrace::resume_from_checkpoint(other_program, &aet_checkpoint);
+        let_assert!(Err(CheckpointError::ProgramDigestMismatch) = aet);
+    }
+
     #[test]
     fn test_profile_with_open_calls() {
         let program = triton_program! {
@@ -978,6 +1359,72 @@ mod tests {
         assert!(maybe_open_call.is_some());
     }
 
+    #[test]
+    fn profiled_table_heights_match_algebraic_execution_trace() {
+        let program_with_sponge_instructions = triton_program! {
+            sponge_init
+            push 1 push 2 push 3 push 4 push 5 push 6 push 7 push 8 push 9 push 10
+            sponge_absorb sponge_squeeze
+            push 5 push 7 lt split pop 3
+            halt
+        };
+        let programs = [
+            CALCULATE_NEW_MMR_PEAKS_FROM_APPEND_WITH_SAFE_LISTS.clone(),
+            program_with_sponge_instructions,
+        ];
+        for program in programs {
+            let (_, profile) = program.profile([].into(), [].into()).unwrap();
+            let (aet, _) = program.trace_execution([].into(), [].into()).unwrap();
+
+            let total = profile.last().unwrap().table_heights;
+            assert!(aet.program_table_length() == total.program);
+            assert!(aet.processor_table_length() == total.processor);
+            assert!(aet.op_stack_table_length() == total.op_stack);
+            assert!(aet.ram_table_length() == total.ram);
+            assert!(aet.hash_table_length() == total.hash + total.sponge);
+            assert!(aet.cascade_table_length() == total.cascade);
+            assert!(aet.lookup_table_length() == total.lookup);
+            assert!(aet.u32_table_length() == total.u32);
+            assert!(aet.padded_height() == total.padded_height());
+
+            let summary = ProfileSummary::new(&profile);
+            assert!(aet.padded_height() == summary.padded_height);
+            println!("{summary}");
+        }
+    }
+
+    #[test]
+    fn table_heights_are_attributed_to_the_calls_causing_them() {
+        let program = triton_program! {
+            call hasher call splitter halt
+            hasher:
+                push 0 push 0 push 0 push 0 push 0
+                push 0 push 0 push 0 push 0 push 0
+                hash pop 5 return
+            splitter:
+                push 1 push 2 lt pop 1 push -1 split pop 2 return
+        };
+        let (_, profile) = program.profile([].into(), [].into()).unwrap();
+        let_assert!([hasher, splitter, total] = profile.as_slice());
+
+        assert!(hasher.cycle_count() == hasher.table_heights.processor as u32);
+        assert!(hasher.table_heights.hash > 0);
+        assert!(0 == hasher.table_heights.u32);
+        assert!(0 == splitter.table_heights.hash);
+        assert!(splitter.table_heights.u32 > 0);
+        assert!(0 == total.table_heights.sponge);
+
+        let summary = ProfileSummary::new(&profile);
+        let tallest_table = summary.tallest_table;
+        for table in TableId::iter() {
+            let height = summary.table_heights.height_of_table(table);
+            assert!(height <= summary.table_heights.height_of_table(tallest_table));
+        }
+        let hasher_rows = hasher.table_heights.height_of_table(tallest_table);
+        let hasher_contribution = ("hasher".to_string(), hasher_rows);
+        assert!(summary.contributions.contains(&hasher_contribution));
+    }
+
     #[test]
     fn program_with_too_many_returns_crashes_vm_but_not_profiler() {
         let program = triton_program! {
@@ -0,0 +1,226 @@
+//! Progress reporting and cooperative cancellation for proof generation.
+//!
+//! Pass a [`ProvingHandle`] to [`Stark::prove_with_handle`](crate::stark::Stark::prove_with_handle)
+//! to get notified whenever the prover enters a new [stage](ProvingStage), and to abort proof
+//! generation from a different thread using [`ProvingHandle::cancel`]. Cancellation is
+//! cooperative: the prover checks for it between stages and inside its most expensive parallel
+//! loops, then returns [`ProvingError::Cancelled`].
+
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use crate::error::ProvingError;
+
+/// The stages of proof generation, in the order the prover goes through them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProvingStage {
+    /// Creating, padding, and randomizing the master base table from the execution trace.
+    BaseTableCreation,
+    BaseTableLowDegreeExtension,
+    BaseTableMerkleTree,
+
+    /// Extending the master base table using the verifier's challenges.
+    ExtensionTableCreation,
+    ExtensionTableLowDegreeExtension,
+    ExtensionTableMerkleTree,
+
+    /// Computing and committing to the quotients, as well as evaluating everything out of domain.
+    Quotients,
+
+    /// Combining all codewords into the DEEP codeword.
+    Deep,
+
+    /// Committing to the given round of FRI. Round 0 is the DEEP codeword itself.
+    FriRound(usize),
+
+    /// Revealing the rows of the committed tables that FRI asks for.
+    Openings,
+
+    /// The proof is complete.
+    Finished,
+}
+
+impl ProvingStage {
+    /// A rough estimate of the fraction of the total proving time that has elapsed once the prover
+    /// enters this stage. For [`FriRound`](Self::FriRound), the total number of rounds is required.
+    ///
+    /// The estimates are derived from profiles of typical programs. They are good enough to drive
+    /// a progress bar, but not to predict the remaining proving time accurately.
+    pub fn estimated_fraction_done(self, num_fri_rounds: usize) -> f64 {
+        match self {
+            ProvingStage::BaseTableCreation => 0.0,
+            ProvingStage::BaseTableLowDegreeExtension => 0.05,
+            ProvingStage::BaseTableMerkleTree => 0.15,
+            ProvingStage::ExtensionTableCreation => 0.25,
+            ProvingStage::ExtensionTableLowDegreeExtension => 0.3,
+            ProvingStage::ExtensionTableMerkleTree => 0.4,
+            ProvingStage::Quotients => 0.48,
+            ProvingStage::Deep => 0.7,
+            ProvingStage::FriRound(round) => {
+                let num_fri_rounds = num_fri_rounds.max(1) as f64;
+                let fraction_of_fri_done = (round as f64 / num_fri_rounds).min(1.0);
+                0.78 + 0.16 * fraction_of_fri_done
+            }
+            ProvingStage::Openings => 0.95,
+            ProvingStage::Finished => 1.0,
+        }
+    }
+}
+
+/// Reported to the observer of a [`ProvingHandle`] whenever the prover enters a new stage.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProvingProgress {
+    pub stage: ProvingStage,
+
+    /// See [`ProvingStage::estimated_fraction_done`].
+    pub estimated_fraction_done: f64,
+}
+
+type ProgressObserver = dyn Fn(ProvingProgress) + Send + Sync;
+
+/// Observes and controls proof generation. Clones of a handle share the cancellation flag and the
+/// observer, allowing to keep one clone to [cancel](Self::cancel) a proof that is being generated
+/// on a different thread.
+#[derive(Clone, Default)]
+pub struct ProvingHandle {
+    cancelled: Arc<AtomicBool>,
+    observer: Option<Arc<ProgressObserver>>,
+}
+
+impl ProvingHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Call the given function whenever the prover enters a new [stage](ProvingStage). The
+    /// function is called from the thread generating the proof.
+    #[must_use]
+    pub fn with_observer(
+        mut self,
+        observer: impl Fn(ProvingProgress) + Send + Sync + 'static,
+    ) -> Self {
+        self.observer = Some(Arc::new(observer));
+        self
+    }
+
+    /// Request the proof generation using this handle (or any of its clones) to stop. The prover
+    /// stops at the next opportunity and returns [`ProvingError::Cancelled`].
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn check_cancellation(&self) -> Result<(), ProvingError> {
+        match self.is_cancelled() {
+            true => Err(ProvingError::Cancelled),
+            false => Ok(()),
+        }
+    }
+
+    /// Report the start of the given stage, unless proof generation was cancelled.
+    pub(crate) fn enter_stage(&self, stage: ProvingStage) -> Result<(), ProvingError> {
+        self.report(stage, 0)
+    }
+
+    /// Report the start of the given round of FRI, unless proof generation was cancelled.
+    pub(crate) fn enter_fri_round(
+        &self,
+        round: usize,
+        num_rounds: usize,
+    ) -> Result<(), ProvingError> {
+        self.report(ProvingStage::FriRound(round), num_rounds)
+    }
+
+    fn report(&self, stage: ProvingStage, num_fri_rounds: usize) -> Result<(), ProvingError> {
+        self.check_cancellation()?;
+        if let Some(observer) = &self.observer {
+            let estimated_fraction_done = stage.estimated_fraction_done(num_fri_rounds);
+            observer(ProvingProgress {
+                stage,
+                estimated_fraction_done,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Debug for ProvingHandle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("ProvingHandle")
+            .field("cancelled", &self.is_cancelled())
+            .field("has_observer", &self.observer.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use assert2::assert;
+    use assert2::let_assert;
+
+    use super::*;
+
+    #[test]
+    fn clones_of_a_handle_share_the_cancellation_flag() {
+        let handle = ProvingHandle::new();
+        let clone = handle.clone();
+        assert!(!clone.is_cancelled());
+        handle.cancel();
+        assert!(clone.is_cancelled());
+        let_assert!(Err(ProvingError::Cancelled) = clone.enter_stage(ProvingStage::Deep));
+    }
+
+    #[test]
+    fn observer_is_notified_about_stages() {
+        let reports = Arc::new(Mutex::new(vec![]));
+        let reports_for_observer = reports.clone();
+        let handle = ProvingHandle::new()
+            .with_observer(move |progress| reports_for_observer.lock().unwrap().push(progress));
+
+        handle.enter_stage(ProvingStage::Quotients).unwrap();
+        handle.enter_fri_round(1, 4).unwrap();
+
+        let reports = reports.lock().unwrap();
+        let_assert!([quotients, fri] = reports.as_slice());
+        assert!(ProvingStage::Quotients == quotients.stage);
+        assert!(ProvingStage::FriRound(1) == fri.stage);
+        assert!(quotients.estimated_fraction_done < fri.estimated_fraction_done);
+    }
+
+    #[test]
+    fn estimated_fractions_are_monotonic() {
+        let num_fri_rounds = 5;
+        let fri_rounds = (0..=num_fri_rounds).map(ProvingStage::FriRound);
+        let stages = [
+            ProvingStage::BaseTableCreation,
+            ProvingStage::BaseTableLowDegreeExtension,
+            ProvingStage::BaseTableMerkleTree,
+            ProvingStage::ExtensionTableCreation,
+            ProvingStage::ExtensionTableLowDegreeExtension,
+            ProvingStage::ExtensionTableMerkleTree,
+            ProvingStage::Quotients,
+            ProvingStage::Deep,
+        ]
+        .into_iter()
+        .chain(fri_rounds)
+        .chain([ProvingStage::Openings, ProvingStage::Finished]);
+
+        let fractions = stages
+            .map(|stage| stage.estimated_fraction_done(num_fri_rounds))
+            .collect::<Vec<_>>();
+        for (earlier, later) in fractions.iter().zip(fractions.iter().skip(1)) {
+            assert!(earlier <= later);
+        }
+        assert!(0.0 == fractions[0]);
+        assert!(1.0 == fractions[fractions.len() - 1]);
+    }
+}
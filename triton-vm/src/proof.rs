@@ -8,6 +8,8 @@ use twenty_first::shared_math::bfield_codec::BFieldCodec;
 use twenty_first::shared_math::tip5::Digest;
 
 use crate::error::ProofStreamError;
+use crate::hashed_io;
+use crate::proof_inspection::ProofInspection;
 use crate::proof_stream::ProofStream;
 use crate::stark;
 
@@ -35,6 +37,22 @@ impl Proof {
         }
         Ok(1 << log_2_padded_heights[0])
     }
+
+    /// Break down the proof into its parts, listing their sizes and the parameters implied by the
+    /// proof's shape. The [`Display`](std::fmt::Display) of the returned [`ProofInspection`]
+    /// renders the breakdown as tables.
+    pub fn inspect(&self) -> Result<ProofInspection, ProofStreamError> {
+        ProofInspection::new(self)
+    }
+
+    /// Every field element of the proof as 8 little-endian bytes. This is the format read by
+    /// [`ProofReader`](crate::proof_reader::ProofReader).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0
+            .iter()
+            .flat_map(|element| element.value().to_le_bytes())
+            .collect()
+    }
 }
 
 /// Contains the public information of a verifiably correct computation.
@@ -57,6 +75,22 @@ pub struct Claim {
 }
 
 impl Claim {
+    /// A claim about a program whose actual public input and output are only committed to through
+    /// their [digests](crate::hashed_io::io_digest). The verifier does not need to know the actual
+    /// input or output. See [`hashed_io`](crate::hashed_io) for how the program binds them to the
+    /// digests.
+    pub fn with_hashed_io(
+        program_digest: Digest,
+        input_digest: Digest,
+        output_digest: Digest,
+    ) -> Self {
+        Self {
+            program_digest,
+            input: hashed_io::digest_as_public_input(input_digest),
+            output: hashed_io::digest_as_public_output(output_digest),
+        }
+    }
+
     /// The public input as `u64`s.
     /// If `BFieldElement`s are needed, use field `input`.
     pub fn public_input(&self) -> Vec<u64> {
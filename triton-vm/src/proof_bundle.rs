@@ -0,0 +1,383 @@
+//! A self-describing container for a [`Proof`], bundling it with everything needed to verify it:
+//! the [`StarkParameters`], the [`Claim`], and the version of the AIR the proof was generated
+//! for. See [`ProofBundle`].
+//!
+//! The binary encoding of a proof bundle starts with a fixed header, followed by the payload:
+//!
+//! | bytes  | content                                                        |
+//! |-------:|:---------------------------------------------------------------|
+//! | 0..4   | the magic bytes `TVPB`                                         |
+//! | 4..6   | the format version, little-endian                              |
+//! | 6..46  | the [AIR version](air_version), as 5 little-endian `u64`s      |
+//! | 46..   | the [bincode]-serialized parameters, claim, and proof          |
+//!
+//! The JSON encoding is an object with the fields `magic`, `format_version`, `air_version`,
+//! `parameters`, `claim`, and `proof`.
+
+use ndarray::Array1;
+use serde::Deserialize;
+use serde::Serialize;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+use twenty_first::shared_math::tip5::Digest;
+use twenty_first::shared_math::tip5::DIGEST_LENGTH;
+use twenty_first::util_types::algebraic_hasher::AlgebraicHasher;
+
+use crate::error::ProofBundleError;
+use crate::error::ProofBundleError::*;
+use crate::error::VerificationError;
+use crate::proof::Claim;
+use crate::proof::Proof;
+use crate::stark::Stark;
+use crate::stark::StarkHasher;
+use crate::stark::StarkParameters;
+use crate::stark::StarkProofStream;
+use crate::table::challenges::Challenges;
+use crate::table::extension_table::Evaluable;
+use crate::table::extension_table::Quotientable;
+use crate::table::master_table::MasterExtTable;
+use crate::table::master_table::NUM_BASE_COLUMNS;
+use crate::table::master_table::NUM_EXT_COLUMNS;
+
+/// The magic bytes every proof bundle starts with.
+pub const PROOF_BUNDLE_MAGIC: [u8; 4] = *b"TVPB";
+
+/// The version of the proof bundle format. Bumped whenever the layout of a proof bundle changes
+/// in a backwards-incompatible way.
+pub const PROOF_BUNDLE_VERSION: u16 = 1;
+
+const AIR_VERSION_LEN: usize = DIGEST_LENGTH * 8;
+const HEADER_LEN: usize = PROOF_BUNDLE_MAGIC.len() + 2 + AIR_VERSION_LEN;
+
+/// Separates the pseudo-random evaluation points of [`air_version`] from all other uses of the
+/// sponge.
+const AIR_VERSION_DOMAIN_SEPARATOR: &[u8] = b"Triton VM AIR version";
+
+/// The interpolant degree and padded height for which the quotients' degree bounds enter the
+/// [`air_version`].
+const AIR_VERSION_PADDED_HEIGHT: usize = 1 << 10;
+
+/// A [`Proof`] together with the [`StarkParameters`] and the [`Claim`] it was generated for, as
+/// well as the [version of the AIR](air_version) of the Triton VM that generated it.
+///
+/// Use [`to_bytes`](Self::to_bytes) and [`to_json`](Self::to_json) to store a bundle, and
+/// [`from_bytes`](Self::from_bytes) and [`from_json`](Self::from_json) to load it. Loading
+/// rejects bundles of an unknown format version or of a different AIR version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofBundle {
+    pub air_version: Digest,
+    pub parameters: StarkParameters,
+    pub claim: Claim,
+    pub proof: Proof,
+}
+
+/// The parts of a [`ProofBundle`] that are not part of the binary encoding's header.
+#[derive(Serialize, Deserialize)]
+struct Payload {
+    parameters: StarkParameters,
+    claim: Claim,
+    proof: Proof,
+}
+
+/// The JSON encoding of a [`ProofBundle`].
+#[derive(Serialize, Deserialize)]
+struct JsonProofBundle {
+    magic: String,
+    format_version: u16,
+    #[serde(flatten)]
+    bundle: ProofBundle,
+}
+
+/// The part of the JSON encoding of a [`ProofBundle`] that is stable across format versions.
+#[derive(Deserialize)]
+struct JsonHeader {
+    magic: String,
+    format_version: u16,
+}
+
+impl ProofBundle {
+    /// Bundle the given proof with the [current AIR version](air_version).
+    pub fn new(parameters: StarkParameters, claim: Claim, proof: Proof) -> Self {
+        Self {
+            air_version: air_version(),
+            parameters,
+            claim,
+            proof,
+        }
+    }
+
+    /// The compact binary encoding of the bundle. See the [module-level documentation](self).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN);
+        bytes.extend_from_slice(&PROOF_BUNDLE_MAGIC);
+        bytes.extend_from_slice(&PROOF_BUNDLE_VERSION.to_le_bytes());
+        for element in self.air_version.values() {
+            bytes.extend_from_slice(&element.value().to_le_bytes());
+        }
+        let payload = Payload {
+            parameters: self.parameters,
+            claim: self.claim.clone(),
+            proof: self.proof.clone(),
+        };
+        bincode::serialize_into(&mut bytes, &payload)
+            .expect("serializing into a vector of bytes must succeed");
+        bytes
+    }
+
+    /// Load a bundle previously encoded using [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofBundleError> {
+        if bytes.len() < HEADER_LEN {
+            return Err(TooShort);
+        }
+        let (header, payload) = bytes.split_at(HEADER_LEN);
+        let (magic, header) = header.split_at(PROOF_BUNDLE_MAGIC.len());
+        if magic != PROOF_BUNDLE_MAGIC {
+            return Err(BadMagic);
+        }
+        let (version, air_version) = header.split_at(2);
+        let version = u16::from_le_bytes([version[0], version[1]]);
+        if version != PROOF_BUNDLE_VERSION {
+            return Err(UnsupportedVersion(version));
+        }
+
+        let mut air_version_elements = [BFieldElement::new(0); DIGEST_LENGTH];
+        for (element, bytes) in air_version_elements
+            .iter_mut()
+            .zip(air_version.chunks_exact(8))
+        {
+            let value = u64::from_le_bytes(bytes.try_into().unwrap());
+            if value > BFieldElement::MAX {
+                return Err(NonCanonicalAirVersion);
+            }
+            *element = BFieldElement::new(value);
+        }
+        let air_version = Digest::new(air_version_elements);
+        check_air_version(air_version)?;
+
+        let Payload {
+            parameters,
+            claim,
+            proof,
+        } = bincode::deserialize(payload)?;
+        Ok(Self {
+            air_version,
+            parameters,
+            claim,
+            proof,
+        })
+    }
+
+    /// The JSON encoding of the bundle. See the [module-level documentation](self).
+    pub fn to_json(&self) -> String {
+        let json_bundle = JsonProofBundle {
+            magic: magic_as_string(),
+            format_version: PROOF_BUNDLE_VERSION,
+            bundle: self.clone(),
+        };
+        serde_json::to_string_pretty(&json_bundle).unwrap()
+    }
+
+    /// Load a bundle previously encoded using [`to_json`](Self::to_json).
+    pub fn from_json(json: &str) -> Result<Self, ProofBundleError> {
+        let header: JsonHeader = serde_json::from_str(json)?;
+        if header.magic != magic_as_string() {
+            return Err(BadMagic);
+        }
+        if header.format_version != PROOF_BUNDLE_VERSION {
+            return Err(UnsupportedVersion(header.format_version));
+        }
+        let JsonProofBundle { bundle, .. } = serde_json::from_str(json)?;
+        check_air_version(bundle.air_version)?;
+        Ok(bundle)
+    }
+
+    /// Verify the bundled proof for the bundled claim and parameters. Fails if the bundle was
+    /// generated for a different [AIR version](air_version).
+    pub fn verify(&self) -> Result<(), VerificationError> {
+        if self.air_version != air_version() {
+            return Err(VerificationError::AirVersionMismatch);
+        }
+        Stark::verify(self.parameters, &self.claim, &self.proof, &mut None)
+    }
+}
+
+fn magic_as_string() -> String {
+    String::from_utf8_lossy(&PROOF_BUNDLE_MAGIC).into_owned()
+}
+
+fn check_air_version(air_version_of_bundle: Digest) -> Result<(), ProofBundleError> {
+    let expected = air_version();
+    if air_version_of_bundle != expected {
+        return Err(AirVersionMismatch {
+            expected,
+            found: air_version_of_bundle,
+        });
+    }
+    Ok(())
+}
+
+/// A fingerprint of Triton VM's Arithmetic Intermediate Representation (AIR). Proofs generated
+/// for one AIR cannot be verified using a different AIR.
+///
+/// The fingerprint is the hash of the table widths, the quotients' degree bounds, and the
+/// evaluation of all AIR constraints in pseudo-random points. Any change to the constraints
+/// changes the fingerprint with overwhelming probability.
+pub fn air_version() -> Digest {
+    let mut proof_stream = StarkProofStream::new();
+    let domain_separator = AIR_VERSION_DOMAIN_SEPARATOR
+        .iter()
+        .map(|&byte| BFieldElement::new(byte.into()))
+        .collect::<Vec<_>>();
+    proof_stream.alter_fiat_shamir_state_with(&domain_separator);
+
+    let mut sample_row = |num_columns| Array1::from(proof_stream.sample_scalars(num_columns));
+    let curr_base_row = sample_row(NUM_BASE_COLUMNS);
+    let curr_ext_row = sample_row(NUM_EXT_COLUMNS);
+    let next_base_row = sample_row(NUM_BASE_COLUMNS);
+    let next_ext_row = sample_row(NUM_EXT_COLUMNS);
+    let challenges = sample_row(Challenges::count()).to_vec();
+    let challenges = Challenges {
+        challenges: challenges.try_into().unwrap(),
+    };
+
+    let evaluated_constraints = [
+        MasterExtTable::evaluate_initial_constraints(
+            curr_base_row.view(),
+            curr_ext_row.view(),
+            &challenges,
+        ),
+        MasterExtTable::evaluate_consistency_constraints(
+            curr_base_row.view(),
+            curr_ext_row.view(),
+            &challenges,
+        ),
+        MasterExtTable::evaluate_transition_constraints(
+            curr_base_row.view(),
+            curr_ext_row.view(),
+            next_base_row.view(),
+            next_ext_row.view(),
+            &challenges,
+        ),
+        MasterExtTable::evaluate_terminal_constraints(
+            curr_base_row.view(),
+            curr_ext_row.view(),
+            &challenges,
+        ),
+    ]
+    .concat();
+
+    let padded_height = AIR_VERSION_PADDED_HEIGHT;
+    let interpolant_degree = (padded_height - 1) as i64;
+    let degree_bounds = [
+        MasterExtTable::initial_quotient_degree_bounds(interpolant_degree),
+        MasterExtTable::consistency_quotient_degree_bounds(interpolant_degree, padded_height),
+        MasterExtTable::transition_quotient_degree_bounds(interpolant_degree, padded_height),
+        MasterExtTable::terminal_quotient_degree_bounds(interpolant_degree),
+    ]
+    .concat();
+
+    let table_widths = [NUM_BASE_COLUMNS, NUM_EXT_COLUMNS].map(|width| width as u64);
+    let fingerprint = table_widths
+        .into_iter()
+        .map(BFieldElement::new)
+        .chain(
+            degree_bounds
+                .into_iter()
+                .map(|bound| BFieldElement::new(bound as u64)),
+        )
+        .chain(
+            evaluated_constraints
+                .into_iter()
+                .flat_map(|e| e.coefficients),
+        )
+        .collect::<Vec<_>>();
+    StarkHasher::hash_varlen(&fingerprint)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::assert;
+    use assert2::let_assert;
+    use proptest_arbitrary_interop::arb;
+    use test_strategy::proptest;
+
+    use crate::shared_tests::*;
+    use crate::triton_program;
+    use crate::NonDeterminism;
+    use crate::PublicInput;
+
+    use super::*;
+
+    #[proptest(cases = 20)]
+    fn encoding_and_decoding_bundle_is_identity(
+        #[strategy(arb())] claim: Claim,
+        #[strategy(arb())] proof: Proof,
+    ) {
+        let bundle = ProofBundle::new(StarkParameters::default(), claim, proof);
+        let_assert!(Ok(decoded) = ProofBundle::from_bytes(&bundle.to_bytes()));
+        assert!(bundle == decoded);
+        let_assert!(Ok(decoded) = ProofBundle::from_json(&bundle.to_json()));
+        assert!(bundle == decoded);
+    }
+
+    fn dummy_bundle() -> ProofBundle {
+        let claim = Claim {
+            program_digest: Digest::default(),
+            input: vec![],
+            output: vec![],
+        };
+        ProofBundle::new(StarkParameters::default(), claim, Proof(vec![]))
+    }
+
+    #[test]
+    fn bundle_without_magic_is_rejected() {
+        let mut bytes = dummy_bundle().to_bytes();
+        bytes[0] = b'X';
+        let_assert!(Err(BadMagic) = ProofBundle::from_bytes(&bytes));
+        let_assert!(Err(TooShort) = ProofBundle::from_bytes(&bytes[..HEADER_LEN - 1]));
+
+        let json = dummy_bundle().to_json().replace("TVPB", "XVPB");
+        let_assert!(Err(BadMagic) = ProofBundle::from_json(&json));
+    }
+
+    #[test]
+    fn bundle_of_unknown_version_is_rejected() {
+        let mut bytes = dummy_bundle().to_bytes();
+        bytes[4] ^= 0xff;
+        let_assert!(Err(UnsupportedVersion(_)) = ProofBundle::from_bytes(&bytes));
+
+        let version_field = format!("\"format_version\": {PROOF_BUNDLE_VERSION}");
+        let json = dummy_bundle().to_json();
+        assert!(json.contains(&version_field));
+        let json = json.replace(&version_field, "\"format_version\": 65535");
+        let_assert!(Err(UnsupportedVersion(65535)) = ProofBundle::from_json(&json));
+    }
+
+    #[test]
+    fn bundle_of_different_air_version_is_rejected() {
+        let mut bundle = dummy_bundle();
+        bundle.air_version = Digest::default();
+        let_assert!(Err(AirVersionMismatch { .. }) = ProofBundle::from_bytes(&bundle.to_bytes()));
+        let_assert!(Err(AirVersionMismatch { .. }) = ProofBundle::from_json(&bundle.to_json()));
+        let_assert!(Err(VerificationError::AirVersionMismatch) = bundle.verify());
+    }
+
+    #[test]
+    fn bundled_proof_can_be_verified_after_loading() {
+        let program = triton_program!(read_io 1 push 2 mul write_io 1 halt);
+        let public_input = PublicInput::new(vec![BFieldElement::new(21)]);
+        let (parameters, claim, proof) = prove_with_low_security_level(
+            &program,
+            public_input,
+            NonDeterminism::default(),
+            &mut None,
+        );
+        let bundle = ProofBundle::new(parameters, claim, proof);
+
+        let_assert!(Ok(loaded) = ProofBundle::from_bytes(&bundle.to_bytes()));
+        assert!(crate::verify_bundle(&loaded));
+
+        let mut tampered = loaded;
+        tampered.claim.output[0] += BFieldElement::new(1);
+        assert!(!crate::verify_bundle(&tampered));
+    }
+}
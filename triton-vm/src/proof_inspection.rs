@@ -0,0 +1,378 @@
+//! A structured breakdown of a [`Proof`], useful for tracking proof sizes and for explaining
+//! where the size of a proof comes from. See [`Proof::inspect`].
+
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+
+use twenty_first::shared_math::b_field_element::BFieldElement;
+use twenty_first::shared_math::bfield_codec::BFieldCodec;
+
+use crate::error::ProofStreamError;
+use crate::proof::Proof;
+use crate::proof_item::ProofItem;
+use crate::stark::StarkProofStream;
+use crate::table::master_table::NUM_EXT_COLUMNS;
+
+/// The number of bytes required to store one [`BFieldElement`].
+const BYTES_PER_BFIELD_ELEMENT: usize = std::mem::size_of::<BFieldElement>();
+
+/// The number of Merkle roots in a proof that do not belong to FRI: one each for the master base
+/// table, the master extension table, and the quotient segments.
+const NUM_NON_FRI_MERKLE_ROOTS: usize = 3;
+
+/// A breakdown of a [`Proof`]'s size and the parameters implied by its shape.
+/// All sizes are given in number of [`BFieldElement`]s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofInspection {
+    /// The size of the entire proof.
+    pub total_size: usize,
+
+    /// The number of items and their accumulated size for every kind of [`ProofItem`] present in
+    /// the proof, in order of first appearance.
+    pub items: Vec<ProofItemSummary>,
+
+    /// The authentication structures of all Merkle trees, in order of appearance.
+    pub authentication_structures: Vec<AuthenticationStructureSummary>,
+
+    /// The revealed parts of the FRI rounds, one for every
+    /// [`FriResponse`](crate::proof_item::FriResponse) in the proof.
+    pub fri_rounds: Vec<FriRoundSummary>,
+
+    /// The padded height of the proven trace. See [`Proof::padded_height`].
+    pub padded_height: Option<usize>,
+
+    /// Whether the proof was generated in zero-knowledge mode.
+    pub zero_knowledge: Option<bool>,
+
+    /// The number of randomizer polynomials, derived from the width of the revealed extension
+    /// table rows.
+    pub num_randomizer_polynomials: Option<usize>,
+
+    /// The number of revealed rows of the master tables.
+    pub num_combination_codeword_checks: Option<usize>,
+
+    /// The number of FRI rounds, derived from the number of committed Merkle roots.
+    pub num_fri_rounds: Option<usize>,
+
+    /// The length of the codeword of FRI's last round.
+    pub fri_last_codeword_len: Option<usize>,
+
+    /// Whether the proof contains a proof-of-work.
+    pub has_proof_of_work: bool,
+}
+
+/// The number of [`ProofItem`]s of one kind and their accumulated size.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofItemSummary {
+    /// The name of the [`ProofItem`] variant.
+    pub name: String,
+    pub count: usize,
+    pub size: usize,
+}
+
+/// The Merkle tree an authentication structure belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MerkleTreeKind {
+    MasterBaseTable,
+    MasterExtTable,
+    QuotientSegments,
+    FriRound(usize),
+}
+
+/// The size of one authentication structure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuthenticationStructureSummary {
+    pub tree: MerkleTreeKind,
+    pub num_digests: usize,
+    pub size: usize,
+}
+
+/// The revealed parts of one FRI round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FriRoundSummary {
+    pub round: usize,
+    pub num_revealed_leaves: usize,
+    pub num_authentication_digests: usize,
+    /// The size of the round's entire [`FriResponse`](crate::proof_item::FriResponse).
+    pub size: usize,
+}
+
+impl ProofInspection {
+    pub(crate) fn new(proof: &Proof) -> Result<Self, ProofStreamError> {
+        let proof_stream = StarkProofStream::try_from(proof)?;
+        let mut inspection = Self {
+            total_size: proof.0.len(),
+            items: vec![],
+            authentication_structures: vec![],
+            fri_rounds: vec![],
+            padded_height: None,
+            zero_knowledge: None,
+            num_randomizer_polynomials: None,
+            num_combination_codeword_checks: None,
+            num_fri_rounds: None,
+            fri_last_codeword_len: None,
+            has_proof_of_work: false,
+        };
+
+        let mut num_merkle_roots: usize = 0;
+        let mut tree_of_next_authentication_structure = None;
+        for item in &proof_stream.items {
+            inspection.record_item_size(item);
+            match item {
+                ProofItem::ZeroKnowledge(zero_knowledge) => {
+                    inspection.zero_knowledge = Some(*zero_knowledge)
+                }
+                ProofItem::Log2PaddedHeight(log_2_padded_height) => {
+                    inspection.padded_height = Some(1 << log_2_padded_height)
+                }
+                ProofItem::MasterBaseTableRows(rows) => {
+                    inspection.num_combination_codeword_checks = Some(rows.len());
+                    tree_of_next_authentication_structure = Some(MerkleTreeKind::MasterBaseTable);
+                }
+                ProofItem::MasterExtTableRows(rows) => {
+                    inspection.num_randomizer_polynomials = rows
+                        .first()
+                        .map(|row| row.len().saturating_sub(NUM_EXT_COLUMNS));
+                    tree_of_next_authentication_structure = Some(MerkleTreeKind::MasterExtTable);
+                }
+                ProofItem::QuotientSegmentsElements(_) => {
+                    tree_of_next_authentication_structure = Some(MerkleTreeKind::QuotientSegments);
+                }
+                ProofItem::AuthenticationStructure(auth_structure) => {
+                    if let Some(tree) = tree_of_next_authentication_structure.take() {
+                        inspection
+                            .authentication_structures
+                            .push(AuthenticationStructureSummary {
+                                tree,
+                                num_digests: auth_structure.len(),
+                                size: auth_structure.encode().len(),
+                            });
+                    }
+                }
+                ProofItem::MerkleRoot(_) => num_merkle_roots += 1,
+                ProofItem::FriCodeword(codeword) => {
+                    inspection.fri_last_codeword_len = Some(codeword.len())
+                }
+                ProofItem::FriResponse(response) => {
+                    let round = inspection.fri_rounds.len();
+                    let auth_structure = &response.auth_structure;
+                    inspection
+                        .authentication_structures
+                        .push(AuthenticationStructureSummary {
+                            tree: MerkleTreeKind::FriRound(round),
+                            num_digests: auth_structure.len(),
+                            size: auth_structure.encode().len(),
+                        });
+                    inspection.fri_rounds.push(FriRoundSummary {
+                        round,
+                        num_revealed_leaves: response.revealed_leaves.len(),
+                        num_authentication_digests: auth_structure.len(),
+                        size: item.encode().len(),
+                    });
+                }
+                ProofItem::ProofOfWork(_) => inspection.has_proof_of_work = true,
+                _ => (),
+            }
+        }
+
+        // FRI commits to one Merkle root more than it has rounds: the one of the last codeword.
+        inspection.num_fri_rounds = num_merkle_roots.checked_sub(NUM_NON_FRI_MERKLE_ROOTS + 1);
+        Ok(inspection)
+    }
+
+    fn record_item_size(&mut self, item: &ProofItem) {
+        let name = item.to_string();
+        let size = item.encode().len();
+        match self.items.iter_mut().find(|summary| summary.name == name) {
+            Some(summary) => {
+                summary.count += 1;
+                summary.size += size;
+            }
+            None => self.items.push(ProofItemSummary {
+                name,
+                count: 1,
+                size,
+            }),
+        }
+    }
+
+    /// The share of the given size in the total size of the proof, in percent.
+    fn percentage(&self, size: usize) -> f64 {
+        match self.total_size {
+            0 => 0.0,
+            total_size => (size as f64) / (total_size as f64) * 100.0,
+        }
+    }
+}
+
+impl Display for MerkleTreeKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            MerkleTreeKind::MasterBaseTable => write!(f, "master base table"),
+            MerkleTreeKind::MasterExtTable => write!(f, "master extension table"),
+            MerkleTreeKind::QuotientSegments => write!(f, "quotient segments"),
+            MerkleTreeKind::FriRound(round) => write!(f, "FRI round {round}"),
+        }
+    }
+}
+
+impl Display for ProofInspection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let total_size = self.total_size;
+        let num_bytes = total_size * BYTES_PER_BFIELD_ELEMENT;
+        writeln!(
+            f,
+            "Proof of {total_size} field elements ({num_bytes} bytes)"
+        )?;
+        writeln!(f)?;
+
+        let mut items = self.items.clone();
+        items.sort_by_key(|summary| std::cmp::Reverse(summary.size));
+        writeln!(
+            f,
+            "| {:<30} | {:>5} | {:>10} | {:>6} |",
+            "Proof item", "Count", "Size [bfe]", "[%]"
+        )?;
+        writeln!(f, "|:{:-<30}-|-{:->5}:|-{:->10}:|-{:->6}:|", "", "", "", "")?;
+        for ProofItemSummary { name, count, size } in items {
+            let percentage = self.percentage(size);
+            writeln!(
+                f,
+                "| {name:<30} | {count:>5} | {size:>10} | {percentage:>6.2} |"
+            )?;
+        }
+        writeln!(f)?;
+
+        writeln!(
+            f,
+            "| {:<30} | {:>7} | {:>10} | {:>6} |",
+            "Authentication structure", "Digests", "Size [bfe]", "[%]"
+        )?;
+        writeln!(f, "|:{:-<30}-|-{:->7}:|-{:->10}:|-{:->6}:|", "", "", "", "")?;
+        for summary in &self.authentication_structures {
+            let tree = summary.tree.to_string();
+            let num_digests = summary.num_digests;
+            let size = summary.size;
+            let percentage = self.percentage(size);
+            writeln!(
+                f,
+                "| {tree:<30} | {num_digests:>7} | {size:>10} | {percentage:>6.2} |"
+            )?;
+        }
+        writeln!(f)?;
+
+        writeln!(
+            f,
+            "| {:<9} | {:>6} | {:>7} | {:>10} | {:>6} |",
+            "FRI round", "Leaves", "Digests", "Size [bfe]", "[%]"
+        )?;
+        writeln!(
+            f,
+            "|:{:-<9}-|-{:->6}:|-{:->7}:|-{:->10}:|-{:->6}:|",
+            "", "", "", "", ""
+        )?;
+        for summary in &self.fri_rounds {
+            let round = summary.round;
+            let num_leaves = summary.num_revealed_leaves;
+            let num_digests = summary.num_authentication_digests;
+            let size = summary.size;
+            let percentage = self.percentage(size);
+            writeln!(
+                f,
+                "| {round:>9} | {num_leaves:>6} | {num_digests:>7} | {size:>10} | {percentage:>6.2} |"
+            )?;
+        }
+        writeln!(f)?;
+
+        let display = |value: Option<usize>| match value {
+            Some(value) => value.to_string(),
+            None => "unknown".to_string(),
+        };
+        let zero_knowledge = match self.zero_knowledge {
+            Some(zero_knowledge) => zero_knowledge.to_string(),
+            None => "unknown".to_string(),
+        };
+        writeln!(f, "Implied parameters:")?;
+        writeln!(f, "- padded height: {}", display(self.padded_height))?;
+        writeln!(f, "- zero-knowledge: {zero_knowledge}")?;
+        writeln!(
+            f,
+            "- randomizer polynomials: {}",
+            display(self.num_randomizer_polynomials)
+        )?;
+        writeln!(
+            f,
+            "- combination codeword checks: {}",
+            display(self.num_combination_codeword_checks)
+        )?;
+        writeln!(f, "- FRI rounds: {}", display(self.num_fri_rounds))?;
+        writeln!(
+            f,
+            "- FRI last codeword length: {}",
+            display(self.fri_last_codeword_len)
+        )?;
+        write!(f, "- proof-of-work: {}", self.has_proof_of_work)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::assert;
+    use assert2::let_assert;
+
+    use crate::shared_tests::*;
+    use crate::stark::Stark;
+    use crate::triton_program;
+    use crate::NonDeterminism;
+    use crate::PublicInput;
+
+    use super::*;
+
+    #[test]
+    fn inspection_of_proof_agrees_with_parameters() {
+        let program = triton_program!(read_io 1 push 3 mul write_io 1 halt);
+        let public_input = PublicInput::new(vec![BFieldElement::new(14)]);
+        let (parameters, _, proof) = prove_with_low_security_level(
+            &program,
+            public_input,
+            NonDeterminism::default(),
+            &mut None,
+        );
+        let_assert!(Ok(inspection) = proof.inspect());
+        let padded_height = proof.padded_height().unwrap();
+        let fri = Stark::derive_fri(parameters, padded_height);
+
+        assert!(proof.0.len() == inspection.total_size);
+        assert!(Some(padded_height) == inspection.padded_height);
+        assert!(Some(parameters.zero_knowledge) == inspection.zero_knowledge);
+        assert!(
+            Some(parameters.num_randomizer_polynomials) == inspection.num_randomizer_polynomials
+        );
+        assert!(
+            Some(parameters.num_combination_codeword_checks)
+                == inspection.num_combination_codeword_checks
+        );
+        assert!(Some(fri.num_rounds()) == inspection.num_fri_rounds);
+        assert!(fri.num_rounds().max(1) == inspection.fri_rounds.len());
+        assert!(inspection.fri_rounds.len() + 3 == inspection.authentication_structures.len());
+        assert!((parameters.num_grinding_bits > 0) == inspection.has_proof_of_work);
+
+        let sum_of_item_sizes: usize = inspection.items.iter().map(|item| item.size).sum();
+        assert!(sum_of_item_sizes <= inspection.total_size);
+
+        let rendered = inspection.to_string();
+        assert!(rendered.contains("MerkleRoot"));
+        assert!(rendered.contains("FRI round 0"));
+        assert!(rendered.contains(&format!("padded height: {padded_height}")));
+    }
+
+    #[test]
+    fn inspecting_proof_without_items_gives_empty_inspection() {
+        let proof: Proof = StarkProofStream::new().into();
+        let_assert!(Ok(inspection) = proof.inspect());
+        assert!(inspection.items.is_empty());
+        assert!(inspection.padded_height.is_none());
+        assert!(!inspection.to_string().is_empty());
+    }
+}
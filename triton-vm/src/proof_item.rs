@@ -37,6 +37,8 @@ pub enum ProofItem {
     QuotientSegmentsElements(Vec<[XFieldElement; NUM_QUOTIENT_SEGMENTS]>),
     FriCodeword(Vec<XFieldElement>),
     FriResponse(FriResponse),
+    ZeroKnowledge(bool),
+    ProofOfWork(u64),
 }
 
 impl ProofItem {
@@ -55,6 +57,7 @@ impl ProofItem {
             OutOfDomainBaseRow(_) => true,
             OutOfDomainExtRow(_) => true,
             OutOfDomainQuotientSegments(_) => true,
+            ProofOfWork(_) => true,
             // all of the following are implied by a corresponding Merkle root
             AuthenticationStructure(_) => false,
             MasterBaseTableRows(_) => false,
@@ -63,6 +66,8 @@ impl ProofItem {
             QuotientSegmentsElements(_) => false,
             FriCodeword(_) => false,
             FriResponse(_) => false,
+            // checked against the verifier's parameters
+            ZeroKnowledge(_) => false,
         }
     }
 
@@ -149,6 +154,20 @@ impl ProofItem {
             other => Err(UnexpectedItem("FRI proof", other.to_owned())),
         }
     }
+
+    pub fn as_zero_knowledge(&self) -> Result<bool> {
+        match self {
+            Self::ZeroKnowledge(zero_knowledge) => Ok(*zero_knowledge),
+            other => Err(UnexpectedItem("zero-knowledge flag", other.to_owned())),
+        }
+    }
+
+    pub fn as_proof_of_work(&self) -> Result<u64> {
+        match self {
+            Self::ProofOfWork(nonce) => Ok(*nonce),
+            other => Err(UnexpectedItem("proof-of-work nonce", other.to_owned())),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -263,5 +282,7 @@ mod tests {
         assert!(let Err(UnexpectedItem(_, _)) = proof_item.as_quotient_segments_elements());
         assert!(let Err(UnexpectedItem(_, _)) = proof_item.as_fri_codeword());
         assert!(let Err(UnexpectedItem(_, _)) = proof_item.as_fri_response());
+        assert!(let Err(UnexpectedItem(_, _)) = proof_item.as_zero_knowledge());
+        assert!(let Err(UnexpectedItem(_, _)) = proof_item.as_proof_of_work());
     }
 }
@@ -0,0 +1,302 @@
+//! Read the [`ProofItem`]s of a [`Proof`] lazily from any [`Read`]er.
+//!
+//! Decoding a [`Proof`] into a [`ProofStream`] holds the entire proof in memory twice before the
+//! verifier even looks at the first item. A [`ProofReader`] instead reads and decodes one item at
+//! a time, exactly when the verifier [dequeues](VerifierProofStream::dequeue) it. This way,
+//! verification stops at the first malformed or failing item without reading the rest of the
+//! proof. See [`Stark::verify_from_reader`](crate::stark::Stark::verify_from_reader).
+//!
+//! [`Proof`]: crate::proof::Proof
+
+use std::io::ErrorKind;
+use std::io::Read;
+
+use twenty_first::shared_math::b_field_element::BFieldElement;
+use twenty_first::shared_math::bfield_codec::BFieldCodec;
+use twenty_first::shared_math::x_field_element::XFieldElement;
+use twenty_first::util_types::algebraic_hasher::AlgebraicHasher;
+
+use crate::error::ProofStreamError;
+use crate::error::ProofStreamError::*;
+use crate::proof_item::ProofItem;
+use crate::proof_stream::ProofStream;
+use crate::proof_stream::VerifierProofStream;
+
+/// The number of bytes used to encode one [`BFieldElement`].
+const BYTES_PER_ELEMENT: usize = 8;
+
+/// The default maximum number of field elements a single [`ProofItem`] may be encoded with.
+/// Considerably larger than any item of an honestly generated proof.
+pub const DEFAULT_MAX_ITEM_LEN: usize = 1 << 20;
+
+/// Reads the [`ProofItem`]s of a proof lazily, one item per call to
+/// [`dequeue`](VerifierProofStream::dequeue). The proof is expected in the format produced by
+/// [`Proof::to_bytes`](crate::proof::Proof::to_bytes).
+///
+/// Every length prefix is checked against the length of the remaining proof as well as the
+/// [maximum item length](Self::with_max_item_len) before the item is read. Memory is only ever
+/// allocated for bytes that were actually read, never based on a length prefix alone.
+#[derive(Debug)]
+pub struct ProofReader<R, H>
+where
+    H: AlgebraicHasher,
+{
+    reader: R,
+
+    /// Holds the Fiat-Shamir sponge state, but never any items.
+    proof_stream: ProofStream<H>,
+
+    max_item_len: usize,
+
+    /// `None` until the proof's header has been read.
+    num_remaining_items: Option<u64>,
+
+    num_remaining_elements: u64,
+}
+
+impl<R, H> ProofReader<R, H>
+where
+    R: Read,
+    H: AlgebraicHasher,
+{
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            proof_stream: ProofStream::new(),
+            max_item_len: DEFAULT_MAX_ITEM_LEN,
+            num_remaining_items: None,
+            num_remaining_elements: 0,
+        }
+    }
+
+    /// Set the maximum number of field elements a single item may be encoded with. Items with a
+    /// longer length prefix are rejected before they are read. The default is
+    /// [`DEFAULT_MAX_ITEM_LEN`].
+    #[must_use]
+    pub fn with_max_item_len(mut self, max_item_len: usize) -> Self {
+        self.max_item_len = max_item_len;
+        self
+    }
+
+    /// Read the length of the proof and the number of items it contains.
+    fn read_header(&mut self) -> Result<u64, ProofStreamError> {
+        let proof_len = self.read_element()?.value();
+        let num_items = self.read_element()?.value();
+
+        // Every item takes up at least one element: its length prefix.
+        let Some(num_remaining_elements) = proof_len.checked_sub(1) else {
+            return Err(InconsistentProofLength(proof_len, num_items));
+        };
+        if num_items > num_remaining_elements {
+            return Err(InconsistentProofLength(proof_len, num_items));
+        }
+
+        self.num_remaining_elements = num_remaining_elements;
+        Ok(num_items)
+    }
+
+    fn read_item(&mut self) -> Result<ProofItem, ProofStreamError> {
+        let num_remaining_items = match self.num_remaining_items {
+            Some(num_remaining_items) => num_remaining_items,
+            None => self.read_header()?,
+        };
+        self.num_remaining_items = Some(num_remaining_items);
+        if num_remaining_items == 0 {
+            return Err(EmptyQueue);
+        }
+
+        // Leave at least one element for the length prefix of each of the remaining items.
+        let num_elements_available = self.num_remaining_elements - num_remaining_items;
+        let max_item_len = num_elements_available.min(self.max_item_len as u64);
+        let item_len = self.read_element()?.value();
+        if item_len > max_item_len {
+            return Err(ItemTooLong(item_len as usize, max_item_len as usize));
+        }
+
+        let encoding = self.read_elements(item_len as usize)?;
+        self.num_remaining_elements -= 1 + item_len;
+        self.num_remaining_items = Some(num_remaining_items - 1);
+        Ok(*ProofItem::decode(&encoding)?)
+    }
+
+    fn read_element(&mut self) -> Result<BFieldElement, ProofStreamError> {
+        let mut bytes = [0; BYTES_PER_ELEMENT];
+        self.reader.read_exact(&mut bytes)?;
+        Self::element_from_bytes(bytes)
+    }
+
+    fn read_elements(
+        &mut self,
+        num_elements: usize,
+    ) -> Result<Vec<BFieldElement>, ProofStreamError> {
+        let num_bytes = num_elements * BYTES_PER_ELEMENT;
+        let mut bytes = vec![];
+        let mut reader = self.reader.by_ref().take(num_bytes as u64);
+        reader.read_to_end(&mut bytes)?;
+        if bytes.len() < num_bytes {
+            return Err(ReadError(ErrorKind::UnexpectedEof.into()));
+        }
+
+        bytes
+            .chunks_exact(BYTES_PER_ELEMENT)
+            .map(|chunk| Self::element_from_bytes(chunk.try_into().unwrap()))
+            .collect()
+    }
+
+    fn element_from_bytes(
+        bytes: [u8; BYTES_PER_ELEMENT],
+    ) -> Result<BFieldElement, ProofStreamError> {
+        let value = u64::from_le_bytes(bytes);
+        if value >= BFieldElement::P {
+            return Err(NonCanonicalFieldElement(value));
+        }
+        Ok(BFieldElement::new(value))
+    }
+}
+
+impl<R, H> VerifierProofStream for ProofReader<R, H>
+where
+    R: Read,
+    H: AlgebraicHasher,
+{
+    fn dequeue(&mut self) -> Result<ProofItem, ProofStreamError> {
+        let item = self.read_item()?;
+        self.proof_stream.absorb_proof_item(&item);
+        Ok(item)
+    }
+
+    fn alter_fiat_shamir_state_with<T: BFieldCodec>(&mut self, item: &T) {
+        self.proof_stream.alter_fiat_shamir_state_with(item)
+    }
+
+    fn sample_indices(&mut self, upper_bound: usize, num_indices: usize) -> Vec<usize> {
+        self.proof_stream.sample_indices(upper_bound, num_indices)
+    }
+
+    fn sample_scalars(&mut self, num_scalars: usize) -> Vec<XFieldElement> {
+        self.proof_stream.sample_scalars(num_scalars)
+    }
+
+    fn has_sufficient_proof_of_work(&self, num_grinding_bits: usize) -> bool {
+        self.proof_stream
+            .has_sufficient_proof_of_work(num_grinding_bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::assert;
+    use assert2::let_assert;
+    use proptest_arbitrary_interop::arb;
+    use test_strategy::proptest;
+    use twenty_first::shared_math::tip5::Tip5;
+
+    use crate::error::VerificationError;
+    use crate::proof::Proof;
+    use crate::shared_tests::*;
+    use crate::stark::Stark;
+    use crate::stark::StarkProofStream;
+    use crate::triton_program;
+    use crate::NonDeterminism;
+    use crate::PublicInput;
+
+    use super::*;
+
+    fn proof_from_items(items: Vec<ProofItem>) -> Proof {
+        let mut proof_stream = ProofStream::<Tip5>::new();
+        items
+            .into_iter()
+            .for_each(|item| proof_stream.enqueue(item));
+        proof_stream.into()
+    }
+
+    #[proptest(cases = 20)]
+    fn reading_items_lazily_gives_items_of_decoded_proof(#[strategy(arb())] items: Vec<ProofItem>) {
+        let proof = proof_from_items(items.clone());
+        let bytes = proof.to_bytes();
+        let mut proof_reader = ProofReader::<_, Tip5>::new(bytes.as_slice());
+        for item in items {
+            let_assert!(Ok(read_item) = proof_reader.dequeue());
+            assert!(item == read_item);
+        }
+        let_assert!(Err(EmptyQueue) = proof_reader.dequeue());
+    }
+
+    #[test]
+    fn item_with_too_long_length_prefix_is_rejected_before_it_is_read() {
+        let proof = proof_from_items(vec![ProofItem::FriCodeword(vec![])]);
+        let Proof(mut elements) = proof;
+        let item_len_index = 2;
+        let item_len = elements[item_len_index].value() as usize;
+        elements[item_len_index] = BFieldElement::new(u32::MAX.into());
+        let bytes = Proof(elements).to_bytes();
+
+        let mut proof_reader = ProofReader::<_, Tip5>::new(bytes.as_slice());
+        let_assert!(Err(ItemTooLong(_, max_item_len)) = proof_reader.dequeue());
+        assert!(item_len == max_item_len);
+    }
+
+    #[test]
+    fn item_exceeding_maximum_item_length_is_rejected() {
+        let item =
+            ProofItem::FriCodeword(vec![XFieldElement::new_const(BFieldElement::new(1)); 10]);
+        let bytes = proof_from_items(vec![item]).to_bytes();
+        let mut proof_reader = ProofReader::<_, Tip5>::new(bytes.as_slice()).with_max_item_len(5);
+        let_assert!(Err(ItemTooLong(_, 5)) = proof_reader.dequeue());
+    }
+
+    #[test]
+    fn truncated_proof_is_rejected() {
+        let bytes = proof_from_items(vec![ProofItem::Log2PaddedHeight(8)]).to_bytes();
+        let truncated_bytes = &bytes[..bytes.len() - 1];
+        let mut proof_reader = ProofReader::<_, Tip5>::new(truncated_bytes);
+        let_assert!(Err(ReadError(error)) = proof_reader.dequeue());
+        assert!(ErrorKind::UnexpectedEof == error.kind());
+    }
+
+    #[test]
+    fn proof_with_non_canonical_field_element_is_rejected() {
+        let mut bytes = proof_from_items(vec![ProofItem::Log2PaddedHeight(8)]).to_bytes();
+        let last_element_start = bytes.len() - BYTES_PER_ELEMENT;
+        bytes[last_element_start..].copy_from_slice(&u64::MAX.to_le_bytes());
+        let mut proof_reader = ProofReader::<_, Tip5>::new(bytes.as_slice());
+        let_assert!(Err(NonCanonicalFieldElement(u64::MAX)) = proof_reader.dequeue());
+    }
+
+    #[test]
+    fn proof_claiming_more_items_than_it_can_hold_is_rejected() {
+        let num_items = 1 << 40;
+        let bytes = Proof(vec![BFieldElement::new(1), BFieldElement::new(num_items)]).to_bytes();
+        let mut proof_reader = ProofReader::<_, Tip5>::new(bytes.as_slice());
+        let_assert!(Err(InconsistentProofLength(1, _)) = proof_reader.dequeue());
+    }
+
+    #[test]
+    fn honest_proof_read_lazily_verifies_and_early_failure_stops_reading() {
+        let program = triton_program!(read_io 1 push 2 mul write_io 1 halt);
+        let public_input = PublicInput::new(vec![BFieldElement::new(21)]);
+        let (parameters, claim, proof) = prove_with_low_security_level(
+            &program,
+            public_input,
+            NonDeterminism::default(),
+            &mut None,
+        );
+        let bytes = proof.to_bytes();
+        let_assert!(
+            Ok(()) = Stark::verify_from_reader(parameters, &claim, bytes.as_slice(), &mut None)
+        );
+
+        let_assert!(Ok(mut proof_stream) = StarkProofStream::try_from(&proof));
+        let_assert!(Ok(zero_knowledge) = proof_stream.items[0].as_zero_knowledge());
+        proof_stream.items[0] = ProofItem::ZeroKnowledge(!zero_knowledge);
+        let bytes = Proof::from(proof_stream).to_bytes();
+
+        let mut unread_bytes = bytes.as_slice();
+        let verdict = Stark::verify_from_reader(parameters, &claim, &mut unread_bytes, &mut None);
+        let_assert!(Err(VerificationError::ZeroKnowledgeMismatch) = verdict);
+
+        let num_elements_read = 5;
+        let num_bytes_read = num_elements_read * BYTES_PER_ELEMENT;
+        assert!(bytes.len() - num_bytes_read == unread_bytes.len());
+    }
+}
@@ -3,6 +3,8 @@ use twenty_first::shared_math::b_field_element::BFieldElement;
 use twenty_first::shared_math::b_field_element::BFIELD_ONE;
 use twenty_first::shared_math::b_field_element::BFIELD_ZERO;
 use twenty_first::shared_math::bfield_codec::BFieldCodec;
+use twenty_first::shared_math::digest::Digest;
+use twenty_first::shared_math::digest::DIGEST_LENGTH;
 use twenty_first::shared_math::other::is_power_of_two;
 use twenty_first::shared_math::x_field_element::XFieldElement;
 use twenty_first::util_types::algebraic_hasher::AlgebraicHasher;
@@ -10,6 +12,8 @@ use twenty_first::util_types::algebraic_hasher::AlgebraicHasher;
 use crate::error::ProofStreamError;
 use crate::proof::Proof;
 use crate::proof_item::ProofItem;
+use crate::transcript::Transcript;
+use crate::transcript::TranscriptStep;
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Arbitrary, BFieldCodec)]
 pub struct ProofStream<H>
@@ -23,6 +27,11 @@ where
 
     #[bfield_codec(ignore)]
     pub sponge_state: H::SpongeState,
+
+    /// If set, records every interaction with the Fiat-Shamir sponge.
+    /// See [`with_transcript_recording`](Self::with_transcript_recording).
+    #[bfield_codec(ignore)]
+    pub transcript: Option<Transcript>,
 }
 
 impl<H> ProofStream<H>
@@ -34,6 +43,29 @@ where
             items: vec![],
             items_index: 0,
             sponge_state: H::init(),
+            transcript: None,
+        }
+    }
+
+    /// Record every item absorbed into the Fiat-Shamir sponge as well as all sampled scalars and
+    /// indices in the proof stream's [`transcript`](Self::transcript). Comparing the prover's
+    /// transcript with the verifier's using [`Transcript::first_divergence`] points to the first
+    /// step in which they disagree.
+    #[must_use]
+    pub fn with_transcript_recording(self) -> Self {
+        let transcript = Some(Transcript::default());
+        Self { transcript, ..self }
+    }
+
+    /// Append a step to the transcript, if recording is enabled.
+    fn record(&mut self, step: impl FnOnce(Digest) -> TranscriptStep) {
+        if self.transcript.is_none() {
+            return;
+        }
+        let squeezed = H::squeeze(&mut self.sponge_state.clone());
+        let sponge_state_digest = Digest::new(squeezed[..DIGEST_LENGTH].try_into().unwrap());
+        if let Some(transcript) = self.transcript.as_mut() {
+            transcript.steps.push(step(sponge_state_digest));
         }
     }
 
@@ -64,11 +96,20 @@ where
     /// [`Claim`](crate::proof::Claim).
     ///
     /// See also [`Self::enqueue()`] and [`Self::dequeue()`].
-    pub fn alter_fiat_shamir_state_with(&mut self, item: &impl BFieldCodec) {
+    pub fn alter_fiat_shamir_state_with<T: BFieldCodec>(&mut self, item: &T) {
+        self.absorb(item, || std::any::type_name::<T>().to_string());
+    }
+
+    fn absorb(&mut self, item: &impl BFieldCodec, item_name: impl FnOnce() -> String) {
         H::absorb_repeatedly(
             &mut self.sponge_state,
             Self::encode_and_pad_item(item).iter(),
-        )
+        );
+        self.record(|sponge_state_digest| TranscriptStep::Absorb {
+            item_name: item_name(),
+            encoded_len: item.encode().len(),
+            sponge_state_digest,
+        });
     }
 
     /// Send a proof item as prover to verifier.
@@ -82,9 +123,7 @@ where
     /// - If the proof stream is not used to sample any more randomness, _i.e._, after the last
     ///     round of interaction, no further items need to be hashed.
     pub fn enqueue(&mut self, item: ProofItem) {
-        if item.include_in_fiat_shamir_heuristic() {
-            self.alter_fiat_shamir_state_with(&item);
-        }
+        self.absorb_proof_item(&item);
         self.items.push(item);
     }
 
@@ -95,13 +134,19 @@ where
             return Err(ProofStreamError::EmptyQueue);
         };
         let item = item.to_owned();
-        if item.include_in_fiat_shamir_heuristic() {
-            self.alter_fiat_shamir_state_with(&item);
-        }
+        self.absorb_proof_item(&item);
         self.items_index += 1;
         Ok(item)
     }
 
+    /// Alters the Fiat-Shamir sponge state with the given item if, and only if, it is
+    /// [part of the heuristic](ProofItem::include_in_fiat_shamir_heuristic).
+    pub(crate) fn absorb_proof_item(&mut self, item: &ProofItem) {
+        if item.include_in_fiat_shamir_heuristic() {
+            self.absorb(item, || item.to_string());
+        }
+    }
+
     /// Given an `upper_bound` that is a power of 2, produce `num_indices` uniform random numbers
     /// in the interval `[0; upper_bound)`.
     ///
@@ -110,15 +155,64 @@ where
     pub fn sample_indices(&mut self, upper_bound: usize, num_indices: usize) -> Vec<usize> {
         assert!(is_power_of_two(upper_bound));
         assert!(upper_bound <= BFieldElement::MAX as usize);
-        H::sample_indices(&mut self.sponge_state, upper_bound as u32, num_indices)
-            .into_iter()
-            .map(|i| i as usize)
-            .collect()
+        let indices: Vec<_> =
+            H::sample_indices(&mut self.sponge_state, upper_bound as u32, num_indices)
+                .into_iter()
+                .map(|i| i as usize)
+                .collect();
+        self.record(|sponge_state_digest| TranscriptStep::SampleIndices {
+            upper_bound,
+            indices: indices.clone(),
+            sponge_state_digest,
+        });
+        indices
+    }
+
+    /// Perform proof-of-work, also known as “grinding”: find a nonce such that the sponge state,
+    /// after absorbing the nonce, produces an element with `num_grinding_bits` many leading zeros.
+    /// The nonce is [enqueued](Self::enqueue) as a [`ProofItem::ProofOfWork`].
+    ///
+    /// Grinding right before sampling indices makes it expensive for a cheating prover to re-try
+    /// the sampling until the indices are favorable. Every bit of grinding adds one bit of
+    /// security, allowing to reduce the number of queries accordingly.
+    ///
+    /// See also [`Self::has_sufficient_proof_of_work()`].
+    pub fn grind(&mut self, num_grinding_bits: usize) {
+        assert!(
+            num_grinding_bits < 64,
+            "cannot grind for {num_grinding_bits} bits"
+        );
+        let nonce = (0_u64..)
+            .find(|&nonce| {
+                let mut sponge_state = self.sponge_state.clone();
+                let item = ProofItem::ProofOfWork(nonce);
+                H::absorb_repeatedly(&mut sponge_state, Self::encode_and_pad_item(&item).iter());
+                Self::has_leading_zeros(sponge_state, num_grinding_bits)
+            })
+            .unwrap();
+        self.enqueue(ProofItem::ProofOfWork(nonce));
+    }
+
+    /// Whether the sponge state is the result of absorbing a nonce with sufficient proof-of-work.
+    /// Should be called by the verifier right after [dequeuing](Self::dequeue) the
+    /// [`ProofItem::ProofOfWork`]. See also [`Self::grind()`].
+    pub fn has_sufficient_proof_of_work(&self, num_grinding_bits: usize) -> bool {
+        Self::has_leading_zeros(self.sponge_state.clone(), num_grinding_bits)
+    }
+
+    fn has_leading_zeros(mut sponge_state: H::SpongeState, num_leading_zeros: usize) -> bool {
+        let [first_element, ..] = H::squeeze(&mut sponge_state);
+        first_element.value().leading_zeros() as usize >= num_leading_zeros
     }
 
     /// A thin wrapper around [`H::sample_scalars`](AlgebraicHasher::sample_scalars).
     pub fn sample_scalars(&mut self, num_scalars: usize) -> Vec<XFieldElement> {
-        H::sample_scalars(&mut self.sponge_state, num_scalars)
+        let scalars = H::sample_scalars(&mut self.sponge_state, num_scalars);
+        self.record(|sponge_state_digest| TranscriptStep::SampleScalars {
+            scalars: scalars.clone(),
+            sponge_state_digest,
+        });
+        scalars
     }
 }
 
@@ -152,6 +246,53 @@ where
     }
 }
 
+/// The verifier's view of a proof stream: [`ProofItem`]s are received in the order the prover
+/// sent them, and randomness is sampled from the Fiat-Shamir sponge.
+///
+/// Implemented by [`ProofStream`], which holds all items of a fully decoded proof, and by
+/// [`ProofReader`](crate::proof_reader::ProofReader), which reads items lazily.
+pub trait VerifierProofStream {
+    /// See [`ProofStream::dequeue`].
+    fn dequeue(&mut self) -> Result<ProofItem, ProofStreamError>;
+
+    /// See [`ProofStream::alter_fiat_shamir_state_with`].
+    fn alter_fiat_shamir_state_with<T: BFieldCodec>(&mut self, item: &T);
+
+    /// See [`ProofStream::sample_indices`].
+    fn sample_indices(&mut self, upper_bound: usize, num_indices: usize) -> Vec<usize>;
+
+    /// See [`ProofStream::sample_scalars`].
+    fn sample_scalars(&mut self, num_scalars: usize) -> Vec<XFieldElement>;
+
+    /// See [`ProofStream::has_sufficient_proof_of_work`].
+    fn has_sufficient_proof_of_work(&self, num_grinding_bits: usize) -> bool;
+}
+
+impl<H> VerifierProofStream for ProofStream<H>
+where
+    H: AlgebraicHasher,
+{
+    fn dequeue(&mut self) -> Result<ProofItem, ProofStreamError> {
+        ProofStream::dequeue(self)
+    }
+
+    fn alter_fiat_shamir_state_with<T: BFieldCodec>(&mut self, item: &T) {
+        ProofStream::alter_fiat_shamir_state_with(self, item)
+    }
+
+    fn sample_indices(&mut self, upper_bound: usize, num_indices: usize) -> Vec<usize> {
+        ProofStream::sample_indices(self, upper_bound, num_indices)
+    }
+
+    fn sample_scalars(&mut self, num_scalars: usize) -> Vec<XFieldElement> {
+        ProofStream::sample_scalars(self, num_scalars)
+    }
+
+    fn has_sufficient_proof_of_work(&self, num_grinding_bits: usize) -> bool {
+        ProofStream::has_sufficient_proof_of_work(self, num_grinding_bits)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::VecDeque;
@@ -357,6 +498,29 @@ mod tests {
         let_assert!(Err(ProofStreamError::EmptyQueue) = proof_stream.dequeue());
     }
 
+    #[test]
+    fn grinding_produces_sufficient_proof_of_work() {
+        let num_grinding_bits = 10;
+        let mut proof_stream = ProofStream::<Tip5>::new();
+        proof_stream.enqueue(ProofItem::Log2PaddedHeight(7));
+        proof_stream.grind(num_grinding_bits);
+        let proof = proof_stream.into();
+
+        let_assert!(Ok(mut proof_stream) = ProofStream::<Tip5>::try_from(&proof));
+        let_assert!(Ok(_) = proof_stream.dequeue());
+        let_assert!(Ok(proof_item) = proof_stream.dequeue());
+        let_assert!(Ok(nonce) = proof_item.as_proof_of_work());
+        assert!(proof_stream.has_sufficient_proof_of_work(num_grinding_bits));
+
+        // Grinding finds the smallest sufficient nonce.
+        for insufficient_nonce in 0..nonce {
+            let mut other_proof_stream = ProofStream::<Tip5>::new();
+            other_proof_stream.enqueue(ProofItem::Log2PaddedHeight(7));
+            other_proof_stream.enqueue(ProofItem::ProofOfWork(insufficient_nonce));
+            assert!(!other_proof_stream.has_sufficient_proof_of_work(num_grinding_bits));
+        }
+    }
+
     #[test]
     fn encoded_length_of_prove_stream_is_not_known_at_compile_time() {
         assert!(ProofStream::<Tip5>::static_length().is_none());
@@ -0,0 +1,1460 @@
+//! A verifier for Triton VM proofs, written in Triton assembly – the key to recursion.
+//!
+//! The [`RecursiveVerifier`] generates a [`Program`] that performs the same checks as
+//! [`Stark::verify`]. The program is specialized to one set of [`StarkParameters`] and one
+//! padded height. It reads the [`Claim`] from public input and receives the [`Proof`] through
+//! non-determinism:
+//! - the content of proof items is [divined](AnInstruction::Divine),
+//! - the Fiat-Shamir heuristic is implemented using the sponge instructions,
+//! - Merkle authentication paths are checked using
+//!   [`divine_sibling`](AnInstruction::DivineSibling), and
+//! - the AIR is evaluated out of domain using the Triton assembly generated by the constraint
+//!   evaluation generator, see [`TasmConstraintEvaluation`].
+//!
+//! The program halts gracefully if and only if the proof is valid for the claim. Use
+//! [`RecursiveVerifier::public_input`] and [`RecursiveVerifier::non_determinism`] to run it.
+//!
+//! Unlike [`Stark::verify`], the program does not re-sample FRI indices that stem from the
+//! field element `-1`, but crashes. For honest proofs, this happens with negligible probability.
+
+use itertools::Itertools;
+use num_traits::One;
+use num_traits::Zero;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+use twenty_first::shared_math::bfield_codec::BFieldCodec;
+use twenty_first::shared_math::tip5::Digest;
+use twenty_first::shared_math::tip5::DIGEST_LENGTH;
+use twenty_first::shared_math::tip5::LOOKUP_TABLE;
+use twenty_first::shared_math::traits::Inverse;
+use twenty_first::shared_math::traits::ModPowU32;
+use twenty_first::shared_math::x_field_element::XFieldElement;
+use twenty_first::shared_math::x_field_element::EXTENSION_DEGREE;
+use twenty_first::util_types::algebraic_hasher::AlgebraicHasher;
+use twenty_first::util_types::merkle_tree::MerkleTree;
+
+use crate::arithmetic_domain::ArithmeticDomain;
+use crate::error::FriValidationError;
+use crate::error::VerificationError;
+use crate::error::VerificationError::*;
+use crate::fri::Fri;
+#[cfg(doc)]
+use crate::instruction::AnInstruction;
+use crate::instruction::LabelledInstruction;
+use crate::proof::Claim;
+use crate::proof::Proof;
+use crate::proof_item::ProofItem;
+use crate::stark::Stark;
+use crate::stark::StarkHasher;
+use crate::stark::StarkParameters;
+use crate::stark::StarkProofStream;
+use crate::stark::NUM_QUOTIENT_SEGMENTS;
+use crate::table::challenges::ChallengeId;
+use crate::table::challenges::ChallengeId::*;
+use crate::table::challenges::Challenges;
+use crate::table::extension_table::Quotientable;
+use crate::table::extension_table::TasmConstraintEvaluation;
+use crate::table::extension_table::TasmConstraintEvaluationMemoryLayout;
+use crate::table::master_table::*;
+use crate::triton_asm;
+use crate::NonDeterminism;
+use crate::Program;
+use crate::PublicInput;
+
+/// The number of [`BFieldElement`]s the sponge absorbs or squeezes at once.
+const RATE: usize = 10;
+
+/// The number of [`BFieldElement`]s that can be [divined](AnInstruction::Divine) or written to
+/// memory using a single instruction.
+const MAX_WORDS_PER_INSTRUCTION: usize = 5;
+
+/// The number of pseudo-random [`XFieldElement`]s resulting from a single squeeze.
+const SCALARS_PER_SQUEEZE: usize = 3;
+
+const NUM_DEEP_CODEWORD_COMPONENTS: usize = 3;
+
+/// The address of the first word of the memory page with the given index. Every region of memory
+/// the recursive verifier uses lives on a page of its own, which rules out any overlap.
+const fn page(index: u64) -> u64 {
+    index << 32
+}
+
+// The claim lives at the very beginning of memory such that all its addresses are u32s.
+const CLAIM: u64 = page(0);
+const AIR_FREE_MEMORY: u64 = page(1);
+const CHALLENGES: u64 = page(2);
+const BASE_ROOT: u64 = page(3);
+const EXT_ROOT: u64 = page(4);
+const QUOT_ROOT: u64 = page(5);
+const OOD_CURR_BASE_ROW: u64 = page(6);
+const OOD_CURR_EXT_ROW: u64 = page(7);
+const OOD_NEXT_BASE_ROW: u64 = page(8);
+const OOD_NEXT_EXT_ROW: u64 = page(9);
+const OOD_QUOT_SEGMENTS: u64 = page(10);
+const QUOT_WEIGHTS: u64 = page(11);
+const COMBINATION_WEIGHTS: u64 = page(12);
+const DEEP_WEIGHTS: u64 = page(13);
+const OOD_POINT_CURR_ROW: u64 = page(14);
+const OOD_POINT_NEXT_ROW: u64 = page(15);
+const OOD_POINT_POW_NUM_SEGMENTS: u64 = page(16);
+const ZEROFIER_INVERSES: u64 = page(17);
+const OOD_CURR_ROW_BASE_AND_EXT_VALUE: u64 = page(18);
+const OOD_NEXT_ROW_BASE_AND_EXT_VALUE: u64 = page(19);
+const OOD_CURR_ROW_QUOT_SEGMENTS_VALUE: u64 = page(20);
+const FRI_ROOTS: u64 = page(21);
+const FRI_FOLDING_CHALLENGES: u64 = page(22);
+const FRI_LAST_ROUND_TREE: u64 = page(23);
+const FRI_LAST_ROUND_POLYNOMIAL: u64 = page(24);
+const FRI_PROOF_OF_WORK: u64 = page(25);
+const FRI_INDICES: u64 = page(26);
+const FRI_FOLDED_VALUES: u64 = page(27);
+const FRI_COSET: u64 = page(28);
+const REVEALED_INDICES: u64 = page(29);
+const REVEALED_VALUES: u64 = page(30);
+const BASE_ROW: u64 = page(31);
+const EXT_ROW: u64 = page(32);
+const QUOT_SEGMENTS_ROW: u64 = page(33);
+const DIGEST_SCRATCH: u64 = page(34);
+const DOMAIN_POINT: u64 = page(35);
+const SCALED_FOLDING_CHALLENGE: u64 = page(36);
+const BASE_AND_EXT_VALUE: u64 = page(37);
+const QUOT_SEGMENTS_VALUE: u64 = page(38);
+
+/// Generates a Triton VM [`Program`] that verifies [`Proof`]s of one padded height for one set of
+/// [`StarkParameters`]. See the [module-level documentation](self) for details.
+#[derive(Debug, Clone)]
+pub struct RecursiveVerifier {
+    parameters: StarkParameters,
+    padded_height: usize,
+    fri: Fri<StarkHasher>,
+}
+
+/// The parts of the [non-determinism](NonDeterminism) related to FRI, as well as the indices of
+/// the rows revealed in FRI's first round.
+struct FriHints {
+    tokens: Vec<BFieldElement>,
+    digests: Vec<Digest>,
+    revealed_indices: Vec<usize>,
+}
+
+/// A proof item of fixed size that is absorbed by the Fiat-Shamir sponge. The item's encoding
+/// lives in memory, starting at `address`. The header of the encoding, _e.g._, the item's
+/// discriminant, is known to the verifier. The payload is divined.
+struct AbsorbedItem {
+    address: u64,
+    header: Vec<BFieldElement>,
+    payload_len: usize,
+}
+
+impl AbsorbedItem {
+    /// The `item` only determines the shape. The payload must be the last `payload_len` elements
+    /// of its encoding.
+    fn new(address: u64, item: ProofItem, payload_len: usize) -> Self {
+        let encoding = item.encode();
+        let header = encoding[..encoding.len() - payload_len].to_vec();
+        Self {
+            address,
+            header,
+            payload_len,
+        }
+    }
+
+    fn merkle_root(address: u64) -> Self {
+        Self::new(
+            address,
+            ProofItem::MerkleRoot(Digest::default()),
+            DIGEST_LENGTH,
+        )
+    }
+
+    fn payload_address(&self) -> u64 {
+        self.address + self.header.len() as u64
+    }
+
+    /// Write the header, divine the payload, pad the encoding, and absorb it.
+    fn tasm(&self) -> Vec<LabelledInstruction> {
+        let write_header = self
+            .header
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &word)| write_word(word, self.address + i as u64))
+            .collect_vec();
+        let encoding_len = self.header.len() + self.payload_len;
+        [
+            write_header,
+            divine_to_memory(self.payload_address(), self.payload_len),
+            write_word(BFieldElement::one(), self.address + encoding_len as u64),
+            absorb_memory(self.address, encoding_len + 1),
+        ]
+        .concat()
+    }
+}
+
+impl RecursiveVerifier {
+    /// # Panics
+    ///
+    /// Panics if the padded height is not a power of two, or if FRI does not fold any codeword
+    /// for the given parameters and padded height. Proofs of the latter kind never verify.
+    pub fn new(parameters: StarkParameters, padded_height: usize) -> Self {
+        assert!(padded_height.is_power_of_two());
+        let fri = Stark::derive_fri(parameters, padded_height);
+        assert!(fri.num_rounds() > 0, "FRI must have at least one round");
+        Self {
+            parameters,
+            padded_height,
+            fri,
+        }
+    }
+
+    /// The public input for the [verifier program](Self::program) attesting to the given claim.
+    pub fn public_input(claim: &Claim) -> PublicInput {
+        PublicInput::new(claim.encode())
+    }
+
+    /// The Triton VM program verifying proofs of the configured padded height. Halts gracefully
+    /// if and only if the proof, supplied through [non-determinism](Self::non_determinism), is
+    /// valid for the claim, supplied through [public input](Self::public_input).
+    pub fn program(&self) -> Program {
+        let code = [
+            triton_asm!(sponge_init),
+            Self::read_and_absorb_claim(),
+            AbsorbedItem::merkle_root(BASE_ROOT).tasm(),
+            Self::sample_challenges(),
+            AbsorbedItem::merkle_root(EXT_ROOT).tasm(),
+            sample_scalars(QUOT_WEIGHTS, num_quotients()),
+            AbsorbedItem::merkle_root(QUOT_ROOT).tasm(),
+            sample_scalars(OOD_POINT_CURR_ROW, 1),
+            Self::out_of_domain_items()
+                .iter()
+                .flat_map(AbsorbedItem::tasm)
+                .collect(),
+            MasterExtTable::air_constraint_evaluation_tasm(Self::air_memory_layout()),
+            self.verify_out_of_domain_quotient_value(),
+            sample_scalars(COMBINATION_WEIGHTS, Self::num_combination_weights()),
+            self.out_of_domain_values(),
+            sample_scalars(DEEP_WEIGHTS, NUM_DEEP_CODEWORD_COMPONENTS),
+            self.fri(),
+            self.verify_combination_codeword(),
+            triton_asm!(halt),
+            Self::subroutines(),
+            self.fri_subroutines(),
+            self.combination_check(),
+        ]
+        .concat();
+        Program::new(&code)
+    }
+
+    /// The secret input for the [verifier program](Self::program): the content of the proof
+    /// items, authentication paths, and other hints.
+    ///
+    /// Fails if the proof cannot be decoded, if its shape does not match the parameters, or if
+    /// its padded height differs from the configured one. Successfully generating the
+    /// non-determinism does _not_ imply that the proof is valid.
+    pub fn non_determinism(
+        &self,
+        claim: &Claim,
+        proof: &Proof,
+    ) -> Result<NonDeterminism<BFieldElement>, VerificationError> {
+        let mut proof_stream = StarkProofStream::try_from(proof)?;
+        proof_stream.alter_fiat_shamir_state_with(claim);
+
+        if proof_stream.dequeue()?.as_zero_knowledge()? != self.parameters.zero_knowledge {
+            return Err(ZeroKnowledgeMismatch);
+        }
+        let log_2_padded_height = proof_stream.dequeue()?.as_log2_padded_height()?;
+        if 1 << log_2_padded_height != self.padded_height {
+            return Err(PaddedHeightMismatch);
+        }
+
+        let mut tokens = vec![];
+        let base_root = proof_stream.dequeue()?.as_merkle_root()?;
+        tokens.extend(memory_tokens(&base_root.values()));
+        proof_stream.sample_scalars(Challenges::num_challenges_to_sample());
+        let ext_root = proof_stream.dequeue()?.as_merkle_root()?;
+        tokens.extend(memory_tokens(&ext_root.values()));
+        proof_stream.sample_scalars(num_quotients());
+        let quot_root = proof_stream.dequeue()?.as_merkle_root()?;
+        tokens.extend(memory_tokens(&quot_root.values()));
+        proof_stream.sample_scalars(1);
+
+        let ood_curr_base_row = proof_stream.dequeue()?.as_out_of_domain_base_row()?;
+        let ood_curr_ext_row = proof_stream.dequeue()?.as_out_of_domain_ext_row()?;
+        let ood_next_base_row = proof_stream.dequeue()?.as_out_of_domain_base_row()?;
+        let ood_next_ext_row = proof_stream.dequeue()?.as_out_of_domain_ext_row()?;
+        let ood_quot_segments = proof_stream
+            .dequeue()?
+            .as_out_of_domain_quotient_segments()?;
+        for ood_row in [
+            ood_curr_base_row,
+            ood_curr_ext_row,
+            ood_next_base_row,
+            ood_next_ext_row,
+            ood_quot_segments.to_vec(),
+        ] {
+            tokens.extend(memory_tokens(&flatten(&ood_row)));
+        }
+
+        proof_stream.sample_scalars(Self::num_combination_weights());
+        proof_stream.sample_scalars(NUM_DEEP_CODEWORD_COMPONENTS);
+
+        let fri_hints = self.fri_non_determinism(&mut proof_stream)?;
+        tokens.extend(fri_hints.tokens);
+        let mut digests = fri_hints.digests;
+        let row_indices = fri_hints.revealed_indices;
+
+        let base_rows = proof_stream.dequeue()?.as_master_base_table_rows()?;
+        let base_auth_structure = proof_stream.dequeue()?.as_authentication_structure()?;
+        let ext_rows = proof_stream.dequeue()?.as_master_ext_table_rows()?;
+        let ext_auth_structure = proof_stream.dequeue()?.as_authentication_structure()?;
+        let quot_rows = proof_stream.dequeue()?.as_quotient_segments_elements()?;
+        let quot_auth_structure = proof_stream.dequeue()?.as_authentication_structure()?;
+
+        let num_rows = self.parameters.num_combination_codeword_checks;
+        if base_rows.len() != num_rows {
+            return Err(IncorrectNumberOfBaseTableRows);
+        }
+        if ext_rows.len() != num_rows {
+            return Err(IncorrectNumberOfExtTableRows);
+        }
+        if quot_rows.len() != num_rows {
+            return Err(IncorrectNumberOfQuotientSegmentElements);
+        }
+        let ext_rows = ext_rows.iter().map(|row| flatten(row)).collect_vec();
+        let quot_rows = quot_rows.iter().map(|row| flatten(row)).collect_vec();
+
+        let tree_height = self.fri.domain.length.ilog2() as usize;
+        let base_paths =
+            authentication_paths(tree_height, &row_indices, &base_rows, &base_auth_structure)
+                .ok_or(BaseCodewordAuthenticationFailure)?;
+        let ext_paths =
+            authentication_paths(tree_height, &row_indices, &ext_rows, &ext_auth_structure)
+                .ok_or(ExtensionCodewordAuthenticationFailure)?;
+        let quot_paths =
+            authentication_paths(tree_height, &row_indices, &quot_rows, &quot_auth_structure)
+                .ok_or(QuotientCodewordAuthenticationFailure)?;
+
+        for row_index in 0..num_rows {
+            tokens.extend(memory_tokens(&base_rows[row_index]));
+            digests.extend(&base_paths[row_index]);
+            tokens.extend(memory_tokens(&ext_rows[row_index]));
+            digests.extend(&ext_paths[row_index]);
+            tokens.extend(memory_tokens(&quot_rows[row_index]));
+            digests.extend(&quot_paths[row_index]);
+        }
+
+        Ok(NonDeterminism::new(tokens).with_digests(digests))
+    }
+
+    fn read_and_absorb_claim() -> Vec<LabelledInstruction> {
+        let read_digest = vec![triton_asm![read_io 1 swap 1 write_mem 1]; DIGEST_LENGTH].concat();
+        triton_asm!(
+            // the claim's encoding is [output_len + 1, output_len, output…, input_len + 1, …]
+            push {CLAIM}
+            call read_claim_field           // _ output_end
+            call read_claim_field           // _ input_end
+            {&read_digest}                  // _ claim_end
+
+            // pad the encoding and absorb it
+            dup 0 push 1 swap 1 write_mem 1 pop 1
+            push {RATE} swap 1 div_mod pop 1 push 1 add
+            push {CLAIM + RATE as u64 - 1}
+            call absorb_chunks
+            pop 2
+        )
+    }
+
+    /// Sample the challenges and compute the ones derived from the claim, which must be stored
+    /// in memory as its [encoding](BFieldCodec::encode).
+    fn sample_challenges() -> Vec<LabelledInstruction> {
+        let challenge = |id: ChallengeId| CHALLENGES + (EXTENSION_DEGREE * id.index()) as u64;
+        let lookup_terminal = LOOKUP_TABLE
+            .iter()
+            .flat_map(|&symbol| {
+                let indeterminate = read_xfe(challenge(LookupTablePublicIndeterminate));
+                [indeterminate, triton_asm!(xxmul push {symbol} add)].concat()
+            })
+            .collect_vec();
+
+        [
+            sample_scalars(CHALLENGES, Challenges::num_challenges_to_sample()),
+            triton_asm!(
+                push {challenge(StandardOutputIndeterminate)}
+                push {CLAIM + 1} read_mem 1 pop 1
+                push {CLAIM + 2}
+                push 0 push 0 push 1
+                call compute_eval_arg       // _ indeterminate 0 output_end [terminal; 3]
+            ),
+            write_xfe(challenge(StandardOutputTerminal)),
+            triton_asm!(
+                swap 2 pop 2                // _ output_end
+                push {challenge(StandardInputIndeterminate)}
+                swap 1
+                push 1 add read_mem 1       // _ indeterminate input_len output_end
+                push 2 add
+                push 0 push 0 push 1
+                call compute_eval_arg       // _ indeterminate 0 input_end [terminal; 3]
+            ),
+            write_xfe(challenge(StandardInputTerminal)),
+            triton_asm!(
+                swap 2 pop 2                // _ input_end
+                push {challenge(CompressProgramDigestIndeterminate)}
+                swap 1 push {DIGEST_LENGTH} swap 1
+                push 0 push 0 push 1
+                call compute_eval_arg       // _ indeterminate 0 digest_end [terminal; 3]
+            ),
+            write_xfe(challenge(CompressedProgramDigest)),
+            triton_asm!(pop 3 push 0 push 0 push 1),
+            lookup_terminal,
+            write_xfe(challenge(LookupTablePublicTerminal)),
+        ]
+        .concat()
+    }
+
+    fn out_of_domain_items() -> [AbsorbedItem; 5] {
+        let base_row = |address| {
+            let item = ProofItem::OutOfDomainBaseRow(vec![XFieldElement::zero(); NUM_BASE_COLUMNS]);
+            AbsorbedItem::new(address, item, EXTENSION_DEGREE * NUM_BASE_COLUMNS)
+        };
+        let ext_row = |address| {
+            let item = ProofItem::OutOfDomainExtRow(vec![XFieldElement::zero(); NUM_EXT_COLUMNS]);
+            AbsorbedItem::new(address, item, EXTENSION_DEGREE * NUM_EXT_COLUMNS)
+        };
+        let segments =
+            ProofItem::OutOfDomainQuotientSegments([XFieldElement::zero(); NUM_QUOTIENT_SEGMENTS]);
+        let segments_len = EXTENSION_DEGREE * NUM_QUOTIENT_SEGMENTS;
+        [
+            base_row(OOD_CURR_BASE_ROW),
+            ext_row(OOD_CURR_EXT_ROW),
+            base_row(OOD_NEXT_BASE_ROW),
+            ext_row(OOD_NEXT_EXT_ROW),
+            AbsorbedItem::new(OOD_QUOT_SEGMENTS, segments, segments_len),
+        ]
+    }
+
+    fn air_memory_layout() -> TasmConstraintEvaluationMemoryLayout {
+        let [curr_base_row, curr_ext_row, next_base_row, next_ext_row, _] =
+            Self::out_of_domain_items().map(|item| item.payload_address().into());
+        TasmConstraintEvaluationMemoryLayout {
+            free_mem_page_ptr: AIR_FREE_MEMORY.into(),
+            curr_base_row_ptr: curr_base_row,
+            curr_ext_row_ptr: curr_ext_row,
+            next_base_row_ptr: next_base_row,
+            next_ext_row_ptr: next_ext_row,
+            challenges_ptr: CHALLENGES.into(),
+        }
+    }
+
+    fn num_combination_weights() -> usize {
+        NUM_BASE_COLUMNS + NUM_EXT_COLUMNS + NUM_QUOTIENT_SEGMENTS
+    }
+
+    /// Compute the zerofiers' inverses, divide the evaluated constraints, and compare the
+    /// resulting quotient value to the quotient segments' out-of-domain values.
+    fn verify_out_of_domain_quotient_value(&self) -> Vec<LabelledInstruction> {
+        let trace_domain_generator =
+            ArithmeticDomain::generator_for_length(self.padded_height as u64);
+        let minus_generator_inverse = -trace_domain_generator.inverse();
+        let log_2_padded_height = self.padded_height.ilog2() as usize;
+        let zerofier_inverse = |category| ZEROFIER_INVERSES + (EXTENSION_DEGREE * category) as u64;
+
+        let zerofier_inverses = [
+            // initial: 1 / (z - 1)
+            read_xfe(OOD_POINT_CURR_ROW),
+            triton_asm!(push -1 add xinvert),
+            write_xfe(zerofier_inverse(0)),
+            // consistency: 1 / (z^padded_height - 1)
+            read_xfe(OOD_POINT_CURR_ROW),
+            vec![triton_asm![dup 2 dup 2 dup 2 xxmul]; log_2_padded_height].concat(),
+            triton_asm!(push -1 add xinvert),
+            write_xfe(zerofier_inverse(1)),
+            // transition: (z - g^(-1)) / (z^padded_height - 1)
+            read_xfe(OOD_POINT_CURR_ROW),
+            triton_asm!(push {minus_generator_inverse} add),
+            read_xfe(zerofier_inverse(1)),
+            triton_asm!(xxmul),
+            write_xfe(zerofier_inverse(2)),
+            // terminal: 1 / (z - g^(-1))
+            read_xfe(OOD_POINT_CURR_ROW),
+            triton_asm!(push {minus_generator_inverse} add xinvert),
+            write_xfe(zerofier_inverse(3)),
+        ]
+        .concat();
+
+        let num_quotients_per_category = [
+            MasterExtTable::num_initial_quotients(),
+            MasterExtTable::num_consistency_quotients(),
+            MasterExtTable::num_transition_quotients(),
+            MasterExtTable::num_terminal_quotients(),
+        ];
+        let mut quotient_value = triton_asm!(push 0 push 0 push 0);
+        let mut num_preceding_quotients = 0;
+        for (category, num_quotients) in num_quotients_per_category.into_iter().enumerate() {
+            let offset = (EXTENSION_DEGREE * num_preceding_quotients) as u64;
+            let constraints = AIR_FREE_MEMORY + offset;
+            let weights = QUOT_WEIGHTS + offset;
+            quotient_value.extend(triton_asm!(push 0 push 0 push 0));
+            quotient_value.extend(xx_dot(num_quotients, constraints, weights));
+            quotient_value.extend(read_xfe(zerofier_inverse(category)));
+            quotient_value.extend(triton_asm!(xxmul xxadd));
+            num_preceding_quotients += num_quotients;
+        }
+
+        // Σ z^i·segment_i, evaluated using Horner's method
+        let segment = |index| Self::ood_quot_segments() + (EXTENSION_DEGREE * index) as u64;
+        let mut sum_of_segments = read_xfe(segment(NUM_QUOTIENT_SEGMENTS - 1));
+        for segment_index in (0..NUM_QUOTIENT_SEGMENTS - 1).rev() {
+            sum_of_segments.extend(read_xfe(OOD_POINT_CURR_ROW));
+            sum_of_segments.extend(triton_asm!(xxmul));
+            sum_of_segments.extend(read_xfe(segment(segment_index)));
+            sum_of_segments.extend(triton_asm!(xxadd));
+        }
+
+        [
+            zerofier_inverses,
+            quotient_value,
+            sum_of_segments,
+            assert_xfe_eq(),
+        ]
+        .concat()
+    }
+
+    fn ood_quot_segments() -> u64 {
+        let [.., segments] = Self::out_of_domain_items();
+        segments.payload_address()
+    }
+
+    fn ext_weights() -> u64 {
+        COMBINATION_WEIGHTS + (EXTENSION_DEGREE * NUM_BASE_COLUMNS) as u64
+    }
+
+    fn quot_segment_weights() -> u64 {
+        Self::ext_weights() + (EXTENSION_DEGREE * NUM_EXT_COLUMNS) as u64
+    }
+
+    /// Compute the out-of-domain values of the base & extension rows as well as the quotient
+    /// segments under the combination weights, and the corresponding out-of-domain points.
+    fn out_of_domain_values(&self) -> Vec<LabelledInstruction> {
+        let [curr_base_row, curr_ext_row, next_base_row, next_ext_row, segments] =
+            Self::out_of_domain_items().map(|item| item.payload_address());
+        let base_and_ext_value = |base_row, ext_row| {
+            [
+                triton_asm!(push 0 push 0 push 0),
+                xx_dot(NUM_BASE_COLUMNS, base_row, COMBINATION_WEIGHTS),
+                xx_dot(NUM_EXT_COLUMNS, ext_row, Self::ext_weights()),
+            ]
+            .concat()
+        };
+        let trace_domain_generator =
+            ArithmeticDomain::generator_for_length(self.padded_height as u64);
+        let log_2_num_segments = NUM_QUOTIENT_SEGMENTS.ilog2() as usize;
+
+        [
+            base_and_ext_value(curr_base_row, curr_ext_row),
+            write_xfe(OOD_CURR_ROW_BASE_AND_EXT_VALUE),
+            base_and_ext_value(next_base_row, next_ext_row),
+            write_xfe(OOD_NEXT_ROW_BASE_AND_EXT_VALUE),
+            triton_asm!(push 0 push 0 push 0),
+            xx_dot(
+                NUM_QUOTIENT_SEGMENTS,
+                segments,
+                Self::quot_segment_weights(),
+            ),
+            write_xfe(OOD_CURR_ROW_QUOT_SEGMENTS_VALUE),
+            read_xfe(OOD_POINT_CURR_ROW),
+            triton_asm!(push {trace_domain_generator} xbmul),
+            write_xfe(OOD_POINT_NEXT_ROW),
+            read_xfe(OOD_POINT_CURR_ROW),
+            vec![triton_asm![dup 2 dup 2 dup 2 xxmul]; log_2_num_segments].concat(),
+            write_xfe(OOD_POINT_POW_NUM_SEGMENTS),
+        ]
+        .concat()
+    }
+
+    /// The domain of the given FRI round.
+    fn fri_round_domain(&self, round: usize) -> ArithmeticDomain {
+        let num_folded_cosets = self.fri.folding_factor.pow(round as u32);
+        ArithmeticDomain {
+            offset: self.fri.domain.offset.mod_pow_u32(num_folded_cosets as u32),
+            generator: self
+                .fri
+                .domain
+                .generator
+                .mod_pow_u32(num_folded_cosets as u32),
+            length: self.fri.domain.length / num_folded_cosets,
+        }
+    }
+
+    fn fri_last_round_domain(&self) -> ArithmeticDomain {
+        self.fri_round_domain(self.fri.num_rounds())
+    }
+
+    fn fri_root(round: usize) -> AbsorbedItem {
+        AbsorbedItem::merkle_root(FRI_ROOTS + (2 * RATE * round) as u64)
+    }
+
+    fn fri_folding_challenge(round: usize) -> u64 {
+        FRI_FOLDING_CHALLENGES + (RATE * round) as u64
+    }
+
+    /// The node with the given index in the Merkle tree of FRI's last round. The leafs hold the
+    /// last codeword, the root is node 1.
+    fn fri_last_round_node(index: usize) -> u64 {
+        FRI_LAST_ROUND_TREE + (DIGEST_LENGTH * index) as u64
+    }
+
+    fn fri_round_label(round: usize) -> String {
+        format!("fri_round_{round}")
+    }
+
+    fn fri(&self) -> Vec<LabelledInstruction> {
+        let num_rounds = self.fri.num_rounds();
+        let mut code = vec![];
+        for round in 0..=num_rounds {
+            code.extend(Self::fri_root(round).tasm());
+            if round < num_rounds {
+                code.extend(sample_scalars(Self::fri_folding_challenge(round), 1));
+            }
+        }
+
+        let last_round_len = self.fri_last_round_domain().length;
+        code.extend(triton_asm!(
+            push {last_round_len}
+            push {Self::fri_last_round_node(last_round_len)}
+            call fri_divine_last_codeword
+            pop 2
+        ));
+
+        if self.fri.num_grinding_bits > 0 {
+            let item = ProofItem::ProofOfWork(0);
+            let payload_len = 0_u64.encode().len();
+            code.extend(AbsorbedItem::new(FRI_PROOF_OF_WORK, item, payload_len).tasm());
+        }
+        code.extend(self.fri_sample_indices());
+
+        let coset_len = EXTENSION_DEGREE * self.fri.folding_factor;
+        code.extend(write_word(
+            BFieldElement::one(),
+            FRI_COSET + coset_len as u64,
+        ));
+        for round in 0..num_rounds {
+            let label = Self::fri_round_label(round);
+            code.extend(triton_asm!(push 0 call {label} pop 1));
+        }
+
+        let last_root = Self::fri_root(num_rounds).payload_address();
+        let num_last_round_coefficients = self.fri.last_round_max_degree() + 1;
+        code.extend(triton_asm!(
+            // the folded values agree with the last codeword
+            push 0 call fri_last_round_agreement pop 1
+
+            // the last codeword matches the last round's Merkle root
+            push {last_round_len - 1} call fri_last_round_merkle_root pop 1
+            push {Self::fri_last_round_node(1) + 4} read_mem 5 pop 1
+            push {last_root + 4} read_mem 5 pop 1
+            assert_vector pop 5
+
+            // the last codeword corresponds to a polynomial of low degree
+            push {num_last_round_coefficients}
+            push {FRI_LAST_ROUND_POLYNOMIAL}
+            call fri_divine_last_polynomial
+            pop 2
+            push 0 call fri_last_round_degree pop 1
+        ));
+        code
+    }
+
+    /// Sample the indices of the first round's collinearity checks. In the same way as
+    /// [`ProofStream::has_sufficient_proof_of_work`], the proof-of-work is checked using the
+    /// first squeezed element, which is also the first index.
+    ///
+    /// [`ProofStream::has_sufficient_proof_of_work`]:
+    /// crate::proof_stream::ProofStream::has_sufficient_proof_of_work
+    fn fri_sample_indices(&self) -> Vec<LabelledInstruction> {
+        let num_indices = self.fri.num_collinearity_checks;
+        let index_mask = self.fri.domain.length - 1;
+        let minus_one = -BFieldElement::one();
+
+        let mut code = vec![];
+        for squeeze in 0..num_indices.div_ceil(RATE) {
+            code.extend(triton_asm!(sponge_squeeze));
+            if squeeze == 0 {
+                code.extend(self.fri_check_proof_of_work());
+            }
+            let num_indices_in_squeeze = RATE.min(num_indices - squeeze * RATE);
+            for i in 0..num_indices_in_squeeze {
+                let index_address = FRI_INDICES + (squeeze * RATE + i) as u64;
+                code.extend(triton_asm!(
+                    dup 0 push {minus_one} eq push 0 eq assert
+                    split swap 1 pop 1
+                    push {index_mask} and
+                    push {index_address} write_mem 1 pop 1
+                ));
+            }
+            code.extend(triton_asm![pop 1; RATE - num_indices_in_squeeze]);
+        }
+        code
+    }
+
+    /// The element on top of the stack must have sufficiently many leading zeros.
+    fn fri_check_proof_of_work(&self) -> Vec<LabelledInstruction> {
+        let num_grinding_bits = self.fri.num_grinding_bits;
+        match num_grinding_bits {
+            0 => vec![],
+            1..=32 => triton_asm!(
+                dup 0 split pop 1
+                push {1_u64 << (32 - num_grinding_bits)} swap 1 lt assert
+            ),
+            _ => triton_asm!(
+                dup 0 split
+                push {1_u64 << (64 - num_grinding_bits)} swap 1 lt assert
+                push 0 eq assert
+            ),
+        }
+    }
+
+    /// Authenticate the revealed cosets of one FRI round, check that they agree with the values
+    /// folded in the previous round, and fold them. The values of the first round are recorded
+    /// for the combination codeword checks.
+    fn fri_round(&self, round: usize) -> Vec<LabelledInstruction> {
+        let domain = self.fri_round_domain(round);
+        let folding_factor = self.fri.folding_factor;
+        let num_cosets = domain.length / folding_factor;
+        let coset_len = EXTENSION_DEGREE * folding_factor;
+        let coset_element = |index| FRI_COSET + (EXTENSION_DEGREE * index) as u64;
+
+        let divine_coset = (0..folding_factor)
+            .flat_map(|i| divine_to_memory(coset_element(i), EXTENSION_DEGREE))
+            .collect_vec();
+        let authenticate_coset = [
+            triton_asm!(dup 0 push {num_cosets} add),
+            hash_memory(FRI_COSET, coset_len),
+            verify_authentication_path(
+                Self::fri_root(round).payload_address(),
+                num_cosets.ilog2() as usize,
+            ),
+        ]
+        .concat();
+
+        let agree_with_previous_round_or_record_revealed_values = match round {
+            0 => (0..folding_factor)
+                .flat_map(|i| {
+                    triton_asm!(
+                        // _ i j c
+                        dup 0 push {i * num_cosets} add
+                        dup 3 push {folding_factor} mul push {REVEALED_INDICES + i as u64} add
+                        write_mem 1 pop 1
+                        {&read_xfe(coset_element(i))}
+                        dup 5 push {coset_len} mul push {REVEALED_VALUES + 3 * i as u64} add
+                        write_mem 3 pop 1
+                    )
+                })
+                .collect(),
+            _ => triton_asm!(
+                // _ i j c
+                dup 1 push 3 mul push {FRI_COSET + 2} add read_mem 3 pop 1
+                dup 5 push 3 mul push {FRI_FOLDED_VALUES + 2} add read_mem 3 pop 1
+                {&assert_xfe_eq()}
+            ),
+        };
+
+        // The inverses of the coset's domain points are x_0^(-1)·ω^(-i), where ω is a primitive
+        // root of unity of order `folding_factor`. The folded value is the weighted sum of the
+        // coset's values, where the weights are Σ_{k < folding_factor} (α·x_i^(-1))^k.
+        let root_of_unity_inverse = domain.generator.mod_pow_u32(num_cosets as u32).inverse();
+        let horner_step = [
+            read_xfe(SCALED_FOLDING_CHALLENGE),
+            triton_asm!(xxmul push 1 add),
+        ]
+        .concat();
+        let horner_steps = vec![horner_step; folding_factor - 1].concat();
+        let fold = (0..folding_factor)
+            .flat_map(|i| {
+                let root_of_unity_power = root_of_unity_inverse.mod_pow_u32(i as u32);
+                [
+                    read_xfe(Self::fri_folding_challenge(round)),
+                    triton_asm!(
+                        push {DOMAIN_POINT} read_mem 1 pop 1
+                        push {root_of_unity_power} mul
+                        xbmul
+                    ),
+                    write_xfe(SCALED_FOLDING_CHALLENGE),
+                    triton_asm!(push 0 push 0 push 1),
+                    horner_steps.clone(),
+                    read_xfe(coset_element(i)),
+                    triton_asm!(xxmul xxadd),
+                ]
+                .concat()
+            })
+            .collect_vec();
+        let folding_factor_inverse = BFieldElement::new(folding_factor as u64).inverse();
+
+        triton_asm!(
+            // BEFORE: _ i
+            // AFTER:  _ num_collinearity_checks
+            {Self::fri_round_label(round)}:
+                dup 0 push {self.fri.num_collinearity_checks} eq skiz return
+                dup 0 push {FRI_INDICES} add read_mem 1 pop 1
+                push {domain.length - 1} and
+                push {num_cosets} swap 1 div_mod    // _ i j c
+                {&divine_coset}
+                {&authenticate_coset}
+                {&agree_with_previous_round_or_record_revealed_values}
+                push {domain.generator} pow
+                push {domain.offset} mul invert
+                push {DOMAIN_POINT} write_mem 1 pop 1
+                pop 1                               // _ i
+                push 0 push 0 push 0
+                {&fold}
+                push {folding_factor_inverse} xbmul
+                dup 3 push 3 mul push {FRI_FOLDED_VALUES} add write_mem 3 pop 1
+                push 1 add
+                recurse
+        )
+    }
+
+    fn fri_subroutines(&self) -> Vec<LabelledInstruction> {
+        let num_checks = self.fri.num_collinearity_checks;
+        let last_round_domain = self.fri_last_round_domain();
+        let last_round_len = last_round_domain.length;
+        let last_round_leaf = |offset| Self::fri_last_round_node(last_round_len) + offset;
+        let num_coefficients = self.fri.last_round_max_degree() + 1;
+        let last_polynomial_end =
+            FRI_LAST_ROUND_POLYNOMIAL + (EXTENSION_DEGREE * num_coefficients) as u64 - 1;
+
+        let rounds = (0..self.fri.num_rounds())
+            .flat_map(|round| self.fri_round(round))
+            .collect_vec();
+        let subroutines = triton_asm!(
+            // BEFORE: _ n address
+            // AFTER:  _ 0 address
+            fri_divine_last_codeword:
+                dup 1 push 0 eq skiz return
+                divine 3 dup 3 write_mem 3 pop 1
+                push {DIGEST_LENGTH} add
+                swap 1 push -1 add swap 1
+                recurse
+
+            // BEFORE: _ n address
+            // AFTER:  _ 0 address
+            fri_divine_last_polynomial:
+                dup 1 push 0 eq skiz return
+                divine 3 dup 3 write_mem 3 pop 1
+                push 3 add
+                swap 1 push -1 add swap 1
+                recurse
+
+            // BEFORE: _ i
+            // AFTER:  _ num_collinearity_checks
+            fri_last_round_agreement:
+                dup 0 push {num_checks} eq skiz return
+                dup 0 push {FRI_INDICES} add read_mem 1 pop 1
+                push {last_round_len - 1} and
+                push {DIGEST_LENGTH} mul push {last_round_leaf(2)} add read_mem 3 pop 1
+                dup 3 push 3 mul push {FRI_FOLDED_VALUES + 2} add read_mem 3 pop 1
+                {&assert_xfe_eq()}
+                push 1 add
+                recurse
+
+            // BEFORE: _ node_index
+            // AFTER:  _ 0
+            fri_last_round_merkle_root:
+                dup 0 push 0 eq skiz return
+                dup 0 push {2 * DIGEST_LENGTH} mul push {Self::fri_last_round_node(1) + 4} add
+                read_mem 5 read_mem 5 pop 1
+                hash
+                dup 5 push {DIGEST_LENGTH} mul push {FRI_LAST_ROUND_TREE} add
+                write_mem 5 pop 1
+                push -1 add
+                recurse
+
+            // BEFORE: _ i
+            // AFTER:  _ last_round_len
+            fri_last_round_degree:
+                dup 0 push {last_round_len} eq skiz return
+                dup 0 push {last_round_domain.generator} pow
+                push {last_round_domain.offset} mul
+                push {num_coefficients}
+                push {last_polynomial_end}
+                push 0 push 0 push 0
+                call horner                 // _ i x 0 address [p(x); 3]
+                {&pop_3_below_xfe()}
+                dup 3 push {DIGEST_LENGTH} mul push {last_round_leaf(2)} add read_mem 3 pop 1
+                {&assert_xfe_eq()}
+                push 1 add
+                recurse
+        );
+        [rounds, subroutines].concat()
+    }
+
+    fn fri_non_determinism(
+        &self,
+        proof_stream: &mut StarkProofStream,
+    ) -> Result<FriHints, VerificationError> {
+        let num_rounds = self.fri.num_rounds();
+        let folding_factor = self.fri.folding_factor;
+        let num_checks = self.fri.num_collinearity_checks;
+        let mut tokens = vec![];
+        let mut digests = vec![];
+
+        for round in 0..=num_rounds {
+            let root = proof_stream.dequeue()?.as_merkle_root()?;
+            tokens.extend(memory_tokens(&root.values()));
+            if round < num_rounds {
+                proof_stream.sample_scalars(1);
+            }
+        }
+
+        let last_round_domain = self.fri_last_round_domain();
+        let last_codeword = proof_stream.dequeue()?.as_fri_codeword()?;
+        if last_codeword.len() != last_round_domain.length {
+            return Err(FriValidationError::LastCodewordMismatch.into());
+        }
+        for element in &last_codeword {
+            tokens.extend(memory_tokens(&element.coefficients));
+        }
+
+        if self.fri.num_grinding_bits > 0 {
+            let nonce = proof_stream.dequeue()?.as_proof_of_work()?;
+            tokens.extend(memory_tokens(&nonce.encode()));
+        }
+        let indices = proof_stream.sample_indices(self.fri.domain.length, num_checks);
+
+        let mut revealed_indices = vec![];
+        for round in 0..num_rounds {
+            let domain = self.fri_round_domain(round);
+            let num_cosets = domain.length / folding_factor;
+            let coset_indices = indices
+                .iter()
+                .map(|&index| index % domain.length % num_cosets)
+                .collect_vec();
+
+            let response = proof_stream.dequeue()?.as_fri_response()?;
+            let revealed_leaves = response.revealed_leaves;
+            if revealed_leaves.len() != num_checks * folding_factor {
+                return Err(FriValidationError::IncorrectNumberOfRevealedLeaves.into());
+            }
+            let cosets = revealed_leaves
+                .chunks(folding_factor)
+                .map(flatten)
+                .collect_vec();
+            let tree_height = num_cosets.ilog2() as usize;
+            let auth_structure = &response.auth_structure;
+            let paths = authentication_paths(tree_height, &coset_indices, &cosets, auth_structure)
+                .ok_or(FriValidationError::BadMerkleAuthenticationPath)?;
+
+            for (coset, path) in cosets.iter().zip_eq(paths) {
+                for element in coset.chunks(EXTENSION_DEGREE) {
+                    tokens.extend(memory_tokens(element));
+                }
+                digests.extend(path);
+            }
+
+            if round == 0 {
+                revealed_indices = coset_indices
+                    .iter()
+                    .flat_map(|&coset_index| {
+                        (0..folding_factor).map(move |i| coset_index + i * num_cosets)
+                    })
+                    .collect();
+            }
+        }
+
+        let num_coefficients = self.fri.last_round_max_degree() + 1;
+        let mut coefficients = last_round_domain.interpolate(&last_codeword).coefficients;
+        coefficients.resize(num_coefficients, XFieldElement::zero());
+        for coefficient in coefficients {
+            tokens.extend(memory_tokens(&coefficient.coefficients));
+        }
+
+        Ok(FriHints {
+            tokens,
+            digests,
+            revealed_indices,
+        })
+    }
+
+    fn verify_combination_codeword(&self) -> Vec<LabelledInstruction> {
+        let ext_row_len = EXTENSION_DEGREE * self.ext_row_width();
+        let quot_row_len = EXTENSION_DEGREE * NUM_QUOTIENT_SEGMENTS;
+        [
+            write_word(BFieldElement::one(), BASE_ROW + NUM_BASE_COLUMNS as u64),
+            write_word(BFieldElement::one(), EXT_ROW + ext_row_len as u64),
+            write_word(
+                BFieldElement::one(),
+                QUOT_SEGMENTS_ROW + quot_row_len as u64,
+            ),
+            triton_asm!(push 0 call combination_check pop 1),
+        ]
+        .concat()
+    }
+
+    /// The number of extension columns, including the randomizer polynomials.
+    fn ext_row_width(&self) -> usize {
+        NUM_EXT_COLUMNS + self.parameters.num_randomizer_polynomials
+    }
+
+    /// Authenticate the revealed rows and check that they, linearly combined and DEEP-updated,
+    /// agree with the revealed values of FRI's first round.
+    fn combination_check(&self) -> Vec<LabelledInstruction> {
+        let domain = self.fri.domain;
+        let tree_height = domain.length.ilog2() as usize;
+        let ext_row_len = EXTENSION_DEGREE * self.ext_row_width();
+        let quot_row_len = EXTENSION_DEGREE * NUM_QUOTIENT_SEGMENTS;
+        let authenticate_row = |row, row_len, root: u64| {
+            [
+                divine_to_memory(row, row_len),
+                triton_asm!(dup 0),
+                hash_memory(row, row_len),
+                verify_authentication_path(root + 1, tree_height),
+            ]
+            .concat()
+        };
+
+        let deep_update = |value, ood_value, ood_point, deep_weight_index| {
+            let deep_weight = DEEP_WEIGHTS + (EXTENSION_DEGREE * deep_weight_index) as u64;
+            [
+                read_xfe(ood_point),
+                triton_asm!(push -1 xbmul push {DOMAIN_POINT} read_mem 1 pop 1 add xinvert),
+                read_xfe(ood_value),
+                triton_asm!(push -1 xbmul),
+                read_xfe(value),
+                triton_asm!(xxadd xxmul),
+                read_xfe(deep_weight),
+                triton_asm!(xxmul xxadd),
+            ]
+            .concat()
+        };
+        let randomizers = (NUM_EXT_COLUMNS..self.ext_row_width())
+            .flat_map(|i| {
+                let randomizer = read_xfe(EXT_ROW + (EXTENSION_DEGREE * i) as u64);
+                [randomizer, triton_asm!(xxadd)].concat()
+            })
+            .collect_vec();
+
+        triton_asm!(
+            // BEFORE: _ i
+            // AFTER:  _ num_combination_codeword_checks
+            combination_check:
+                dup 0 push {self.parameters.num_combination_codeword_checks} eq skiz return
+                dup 0 push {REVEALED_INDICES} add read_mem 1 pop 1
+                dup 0 push {domain.generator} pow push {domain.offset} mul
+                push {DOMAIN_POINT} write_mem 1 pop 1
+                push {domain.length} add            // _ i node_index
+                {&authenticate_row(BASE_ROW, NUM_BASE_COLUMNS, BASE_ROOT)}
+                {&authenticate_row(EXT_ROW, ext_row_len, EXT_ROOT)}
+                {&authenticate_row(QUOT_SEGMENTS_ROW, quot_row_len, QUOT_ROOT)}
+                pop 1                               // _ i
+
+                push 0 push 0 push 0
+                {&xb_dot(NUM_BASE_COLUMNS, BASE_ROW, COMBINATION_WEIGHTS)}
+                {&xx_dot(NUM_EXT_COLUMNS, EXT_ROW, Self::ext_weights())}
+                {&write_xfe(BASE_AND_EXT_VALUE)}
+                push 0 push 0 push 0
+                {&xx_dot(NUM_QUOTIENT_SEGMENTS, QUOT_SEGMENTS_ROW, Self::quot_segment_weights())}
+                {&write_xfe(QUOT_SEGMENTS_VALUE)}
+
+                push 0 push 0 push 0
+                {&deep_update(BASE_AND_EXT_VALUE, OOD_CURR_ROW_BASE_AND_EXT_VALUE, OOD_POINT_CURR_ROW, 0)}
+                {&deep_update(BASE_AND_EXT_VALUE, OOD_NEXT_ROW_BASE_AND_EXT_VALUE, OOD_POINT_NEXT_ROW, 1)}
+                {&deep_update(QUOT_SEGMENTS_VALUE, OOD_CURR_ROW_QUOT_SEGMENTS_VALUE, OOD_POINT_POW_NUM_SEGMENTS, 2)}
+                {&randomizers}
+                dup 3 push 3 mul push {REVEALED_VALUES + 2} add read_mem 3 pop 1
+                {&assert_xfe_eq()}
+                push 1 add
+                recurse
+        )
+    }
+
+    fn subroutines() -> Vec<LabelledInstruction> {
+        triton_asm!(
+            // BEFORE: _ address
+            // AFTER:  _ address_after_field
+            read_claim_field:
+                read_io 2                           // _ address len+1 len
+                dup 1 dup 1 push 1 add eq assert
+                swap 1 dup 1 swap 3                 // _ len len len+1 address
+                write_mem 2
+                call read_field_elements
+                swap 1 pop 1
+                return
+
+            // BEFORE: _ n address
+            // AFTER:  _ 0 address_after_elements
+            read_field_elements:
+                dup 1 push 0 eq skiz return
+                read_io 1 swap 1 write_mem 1
+                swap 1 push -1 add swap 1
+                recurse
+
+            // BEFORE: _ num_chunks address_of_last_word_of_first_chunk
+            // AFTER:  _ 0 address
+            absorb_chunks:
+                dup 1 push 0 eq skiz return
+                dup 0 read_mem 5 read_mem 5 pop 1 sponge_absorb
+                push {RATE} add
+                swap 1 push -1 add swap 1
+                recurse
+
+            // BEFORE: _ indeterminate_address n symbols_address [running_evaluation; 3]
+            // AFTER:  _ indeterminate_address 0 address_after_symbols [terminal; 3]
+            compute_eval_arg:
+                dup 4 push 0 eq skiz return
+                dup 5 push 2 add read_mem 3 pop 1
+                xxmul
+                dup 3 read_mem 1 pop 1 add
+                swap 3 push 1 add swap 3
+                swap 4 push -1 add swap 4
+                recurse
+
+            // BEFORE: _ n lhs_address rhs_address [accumulator; 3]
+            // AFTER:  _ 0 lhs_address' rhs_address' [accumulator'; 3]
+            // Both addresses point to the last word of the respective vector of XFieldElements.
+            xx_dot:
+                dup 5 push 0 eq skiz return
+                dup 4 read_mem 3 swap 8 pop 1
+                dup 6 read_mem 3 swap 10 pop 1
+                xxmul xxadd
+                swap 5 push -1 add swap 5
+                recurse
+
+            // BEFORE: _ n lhs_address rhs_address [accumulator; 3]
+            // AFTER:  _ 0 lhs_address' rhs_address' [accumulator'; 3]
+            // Both addresses point to the last word of the respective vector, the lhs being a
+            // vector of BFieldElements, the rhs being a vector of XFieldElements.
+            xb_dot:
+                dup 5 push 0 eq skiz return
+                dup 3 read_mem 3 swap 7 pop 1
+                dup 7 read_mem 1 swap 9 pop 1
+                xbmul xxadd
+                swap 5 push -1 add swap 5
+                recurse
+
+            // BEFORE: _ x n address [accumulator; 3]
+            // AFTER:  _ x 0 address' [accumulator'; 3]
+            // The address points to the last word of the coefficients, which are
+            // XFieldElements. Coefficients are processed from highest to lowest.
+            horner:
+                dup 4 push 0 eq skiz return
+                dup 5 xbmul
+                dup 3 read_mem 3 swap 7 pop 1
+                xxadd
+                swap 4 push -1 add swap 4
+                recurse
+        )
+    }
+}
+
+fn flatten(elements: &[XFieldElement]) -> Vec<BFieldElement> {
+    elements.iter().flat_map(|xfe| xfe.coefficients).collect()
+}
+
+/// The [secret tokens](NonDeterminism) that make [`divine_to_memory`] write the given words.
+fn memory_tokens(words: &[BFieldElement]) -> Vec<BFieldElement> {
+    words
+        .chunks(MAX_WORDS_PER_INSTRUCTION)
+        .flat_map(|chunk| chunk.iter().rev().copied().collect_vec())
+        .collect()
+}
+
+/// Divine `len` words and write them to memory, starting at `address`.
+fn divine_to_memory(address: u64, len: usize) -> Vec<LabelledInstruction> {
+    (0..len)
+        .step_by(MAX_WORDS_PER_INSTRUCTION)
+        .flat_map(|offset| {
+            let chunk_len = MAX_WORDS_PER_INSTRUCTION.min(len - offset);
+            let chunk_address = address + offset as u64;
+            triton_asm!(divine {chunk_len} push {chunk_address} write_mem {chunk_len} pop 1)
+        })
+        .collect()
+}
+
+fn write_word(word: BFieldElement, address: u64) -> Vec<LabelledInstruction> {
+    triton_asm!(push {word} push {address} write_mem 1 pop 1)
+}
+
+fn read_xfe(address: u64) -> Vec<LabelledInstruction> {
+    triton_asm!(push {address + 2} read_mem 3 pop 1)
+}
+
+fn write_xfe(address: u64) -> Vec<LabelledInstruction> {
+    triton_asm!(push {address} write_mem 3 pop 1)
+}
+
+/// Crash the VM unless the two [`XFieldElement`]s on top of the stack are equal. Pops both.
+fn assert_xfe_eq() -> Vec<LabelledInstruction> {
+    triton_asm!(push -1 xbmul xxadd push 0 eq assert push 0 eq assert push 0 eq assert)
+}
+
+/// Remove the 3 words below the [`XFieldElement`] on top of the stack.
+fn pop_3_below_xfe() -> Vec<LabelledInstruction> {
+    triton_asm!(swap 3 pop 1 swap 3 pop 1 swap 3 pop 1)
+}
+
+/// Absorb the `len` words starting at `address`, which must be a multiple of [`RATE`] or be
+/// followed by zeros.
+fn absorb_memory(address: u64, len: usize) -> Vec<LabelledInstruction> {
+    (0..len.div_ceil(RATE))
+        .flat_map(|chunk| {
+            let last_word = address + (RATE * chunk + RATE - 1) as u64;
+            triton_asm!(push {last_word} read_mem 5 read_mem 5 pop 1 sponge_absorb)
+        })
+        .collect()
+}
+
+/// Sample `n` [`XFieldElement`]s and write them to memory, starting at `address`. Might write
+/// up to two additional [`XFieldElement`]s.
+fn sample_scalars(address: u64, n: usize) -> Vec<LabelledInstruction> {
+    let words_per_squeeze = EXTENSION_DEGREE * SCALARS_PER_SQUEEZE;
+    (0..n.div_ceil(SCALARS_PER_SQUEEZE))
+        .flat_map(|squeeze| {
+            let squeeze_address = address + (words_per_squeeze * squeeze) as u64;
+            triton_asm!(sponge_squeeze push {squeeze_address} write_mem 5 write_mem 4 pop 2)
+        })
+        .collect()
+}
+
+/// Hash the `len` words starting at `address` like [`hash_varlen`] does. The padding must already
+/// be in memory. Pushes the digest. Overwrites the sponge state.
+///
+/// [`hash_varlen`]: AlgebraicHasher::hash_varlen
+fn hash_memory(address: u64, len: usize) -> Vec<LabelledInstruction> {
+    [
+        triton_asm!(sponge_init),
+        absorb_memory(address, len + 1),
+        triton_asm!(
+            sponge_squeeze
+            push {DIGEST_SCRATCH} write_mem 5 pop 1 pop 5
+            push {DIGEST_SCRATCH + 4} read_mem 5 pop 1
+        ),
+    ]
+    .concat()
+}
+
+/// Crash the VM unless the digest on top of the stack is the leaf with the node index right below
+/// it in the Merkle tree of the given height with the root at the given address. Pops both.
+fn verify_authentication_path(root: u64, tree_height: usize) -> Vec<LabelledInstruction> {
+    [
+        vec![triton_asm![divine_sibling hash]; tree_height].concat(),
+        triton_asm!(push {root + 4} read_mem 5 pop 1 assert_vector pop 5 pop 1),
+    ]
+    .concat()
+}
+
+/// Add the inner product of the `n` [`XFieldElement`]s starting at `lhs` and the `n`
+/// [`XFieldElement`]s starting at `rhs` to the [`XFieldElement`] on top of the stack.
+fn xx_dot(n: usize, lhs: u64, rhs: u64) -> Vec<LabelledInstruction> {
+    let last_word = |address| address + (EXTENSION_DEGREE * n) as u64 - 1;
+    triton_asm!(
+        push {n} push {last_word(lhs)} push {last_word(rhs)} push 0 push 0 push 0
+        call xx_dot
+        {&pop_3_below_xfe()}
+        xxadd
+    )
+}
+
+/// Add the inner product of the `n` [`BFieldElement`]s starting at `lhs` and the `n`
+/// [`XFieldElement`]s starting at `rhs` to the [`XFieldElement`] on top of the stack.
+fn xb_dot(n: usize, lhs: u64, rhs: u64) -> Vec<LabelledInstruction> {
+    let lhs_last_word = lhs + n as u64 - 1;
+    let rhs_last_word = rhs + (EXTENSION_DEGREE * n) as u64 - 1;
+    triton_asm!(
+        push {n} push {lhs_last_word} push {rhs_last_word} push 0 push 0 push 0
+        call xb_dot
+        {&pop_3_below_xfe()}
+        xxadd
+    )
+}
+
+/// The authentication paths for the given leafs, which are hashed like [`hash_memory`] does.
+/// `None` if the authentication structure is malformed.
+fn authentication_paths(
+    tree_height: usize,
+    leaf_indices: &[usize],
+    leafs: &[Vec<BFieldElement>],
+    authentication_structure: &[Digest],
+) -> Option<Vec<Vec<Digest>>> {
+    let leaf_digests = leafs
+        .iter()
+        .map(|leaf| StarkHasher::hash_varlen(leaf))
+        .collect_vec();
+    MerkleTree::<StarkHasher>::authentication_paths_from_authentication_structure(
+        tree_height,
+        leaf_indices,
+        &leaf_digests,
+        authentication_structure,
+    )
+    .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use assert2::assert;
+    use assert2::let_assert;
+    use ndarray::Array1;
+    use proptest::collection::vec;
+    use proptest_arbitrary_interop::arb;
+    use test_strategy::proptest;
+
+    use crate::shared_tests::*;
+    use crate::table::extension_table::Evaluable;
+    use crate::triton_program;
+    use crate::vm::VMState;
+
+    use super::*;
+
+    fn write_xfes_to_ram(
+        ram: &mut HashMap<BFieldElement, BFieldElement>,
+        address: BFieldElement,
+        elements: &[XFieldElement],
+    ) {
+        for (i, word) in flatten(elements).into_iter().enumerate() {
+            ram.insert(address + BFieldElement::new(i as u64), word);
+        }
+    }
+
+    #[proptest(cases = 3)]
+    fn air_constraint_evaluation_in_triton_assembly_agrees_with_rust(
+        #[strategy(vec(arb(), NUM_BASE_COLUMNS))] curr_base_row: Vec<XFieldElement>,
+        #[strategy(vec(arb(), NUM_EXT_COLUMNS))] curr_ext_row: Vec<XFieldElement>,
+        #[strategy(vec(arb(), NUM_BASE_COLUMNS))] next_base_row: Vec<XFieldElement>,
+        #[strategy(vec(arb(), NUM_EXT_COLUMNS))] next_ext_row: Vec<XFieldElement>,
+        #[strategy(arb())] challenges: Challenges,
+    ) {
+        let layout = RecursiveVerifier::air_memory_layout();
+        let mut ram = HashMap::new();
+        write_xfes_to_ram(&mut ram, layout.curr_base_row_ptr, &curr_base_row);
+        write_xfes_to_ram(&mut ram, layout.curr_ext_row_ptr, &curr_ext_row);
+        write_xfes_to_ram(&mut ram, layout.next_base_row_ptr, &next_base_row);
+        write_xfes_to_ram(&mut ram, layout.next_ext_row_ptr, &next_ext_row);
+        write_xfes_to_ram(&mut ram, layout.challenges_ptr, &challenges.challenges);
+
+        let code = MasterExtTable::air_constraint_evaluation_tasm(layout);
+        let program = Program::new(&[code, triton_asm!(halt)].concat());
+        let non_determinism = NonDeterminism::new(vec![]).with_ram(ram);
+        let mut vm_state = VMState::new(&program, PublicInput::default(), non_determinism);
+        let_assert!(Ok(()) = vm_state.run());
+
+        let curr_base_row = Array1::from(curr_base_row);
+        let curr_ext_row = Array1::from(curr_ext_row);
+        let next_base_row = Array1::from(next_base_row);
+        let next_ext_row = Array1::from(next_ext_row);
+        let rust_evaluation = [
+            MasterExtTable::evaluate_initial_constraints(
+                curr_base_row.view(),
+                curr_ext_row.view(),
+                &challenges,
+            ),
+            MasterExtTable::evaluate_consistency_constraints(
+                curr_base_row.view(),
+                curr_ext_row.view(),
+                &challenges,
+            ),
+            MasterExtTable::evaluate_transition_constraints(
+                curr_base_row.view(),
+                curr_ext_row.view(),
+                next_base_row.view(),
+                next_ext_row.view(),
+                &challenges,
+            ),
+            MasterExtTable::evaluate_terminal_constraints(
+                curr_base_row.view(),
+                curr_ext_row.view(),
+                &challenges,
+            ),
+        ]
+        .concat();
+
+        let read_word = |address: u64| {
+            let address = layout.free_mem_page_ptr + BFieldElement::new(address);
+            vm_state.ram.get(&address).copied().unwrap_or_default()
+        };
+        for (i, &expected) in rust_evaluation.iter().enumerate() {
+            let address = (EXTENSION_DEGREE * i) as u64;
+            let coefficients = [0, 1, 2].map(|j| read_word(address + j));
+            assert!(expected == XFieldElement::new(coefficients));
+        }
+    }
+
+    fn prove_simple_program() -> (RecursiveVerifier, Claim, Proof) {
+        let program = triton_program!(read_io 1 push 17 add write_io 1 halt);
+        let public_input = PublicInput::new(vec![BFieldElement::new(42)]);
+        let (parameters, claim, proof) = prove_with_low_security_level(
+            &program,
+            public_input,
+            NonDeterminism::default(),
+            &mut None,
+        );
+        let padded_height = proof.padded_height().unwrap();
+        let verifier = RecursiveVerifier::new(parameters, padded_height);
+        (verifier, claim, proof)
+    }
+
+    fn run_verifier(verifier: &RecursiveVerifier, claim: &Claim, proof: &Proof) -> bool {
+        let Ok(non_determinism) = verifier.non_determinism(claim, proof) else {
+            return false;
+        };
+        let public_input = RecursiveVerifier::public_input(claim);
+        verifier
+            .program()
+            .run(public_input, non_determinism)
+            .is_ok()
+    }
+
+    /// Apply `tamper` to every item of the proof, which must report whether it changed the item.
+    fn tamper_with_proof(proof: &Proof, tamper: impl FnMut(&mut ProofItem) -> bool) -> Proof {
+        let mut proof_stream = StarkProofStream::try_from(proof).unwrap();
+        let num_tampered_items = proof_stream
+            .items
+            .iter_mut()
+            .map(tamper)
+            .filter(|&is_tampered| is_tampered)
+            .count();
+        assert!(num_tampered_items > 0);
+        proof_stream.into()
+    }
+
+    #[test]
+    fn recursive_verifier_accepts_honest_proof() {
+        let (verifier, claim, proof) = prove_simple_program();
+        assert!(let Ok(()) = Stark::verify(verifier.parameters, &claim, &proof, &mut None));
+        assert!(run_verifier(&verifier, &claim, &proof));
+    }
+
+    #[test]
+    fn recursive_verifier_rejects_proof_for_different_output() {
+        let (verifier, mut claim, proof) = prove_simple_program();
+        claim.output[0] += BFieldElement::one();
+        assert!(!run_verifier(&verifier, &claim, &proof));
+    }
+
+    #[test]
+    fn recursive_verifier_rejects_tampered_out_of_domain_row() {
+        let (verifier, claim, proof) = prove_simple_program();
+        let mut is_first_ood_base_row = true;
+        let proof = tamper_with_proof(&proof, |item| {
+            let ProofItem::OutOfDomainBaseRow(row) = item else {
+                return false;
+            };
+            if !std::mem::take(&mut is_first_ood_base_row) {
+                return false;
+            }
+            row[0] += XFieldElement::one();
+            true
+        });
+        assert!(!run_verifier(&verifier, &claim, &proof));
+    }
+
+    #[test]
+    fn recursive_verifier_rejects_tampered_base_table_row() {
+        let (verifier, claim, proof) = prove_simple_program();
+        let proof = tamper_with_proof(&proof, |item| {
+            let ProofItem::MasterBaseTableRows(rows) = item else {
+                return false;
+            };
+            rows[0][0] += BFieldElement::one();
+            true
+        });
+        assert!(!run_verifier(&verifier, &claim, &proof));
+    }
+}
@@ -0,0 +1,342 @@
+//! Estimates of the soundness of the zk-STARK for given [`StarkParameters`], and a search for the
+//! cheapest parameters achieving some target security level.
+//!
+//! The [`SecurityEstimate`] reports two numbers. The _conjectured_ security level assumes that FRI
+//! is sound up to the list-decoding capacity of the Reed-Solomon code, as is common practice.
+//! The _provable_ security level only relies on soundness up to the Johnson bound as proven in
+//! “Proximity Gaps for Reed-Solomon Codes” by Ben-Sasson, Carmon, Ishai, Kopparty, and Saraf.
+//! Both are the minimum of three terms:
+//! - the queries, _i.e._, the collinearity checks, together with the proof-of-work,
+//! - the size of the extension field, from which all the verifier's challenges are sampled, and
+//! - the collision resistance of the hash function.
+//!
+//! The estimates are meant for comparing and choosing parameters. They are no substitute for a
+//! careful security analysis.
+
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+use itertools::iproduct;
+use twenty_first::shared_math::tip5::DIGEST_LENGTH;
+use twenty_first::shared_math::x_field_element::EXTENSION_DEGREE;
+
+use crate::stark::Stark;
+use crate::stark::StarkParameters;
+use crate::stark::NUM_QUOTIENT_SEGMENTS;
+use crate::table::master_table::NUM_BASE_COLUMNS;
+use crate::table::master_table::NUM_EXT_COLUMNS;
+
+/// The multiplicity parameter of the Guruswami-Sudan list decoder used in the proof of FRI's
+/// soundness up to the Johnson bound. Larger values bring the per-query error closer to the
+/// Johnson bound, but increase the field-size term.
+const JOHNSON_BOUND_MULTIPLICITY: f64 = 16.0;
+
+/// The largest number of grinding bits the [parameter search](optimal_parameters) considers.
+const MAX_NUM_GRINDING_BITS: usize = 24;
+
+/// The logarithm of the size of the base field, rounded to the nearest integer. The difference to
+/// the precise value is negligible, and rounding makes the hash term come out at exactly 160 bits.
+const LOG2_OF_BASE_FIELD_SIZE: f64 = 64.0;
+
+/// The estimated soundness of the zk-STARK for some [`StarkParameters`] and padded height.
+/// All values are in bits. See the [module's documentation](self) for details.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SecurityEstimate {
+    /// The security level under the conjecture that FRI is sound up to the list-decoding capacity.
+    pub conjectured_security_level: f64,
+
+    /// The security level provable under the Johnson bound.
+    pub provable_security_level: f64,
+
+    /// The conjectured security contributed by the collinearity checks and the proof-of-work.
+    pub conjectured_query_security: f64,
+
+    /// The provable security contributed by the collinearity checks and the proof-of-work.
+    pub provable_query_security: f64,
+
+    /// The conjectured security limited by the size of the extension field.
+    pub conjectured_field_security: f64,
+
+    /// The provable security limited by the size of the extension field.
+    pub provable_field_security: f64,
+
+    /// The security limited by the collision resistance of the hash function.
+    pub hash_security: f64,
+}
+
+impl SecurityEstimate {
+    pub fn new(parameters: StarkParameters, padded_height: usize) -> Self {
+        let fri = Stark::derive_fri(parameters, padded_height);
+        let log2_of_fri_domain_length = (fri.domain.length as f64).log2();
+        let log2_of_field_size = EXTENSION_DEGREE as f64 * LOG2_OF_BASE_FIELD_SIZE;
+        let num_fri_rounds = (fri.num_rounds() + 1) as f64;
+        let num_grinding_bits = parameters.num_grinding_bits as f64;
+        let num_collinearity_checks = parameters.num_collinearity_checks as f64;
+
+        let log2_of_expansion_factor = (parameters.fri_expansion_factor as f64).log2();
+        let conjectured_query_security =
+            num_collinearity_checks * log2_of_expansion_factor + num_grinding_bits;
+
+        // A query passes with probability at most √ρ·(1 + 1/2m), with ρ the code's rate.
+        let johnson_slack = 1.0 + 1.0 / (2.0 * JOHNSON_BOUND_MULTIPLICITY);
+        let log2_of_query_error = -0.5 * log2_of_expansion_factor + johnson_slack.log2();
+        let provable_query_security = -num_collinearity_checks * log2_of_query_error;
+        let provable_query_security = provable_query_security + num_grinding_bits;
+
+        // Each folding challenge and each other verifier challenge can hit a bad point, of which
+        // there are at most as many as the FRI domain is long.
+        let log2_of_num_bad_points = log2_of_fri_domain_length + num_fri_rounds.log2();
+        let conjectured_field_security = log2_of_field_size - log2_of_num_bad_points;
+
+        // The commit-phase error of FRI up to the Johnson bound is dominated by
+        // (m + 1/2)^7 / (3·ρ^(3/2)) · |D|^2 / |F| per round.
+        let multiplicity_term = 7.0 * (JOHNSON_BOUND_MULTIPLICITY + 0.5).log2();
+        let rate_term = 1.5 * log2_of_expansion_factor - 3_f64.log2();
+        let log2_of_commit_phase_error =
+            multiplicity_term + rate_term + 2.0 * log2_of_fri_domain_length + num_fri_rounds.log2()
+                - log2_of_field_size;
+        let provable_field_security = -log2_of_commit_phase_error;
+
+        // The birthday bound of Tip5's digests.
+        let hash_security = DIGEST_LENGTH as f64 * LOG2_OF_BASE_FIELD_SIZE / 2.0;
+
+        let conjectured_security_level = conjectured_query_security
+            .min(conjectured_field_security)
+            .min(hash_security);
+        let provable_security_level = provable_query_security
+            .min(provable_field_security)
+            .min(hash_security);
+
+        Self {
+            conjectured_security_level,
+            provable_security_level,
+            conjectured_query_security,
+            provable_query_security,
+            conjectured_field_security,
+            provable_field_security,
+            hash_security,
+        }
+    }
+}
+
+impl Display for SecurityEstimate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let conjectured = self.conjectured_security_level;
+        let provable = self.provable_security_level;
+        writeln!(
+            f,
+            "| {:<8} | {:>11} | {:>8} |",
+            "", "conjectured", "provable"
+        )?;
+        writeln!(f, "|:{:-<8}-|-{:->11}:|-{:->8}:|", "", "", "")?;
+        let conjectured_query = self.conjectured_query_security;
+        let provable_query = self.provable_query_security;
+        writeln!(
+            f,
+            "| {:<8} | {conjectured_query:>11.1} | {provable_query:>8.1} |",
+            "queries"
+        )?;
+        let conjectured_field = self.conjectured_field_security;
+        let provable_field = self.provable_field_security;
+        writeln!(
+            f,
+            "| {:<8} | {conjectured_field:>11.1} | {provable_field:>8.1} |",
+            "field"
+        )?;
+        let hash = self.hash_security;
+        writeln!(f, "| {:<8} | {hash:>11.1} | {hash:>8.1} |", "hash")?;
+        write!(
+            f,
+            "| {:<8} | {conjectured:>11.1} | {provable:>8.1} |",
+            "total"
+        )
+    }
+}
+
+/// The quantity to minimize when [searching for parameters](optimal_parameters).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OptimizationTarget {
+    /// Minimize the estimated size of the proof.
+    #[default]
+    ProofSize,
+
+    /// Minimize the estimated time it takes to generate a proof.
+    ProverTime,
+}
+
+/// Search for the zero-knowledge [`StarkParameters`] that minimize the given
+/// [target](OptimizationTarget) while achieving the given conjectured security level for
+/// executions of the given padded height. Considers FRI expansion factors from 2 to 64, FRI
+/// folding factors 2, 4, 8, and 16, and up to 24 bits of proof-of-work.
+///
+/// Returns `None` if no considered parameters achieve the security level, for example, because
+/// it exceeds the collision resistance of the hash function.
+pub fn optimal_parameters(
+    security_level: usize,
+    padded_height: usize,
+    target: OptimizationTarget,
+) -> Option<StarkParameters> {
+    let log2_of_fri_expansion_factors = 1..=6;
+    let fri_folding_factors = [2, 4, 8, 16];
+    let nums_grinding_bits = 0..=MAX_NUM_GRINDING_BITS;
+
+    let candidates = iproduct!(
+        log2_of_fri_expansion_factors,
+        fri_folding_factors,
+        nums_grinding_bits
+    )
+    .filter_map(
+        |(log2_of_fri_expansion_factor, fri_folding_factor, num_grinding_bits)| {
+            let parameters = StarkParameters::new(security_level, log2_of_fri_expansion_factor)
+                .with_fri_folding_factor(fri_folding_factor)
+                .with_num_grinding_bits(num_grinding_bits);
+            sufficiently_secure(parameters, padded_height)
+        },
+    );
+
+    let cost = |parameters: &StarkParameters| match target {
+        OptimizationTarget::ProofSize => estimated_proof_size(*parameters, padded_height),
+        OptimizationTarget::ProverTime => estimated_prover_cost(*parameters, padded_height),
+    };
+    candidates.min_by(|left, right| cost(left).total_cmp(&cost(right)))
+}
+
+/// Add collinearity checks to the given parameters until they achieve their security level.
+/// Returns `None` if that is impossible, _e.g._, because some other term is too small.
+fn sufficiently_secure(
+    parameters: StarkParameters,
+    padded_height: usize,
+) -> Option<StarkParameters> {
+    let target_security_level = parameters.security_level as f64;
+    let estimate = SecurityEstimate::new(parameters, padded_height);
+    if estimate.conjectured_security_level >= target_security_level {
+        return Some(parameters);
+    }
+    if estimate.conjectured_query_security >= target_security_level {
+        return None;
+    }
+
+    let num_collinearity_checks = parameters.num_collinearity_checks + 1;
+    let parameters = parameters.with_num_collinearity_checks(num_collinearity_checks);
+    let estimate = SecurityEstimate::new(parameters, padded_height);
+    match estimate.conjectured_security_level >= target_security_level {
+        true => Some(parameters),
+        false => None,
+    }
+}
+
+/// An upper bound on the size of a proof, in number of base field elements. Does not account for
+/// the deduplication of nodes in authentication structures.
+pub fn estimated_proof_size(parameters: StarkParameters, padded_height: usize) -> f64 {
+    let fri = Stark::derive_fri(parameters, padded_height);
+    let log2_of_fri_domain_length = fri.domain.length.ilog2() as usize;
+    let num_combination_codeword_checks = parameters.num_combination_codeword_checks;
+
+    let num_ext_columns = NUM_EXT_COLUMNS + parameters.num_randomizer_polynomials;
+    let row_width = NUM_BASE_COLUMNS + EXTENSION_DEGREE * (num_ext_columns + NUM_QUOTIENT_SEGMENTS);
+    let num_opened_trees = 3;
+    let authentication_path_size = log2_of_fri_domain_length * DIGEST_LENGTH;
+    let openings_size =
+        num_combination_codeword_checks * (row_width + num_opened_trees * authentication_path_size);
+
+    let num_collinearity_checks = parameters.num_collinearity_checks;
+    let folding_factor = fri.folding_factor;
+    let log2_of_folding_factor = folding_factor.ilog2() as usize;
+    let fri_queries_size: usize = (0..fri.num_rounds())
+        .map(|round| {
+            let log2_of_num_leaves =
+                log2_of_fri_domain_length - (round + 1) * log2_of_folding_factor;
+            let leaf_size = folding_factor * EXTENSION_DEGREE;
+            let authentication_path_size = log2_of_num_leaves * DIGEST_LENGTH;
+            num_collinearity_checks * (leaf_size + authentication_path_size)
+        })
+        .sum();
+    let last_round_codeword_length =
+        fri.domain.length >> (fri.num_rounds() * log2_of_folding_factor);
+    let last_round_codeword_size = last_round_codeword_length * EXTENSION_DEGREE;
+    let merkle_roots_size = (num_opened_trees + fri.num_rounds() + 1) * DIGEST_LENGTH;
+
+    (openings_size + fri_queries_size + last_round_codeword_size + merkle_roots_size) as f64
+}
+
+/// A relative measure of the time it takes to generate a proof, in number of field operations.
+/// Accounts for the low-degree extension and hashing of all columns, and for the proof-of-work.
+pub fn estimated_prover_cost(parameters: StarkParameters, padded_height: usize) -> f64 {
+    let fri = Stark::derive_fri(parameters, padded_height);
+    let fri_domain_length = fri.domain.length as f64;
+    let num_ext_columns = NUM_EXT_COLUMNS + parameters.num_randomizer_polynomials;
+    let num_columns =
+        NUM_BASE_COLUMNS + EXTENSION_DEGREE * (num_ext_columns + NUM_QUOTIENT_SEGMENTS);
+    let num_columns = num_columns as f64;
+
+    let low_degree_extension_cost = num_columns * fri_domain_length * fri_domain_length.log2();
+
+    // A permutation of Tip5 costs roughly as much as a thousand field operations. Every row is
+    // hashed once, the proof-of-work takes 2 permutations per attempt.
+    let cost_of_permutation = 1000.0;
+    let hashing_cost = fri_domain_length * num_columns / 10.0 * cost_of_permutation;
+    let num_grinding_attempts = 2_f64.powi(parameters.num_grinding_bits as i32);
+    let grinding_cost = 2.0 * num_grinding_attempts * cost_of_permutation;
+
+    low_degree_extension_cost + hashing_cost + grinding_cost
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::assert;
+    use assert2::let_assert;
+
+    use super::*;
+
+    #[test]
+    fn default_parameters_achieve_their_conjectured_security_level() {
+        let parameters = StarkParameters::default();
+        let estimate = SecurityEstimate::new(parameters, 1 << 16);
+        assert!(estimate.conjectured_security_level >= 159.9);
+        assert!(estimate.provable_security_level < estimate.conjectured_security_level);
+        println!("{estimate}");
+    }
+
+    #[test]
+    fn proof_of_work_makes_up_for_fewer_collinearity_checks() {
+        let parameters = StarkParameters::default();
+        let parameters_with_grinding = parameters.with_num_grinding_bits(20);
+        assert!(
+            parameters_with_grinding.num_collinearity_checks < parameters.num_collinearity_checks
+        );
+
+        let padded_height = 1 << 10;
+        let estimate = SecurityEstimate::new(parameters, padded_height);
+        let estimate_with_grinding = SecurityEstimate::new(parameters_with_grinding, padded_height);
+        let security_level = estimate.conjectured_security_level;
+        assert!(estimate_with_grinding.conjectured_security_level >= security_level);
+    }
+
+    #[test]
+    fn optimal_parameters_achieve_security_level_at_no_more_cost_than_default() {
+        let security_level = 160;
+        let padded_height = 1 << 12;
+        let default_parameters = StarkParameters::default();
+
+        let target = OptimizationTarget::ProofSize;
+        let_assert!(Some(parameters) = optimal_parameters(security_level, padded_height, target));
+        let estimate = SecurityEstimate::new(parameters, padded_height);
+        assert!(estimate.conjectured_security_level >= security_level as f64);
+        let proof_size = estimated_proof_size(parameters, padded_height);
+        assert!(proof_size <= estimated_proof_size(default_parameters, padded_height));
+
+        let target = OptimizationTarget::ProverTime;
+        let_assert!(Some(parameters) = optimal_parameters(security_level, padded_height, target));
+        let estimate = SecurityEstimate::new(parameters, padded_height);
+        assert!(estimate.conjectured_security_level >= security_level as f64);
+        let prover_cost = estimated_prover_cost(parameters, padded_height);
+        assert!(prover_cost <= estimated_prover_cost(default_parameters, padded_height));
+    }
+
+    #[test]
+    fn security_level_beyond_hash_collision_resistance_is_unachievable() {
+        let target = OptimizationTarget::ProofSize;
+        assert!(let None = optimal_parameters(200, 1 << 10, target));
+    }
+}
@@ -1,5 +1,10 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::Read;
 use std::ops::Add;
 use std::ops::Mul;
+use std::path::PathBuf;
+use std::sync::Mutex;
 
 use arbitrary::Arbitrary;
 use arbitrary::Unstructured;
@@ -28,17 +33,23 @@ use twenty_first::util_types::merkle_tree_maker::MerkleTreeMaker;
 
 use crate::aet::AlgebraicExecutionTrace;
 use crate::arithmetic_domain::ArithmeticDomain;
+use crate::error::ProvingError;
 use crate::error::VerificationError;
 use crate::error::VerificationError::*;
+use crate::error::VerificationErrorContext;
 use crate::fri::Fri;
 use crate::profiler::prof_itr0;
 use crate::profiler::prof_start;
 use crate::profiler::prof_stop;
 use crate::profiler::TritonProfiler;
+use crate::progress::ProvingHandle;
+use crate::progress::ProvingStage;
 use crate::proof::Claim;
 use crate::proof::Proof;
 use crate::proof_item::ProofItem;
+use crate::proof_reader::ProofReader;
 use crate::proof_stream::ProofStream;
+use crate::proof_stream::VerifierProofStream;
 use crate::table::challenges::Challenges;
 use crate::table::extension_table::Evaluable;
 use crate::table::master_table::*;
@@ -56,6 +67,10 @@ pub(crate) const NUM_QUOTIENT_SEGMENTS: usize = AIR_TARGET_DEGREE as usize;
 
 const NUM_DEEP_CODEWORD_COMPONENTS: usize = 3;
 
+/// The largest padded height for which [batch proving](Stark::prove_batch) generates multiple
+/// proofs in parallel. Proving longer traces saturates all cores on its own.
+const MAX_SHORT_PADDED_HEIGHT: usize = 1 << 12;
+
 /// All the security-related parameters for the zk-STARK.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash)]
 pub struct StarkParameters {
@@ -77,13 +92,30 @@ pub struct StarkParameters {
     /// in all cases. It is integral for achieving zero-knowledge for the FRI part of the zk-STARK.
     pub num_randomizer_polynomials: usize,
 
+    /// The factor by which FRI folds the codeword in each round. Must be a power of 2. Larger
+    /// folding factors mean fewer FRI rounds and authentication paths, but more revealed
+    /// elements per collinearity check.
+    pub fri_folding_factor: usize,
+
     /// The number of collinearity checks to perform in FRI.
     pub num_collinearity_checks: usize,
 
+    /// The number of bits of proof-of-work the prover has to perform before the indices of the
+    /// collinearity checks are sampled. Every bit of proof-of-work replaces one bit of security
+    /// that would otherwise have to come from collinearity checks.
+    /// See also [`with_num_grinding_bits`](Self::with_num_grinding_bits).
+    pub num_grinding_bits: usize,
+
     /// The number of combination codeword checks. These checks link the (DEEP) ALI part and the
     /// FRI part of the zk-STARK. The number of combination codeword checks directly depends on the
     /// number of collinearity checks and the FRI folding factor.
     pub num_combination_codeword_checks: usize,
+
+    /// Whether the generated proofs hide the secret input. If not, there are neither trace
+    /// randomizers nor randomizer polynomials, which makes proving faster and proofs smaller.
+    /// The mode is recorded in the proof; the verifier rejects proofs of the other mode.
+    /// See also [`without_zero_knowledge`](Self::without_zero_knowledge).
+    pub zero_knowledge: bool,
 }
 
 impl StarkParameters {
@@ -97,23 +129,106 @@ impl StarkParameters {
         let fri_expansion_factor = 1 << log2_of_fri_expansion_factor;
         let num_collinearity_checks = security_level / log2_of_fri_expansion_factor;
 
-        // For now, the FRI folding factor is hardcoded in our zk-STARK.
+        // Can be changed through `with_fri_folding_factor`.
         let fri_folding_factor = 2;
         let num_combination_codeword_checks = num_collinearity_checks * fri_folding_factor;
 
-        let num_out_of_domain_rows = 2;
-        let num_trace_randomizers = num_combination_codeword_checks
-            + num_out_of_domain_rows * x_field_element::EXTENSION_DEGREE;
+        let num_trace_randomizers = Self::num_trace_randomizers(num_combination_codeword_checks);
 
         StarkParameters {
             security_level,
             fri_expansion_factor,
             num_trace_randomizers,
             num_randomizer_polynomials,
+            fri_folding_factor,
             num_collinearity_checks,
+            num_grinding_bits: 0,
             num_combination_codeword_checks,
+            zero_knowledge: true,
         }
     }
+
+    /// Set the factor by which FRI folds the codeword in each round, _e.g._, 2, 4, 8, or 16.
+    /// Every collinearity check opens one element of the combination codeword per unit of the
+    /// folding factor, which the number of combination codeword checks and, for zero-knowledge,
+    /// the number of trace randomizers account for.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the folding factor is not a power of 2 greater than 1.
+    #[must_use]
+    pub fn with_fri_folding_factor(self, fri_folding_factor: usize) -> Self {
+        assert!(
+            fri_folding_factor > 1 && fri_folding_factor.is_power_of_two(),
+            "FRI folding factor must be a power of 2 greater than 1, but is {fri_folding_factor}."
+        );
+
+        let parameters = Self {
+            fri_folding_factor,
+            ..self
+        };
+        parameters.with_num_collinearity_checks(self.num_collinearity_checks)
+    }
+
+    /// Require the prover to perform `num_grinding_bits` bits of proof-of-work before the indices
+    /// of the collinearity checks are sampled. This reduces the number of collinearity checks
+    /// required for the security level and thus the proof size, at the cost of prover time
+    /// exponential in the number of grinding bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of grinding bits is 64 or larger.
+    #[must_use]
+    pub fn with_num_grinding_bits(self, num_grinding_bits: usize) -> Self {
+        assert!(
+            num_grinding_bits < 64,
+            "Number of grinding bits must be smaller than 64, but is {num_grinding_bits}."
+        );
+
+        let log2_of_fri_expansion_factor = self.fri_expansion_factor.ilog2() as usize;
+        let security_level_from_queries = self.security_level.saturating_sub(num_grinding_bits);
+        let num_collinearity_checks = security_level_from_queries / log2_of_fri_expansion_factor;
+        let parameters = Self {
+            num_grinding_bits,
+            ..self
+        };
+        parameters.with_num_collinearity_checks(num_collinearity_checks)
+    }
+
+    /// Set the number of collinearity checks and everything that depends on it.
+    pub(crate) fn with_num_collinearity_checks(self, num_collinearity_checks: usize) -> Self {
+        let num_combination_codeword_checks = num_collinearity_checks * self.fri_folding_factor;
+        let num_trace_randomizers = match self.zero_knowledge {
+            true => Self::num_trace_randomizers(num_combination_codeword_checks),
+            false => 0,
+        };
+        Self {
+            num_collinearity_checks,
+            num_combination_codeword_checks,
+            num_trace_randomizers,
+            ..self
+        }
+    }
+
+    /// Disable zero-knowledge. Intended for development, where proofs are generated often and
+    /// the secret input does not need to be hidden. Dropping the trace randomizers can shrink the
+    /// randomized trace domain, and with it the FRI domain, by a factor of 2.
+    ///
+    /// Proofs generated with these parameters do **not** hide the secret input.
+    #[must_use]
+    pub fn without_zero_knowledge(self) -> Self {
+        Self {
+            num_trace_randomizers: 0,
+            num_randomizer_polynomials: 0,
+            zero_knowledge: false,
+            ..self
+        }
+    }
+
+    fn num_trace_randomizers(num_combination_codeword_checks: usize) -> usize {
+        let num_out_of_domain_rows = 2;
+        num_combination_codeword_checks + num_out_of_domain_rows * x_field_element::EXTENSION_DEGREE
+    }
 }
 
 impl Default for StarkParameters {
@@ -129,10 +244,71 @@ impl<'a> Arbitrary<'a> for StarkParameters {
     fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
         let security_level = u.int_in_range(1..=640)?;
         let log_2_of_fri_expansion_factor = u.int_in_range(1..=8)?;
-        Ok(Self::new(security_level, log_2_of_fri_expansion_factor))
+        let log_2_of_fri_folding_factor = u.int_in_range(1..=4)?;
+        let parameters = Self::new(security_level, log_2_of_fri_expansion_factor)
+            .with_fri_folding_factor(1 << log_2_of_fri_folding_factor);
+        match u.arbitrary()? {
+            true => Ok(parameters),
+            false => Ok(parameters.without_zero_knowledge()),
+        }
     }
 }
 
+/// Configuration of the prover that does not influence the security or the verifiability of the
+/// generated proof, but the resources used to generate it.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct ProverConfig {
+    pub low_degree_extension_mode: LowDegreeExtensionMode,
+
+    /// If set, the master tables and their low-degree extensions are backed by memory-mapped
+    /// files in this directory instead of living on the heap. This allows proving executions
+    /// whose traces do not fit into memory, at the cost of disk I/O. Combine with
+    /// [`LowDegreeExtensionMode::Recompute`] to keep the in-memory footprint minimal.
+    pub scratch_directory: Option<PathBuf>,
+
+    /// If set, all of the prover's randomness – the trace randomizers and the randomizer
+    /// polynomials – is derived from this seed using ChaCha, making proofs of the same claim
+    /// byte-for-byte identical. Useful for test fixtures and for debugging. If unset, randomness
+    /// is drawn from the operating system.
+    ///
+    /// Zero-knowledge hinges on the randomness being unpredictable. Never use a fixed or
+    /// guessable seed for proofs that need to hide the secret input.
+    pub randomness_seed: Option<RandomnessSeed>,
+}
+
+/// Configuration of the verifier that does not influence the verdict, but how a failed
+/// verification is reported.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VerifierConfig {
+    /// If set, errors carry context about the failure, like the FRI round, the index of the
+    /// failing query, or the received and the computed values, wrapped in
+    /// [`VerificationError::WithContext`] and [`FriValidationError::WithContext`]. This helps to
+    /// tell a serialization bug or a parameter mismatch apart from a genuinely bad proof.
+    ///
+    /// [`FriValidationError::WithContext`]: crate::error::FriValidationError::WithContext
+    pub verbose_errors: bool,
+}
+
+/// How the prover deals with the low-degree extensions of the master tables' columns.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LowDegreeExtensionMode {
+    /// Compute the low-degree extension of every column once and keep it in memory. This is the
+    /// fastest mode, but its memory footprint grows with the length of the larger one of the FRI
+    /// domain and the quotient domain, times the number of columns.
+    #[default]
+    Memoize,
+
+    /// Keep only the polynomials interpolating the columns. Whenever the low-degree extension is
+    /// needed – to hash rows for the Merkle trees, to compute the quotients, or to open rows – it
+    /// is recomputed in `num_chunks` many parts, only one of which is kept in memory at any time.
+    /// This trades computation time for memory.
+    ///
+    /// The number of chunks must be a power of 2, else proving fails with
+    /// [`ProvingError::InvalidNumberOfChunks`]. It is reduced where a domain is too short to be
+    /// split into that many chunks.
+    Recompute { num_chunks: usize },
+}
+
 pub struct Stark {}
 
 impl Stark {
@@ -142,6 +318,145 @@ impl Stark {
         aet: &AlgebraicExecutionTrace,
         maybe_profiler: &mut Option<TritonProfiler>,
     ) -> Proof {
+        let handle = ProvingHandle::new();
+        Self::prove_with_handle(parameters, claim, aet, &handle, maybe_profiler)
+            .expect("proving without cancellation must succeed")
+    }
+
+    /// Like [`prove`](Self::prove), but reports the prover's progress to the given handle. If
+    /// the handle is [cancelled](ProvingHandle::cancel), proof generation stops early and
+    /// [`ProvingError::Cancelled`] is returned.
+    pub fn prove_with_handle(
+        parameters: StarkParameters,
+        claim: &Claim,
+        aet: &AlgebraicExecutionTrace,
+        handle: &ProvingHandle,
+        maybe_profiler: &mut Option<TritonProfiler>,
+    ) -> Result<Proof, ProvingError> {
+        let config = ProverConfig::default();
+        Self::prove_with_config(parameters, claim, aet, &config, handle, maybe_profiler)
+    }
+
+    /// Like [`prove_with_handle`](Self::prove_with_handle), but uses the given prover
+    /// configuration. The configuration has no influence on the validity of the proof.
+    pub fn prove_with_config(
+        parameters: StarkParameters,
+        claim: &Claim,
+        aet: &AlgebraicExecutionTrace,
+        config: &ProverConfig,
+        handle: &ProvingHandle,
+        maybe_profiler: &mut Option<TritonProfiler>,
+    ) -> Result<Proof, ProvingError> {
+        Self::prove_with_shared_work(parameters, claim, aet, config, handle, None, maybe_profiler)
+    }
+
+    /// Prove many executions of the same program. Each element of `instances` is a [`Claim`]
+    /// together with the [`AlgebraicExecutionTrace`] witnessing it. The returned proofs are in
+    /// the same order as the instances, and each of them is identical to the proof
+    /// [`prove_with_config`](Self::prove_with_config) would produce for the same instance.
+    ///
+    /// Work that does not depend on the individual trace is done only once: the program's digest
+    /// is computed once, and the zerofier inverses are computed once per padded height. Instances
+    /// with a short trace are proven in parallel; instances with a long trace are proven one
+    /// after another, since each of them is parallelized internally already.
+    ///
+    /// Fails with [`ProvingError::ProgramDigestMismatch`] if the instances do not all belong to
+    /// the same program, and with [`ProvingError::BatchInstanceFailure`] if proving any
+    /// individual instance fails.
+    pub fn prove_batch(
+        parameters: StarkParameters,
+        instances: &[(Claim, AlgebraicExecutionTrace)],
+        config: &ProverConfig,
+        handle: &ProvingHandle,
+    ) -> Result<Vec<Proof>, ProvingError> {
+        let Some((_, first_aet)) = instances.first() else {
+            return Ok(vec![]);
+        };
+        let program_digest = first_aet.program.hash::<StarkHasher>();
+        for (claim, aet) in instances {
+            if claim.program_digest != program_digest || aet.program != first_aet.program {
+                return Err(ProvingError::ProgramDigestMismatch);
+            }
+        }
+
+        let shared_work = match config.low_degree_extension_mode {
+            LowDegreeExtensionMode::Memoize => instances
+                .iter()
+                .map(|(_, aet)| aet.padded_height())
+                .unique()
+                .map(|padded_height| {
+                    let zerofier_inverses = Self::zerofier_inverses(parameters, padded_height);
+                    (padded_height, zerofier_inverses)
+                })
+                .collect(),
+            LowDegreeExtensionMode::Recompute { .. } => HashMap::new(),
+        };
+
+        let prove_instance = |index: usize| {
+            let (claim, aet) = &instances[index];
+            let zerofier_inverses = shared_work.get(&aet.padded_height());
+            let proof = Self::prove_with_shared_work(
+                parameters,
+                claim,
+                aet,
+                config,
+                handle,
+                zerofier_inverses,
+                &mut None,
+            );
+            proof.map_err(|source| {
+                let source = Box::new(source);
+                ProvingError::BatchInstanceFailure { index, source }
+            })
+        };
+
+        let (short_instances, long_instances): (Vec<_>, Vec<_>) = (0..instances.len())
+            .partition(|&index| instances[index].1.padded_height() <= MAX_SHORT_PADDED_HEIGHT);
+        let short_proofs = short_instances
+            .par_iter()
+            .map(|&index| prove_instance(index).map(|proof| (index, proof)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let long_proofs = long_instances
+            .iter()
+            .map(|&index| prove_instance(index).map(|proof| (index, proof)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let proofs = short_proofs
+            .into_iter()
+            .chain(long_proofs)
+            .sorted_by_key(|&(index, _)| index)
+            .map(|(_, proof)| proof)
+            .collect();
+        Ok(proofs)
+    }
+
+    /// The [`ZerofierInverses`] used by the prover for traces of the given padded height.
+    fn zerofier_inverses(parameters: StarkParameters, padded_height: usize) -> ZerofierInverses {
+        let max_degree = Self::derive_max_degree(padded_height, parameters.num_trace_randomizers);
+        let fri = Self::derive_fri(parameters, padded_height);
+        let quotient_domain = Self::quotient_domain(fri.domain, max_degree);
+        let trace_domain = ArithmeticDomain::of_length(padded_height);
+        ZerofierInverses::new(trace_domain, quotient_domain)
+    }
+
+    /// Like [`prove_with_config`](Self::prove_with_config), but uses the given zerofier
+    /// inverses, if any. They must belong to the trace's padded height.
+    fn prove_with_shared_work(
+        parameters: StarkParameters,
+        claim: &Claim,
+        aet: &AlgebraicExecutionTrace,
+        config: &ProverConfig,
+        handle: &ProvingHandle,
+        maybe_zerofier_inverses: Option<&ZerofierInverses>,
+        maybe_profiler: &mut Option<TritonProfiler>,
+    ) -> Result<Proof, ProvingError> {
+        let lde_mode = config.low_degree_extension_mode;
+        if let LowDegreeExtensionMode::Recompute { num_chunks } = lde_mode {
+            if !num_chunks.is_power_of_two() {
+                return Err(ProvingError::InvalidNumberOfChunks(num_chunks));
+            }
+        }
+
         prof_start!(maybe_profiler, "Fiat-Shamir: claim", "hash");
         let mut proof_stream = StarkProofStream::new();
         proof_stream.alter_fiat_shamir_state_with(claim);
@@ -152,17 +467,21 @@ impl Stark {
         let max_degree = Self::derive_max_degree(padded_height, parameters.num_trace_randomizers);
         let fri = Self::derive_fri(parameters, padded_height);
         let quotient_domain = Self::quotient_domain(fri.domain, max_degree);
+        proof_stream.enqueue(ProofItem::ZeroKnowledge(parameters.zero_knowledge));
         proof_stream.enqueue(ProofItem::Log2PaddedHeight(padded_height.ilog2()));
         prof_stop!(maybe_profiler, "derive additional parameters");
 
+        handle.enter_stage(ProvingStage::BaseTableCreation)?;
         prof_start!(maybe_profiler, "base tables");
         prof_start!(maybe_profiler, "create", "gen");
-        let mut master_base_table = MasterBaseTable::new(
+        let mut master_base_table = MasterBaseTable::new_with_scratch_directory(
             aet,
             parameters.num_trace_randomizers,
             quotient_domain,
             fri.domain,
-        );
+            config.scratch_directory.as_deref(),
+        )?
+        .with_randomness_seed(config.randomness_seed);
         prof_stop!(maybe_profiler, "create");
 
         prof_start!(maybe_profiler, "pad", "gen");
@@ -173,12 +492,29 @@ impl Stark {
         master_base_table.randomize_trace();
         prof_stop!(maybe_profiler, "randomize trace");
 
+        handle.enter_stage(ProvingStage::BaseTableLowDegreeExtension)?;
         prof_start!(maybe_profiler, "LDE", "LDE");
-        master_base_table.low_degree_extend_all_columns();
+        match lde_mode {
+            LowDegreeExtensionMode::Memoize => {
+                master_base_table.low_degree_extend_all_columns(handle)?
+            }
+            LowDegreeExtensionMode::Recompute { .. } => {
+                master_base_table.interpolate_all_columns(handle)?
+            }
+        }
         prof_stop!(maybe_profiler, "LDE");
 
+        handle.enter_stage(ProvingStage::BaseTableMerkleTree)?;
         prof_start!(maybe_profiler, "Merkle tree", "hash");
-        let base_merkle_tree = master_base_table.merkle_tree(maybe_profiler);
+        let base_merkle_tree = match lde_mode {
+            LowDegreeExtensionMode::Memoize => {
+                master_base_table.merkle_tree(handle, maybe_profiler)?
+            }
+            LowDegreeExtensionMode::Recompute { num_chunks } => {
+                let num_chunks = num_chunks.min(fri.domain.length);
+                master_base_table.merkle_tree_in_chunks(num_chunks, handle, maybe_profiler)?
+            }
+        };
         prof_stop!(maybe_profiler, "Merkle tree");
 
         prof_start!(maybe_profiler, "Fiat-Shamir", "hash");
@@ -187,9 +523,10 @@ impl Stark {
         let challenges = Challenges::new(challenges, claim);
         prof_stop!(maybe_profiler, "Fiat-Shamir");
 
+        handle.enter_stage(ProvingStage::ExtensionTableCreation)?;
         prof_start!(maybe_profiler, "extend", "gen");
         let mut master_ext_table =
-            master_base_table.extend(&challenges, parameters.num_randomizer_polynomials);
+            master_base_table.try_extend(&challenges, parameters.num_randomizer_polynomials)?;
         prof_stop!(maybe_profiler, "extend");
 
         prof_start!(maybe_profiler, "randomize trace", "gen");
@@ -197,13 +534,30 @@ impl Stark {
         prof_stop!(maybe_profiler, "randomize trace");
         prof_stop!(maybe_profiler, "base tables");
 
+        handle.enter_stage(ProvingStage::ExtensionTableLowDegreeExtension)?;
         prof_start!(maybe_profiler, "ext tables");
         prof_start!(maybe_profiler, "LDE", "LDE");
-        master_ext_table.low_degree_extend_all_columns();
+        match lde_mode {
+            LowDegreeExtensionMode::Memoize => {
+                master_ext_table.low_degree_extend_all_columns(handle)?
+            }
+            LowDegreeExtensionMode::Recompute { .. } => {
+                master_ext_table.interpolate_all_columns(handle)?
+            }
+        }
         prof_stop!(maybe_profiler, "LDE");
 
+        handle.enter_stage(ProvingStage::ExtensionTableMerkleTree)?;
         prof_start!(maybe_profiler, "Merkle tree", "hash");
-        let ext_merkle_tree = master_ext_table.merkle_tree(maybe_profiler);
+        let ext_merkle_tree = match lde_mode {
+            LowDegreeExtensionMode::Memoize => {
+                master_ext_table.merkle_tree(handle, maybe_profiler)?
+            }
+            LowDegreeExtensionMode::Recompute { num_chunks } => {
+                let num_chunks = num_chunks.min(fri.domain.length);
+                master_ext_table.merkle_tree_in_chunks(num_chunks, handle, maybe_profiler)?
+            }
+        };
         prof_stop!(maybe_profiler, "Merkle tree");
 
         prof_start!(maybe_profiler, "Fiat-Shamir", "hash");
@@ -211,37 +565,65 @@ impl Stark {
         prof_stop!(maybe_profiler, "Fiat-Shamir");
         prof_stop!(maybe_profiler, "ext tables");
 
-        prof_start!(maybe_profiler, "quotient-domain codewords");
-        let base_quotient_domain_codewords = master_base_table.quotient_domain_table();
-        let ext_quotient_domain_codewords = master_ext_table.quotient_domain_table();
-        prof_stop!(maybe_profiler, "quotient-domain codewords");
-
-        prof_start!(maybe_profiler, "quotient codewords");
-        let master_quotient_table = all_quotients(
-            base_quotient_domain_codewords,
-            ext_quotient_domain_codewords,
-            master_base_table.trace_domain(),
-            quotient_domain,
-            &challenges,
-            maybe_profiler,
-        );
-        prof_stop!(maybe_profiler, "quotient codewords");
-
-        prof_start!(maybe_profiler, "linearly combine quotient codewords", "CC");
-        // Create quotient codeword. This is a part of the combination codeword. To reduce the
-        // amount of hashing necessary, the quotient codeword is linearly summed instead of
-        // hashed prior to committing to it.
-        let quotient_combination_weights = proof_stream.sample_scalars(num_quotients());
-        let quotient_combination_weights = Array1::from(quotient_combination_weights);
-        assert_eq!(
-            quotient_combination_weights.len(),
-            master_quotient_table.ncols()
-        );
+        handle.enter_stage(ProvingStage::Quotients)?;
+        let quotient_codeword = match lde_mode {
+            LowDegreeExtensionMode::Memoize => {
+                prof_start!(maybe_profiler, "quotient-domain codewords");
+                let base_quotient_domain_codewords = master_base_table.quotient_domain_table();
+                let ext_quotient_domain_codewords = master_ext_table.quotient_domain_table();
+                prof_stop!(maybe_profiler, "quotient-domain codewords");
+
+                prof_start!(maybe_profiler, "quotient codewords");
+                let trace_domain = master_base_table.trace_domain();
+                let zerofier_inverses = match maybe_zerofier_inverses {
+                    Some(zerofier_inverses) => Cow::Borrowed(zerofier_inverses),
+                    None => Cow::Owned(ZerofierInverses::new(trace_domain, quotient_domain)),
+                };
+                let master_quotient_table = all_quotients_with_zerofier_inverses(
+                    base_quotient_domain_codewords,
+                    ext_quotient_domain_codewords,
+                    trace_domain,
+                    quotient_domain,
+                    &zerofier_inverses,
+                    &challenges,
+                    handle,
+                    maybe_profiler,
+                )?;
+                prof_stop!(maybe_profiler, "quotient codewords");
+
+                prof_start!(maybe_profiler, "linearly combine quotient codewords", "CC");
+                // Create quotient codeword. This is a part of the combination codeword. To reduce
+                // the amount of hashing necessary, the quotient codeword is linearly summed
+                // instead of hashed prior to committing to it.
+                let quotient_combination_weights = proof_stream.sample_scalars(num_quotients());
+                let quotient_combination_weights = Array1::from(quotient_combination_weights);
+                assert_eq!(
+                    quotient_combination_weights.len(),
+                    master_quotient_table.ncols()
+                );
 
-        let quotient_codeword =
-            Self::random_linear_sum(master_quotient_table.view(), quotient_combination_weights);
+                let quotient_codeword = Self::random_linear_sum(
+                    master_quotient_table.view(),
+                    quotient_combination_weights,
+                );
+                prof_stop!(maybe_profiler, "linearly combine quotient codewords");
+                quotient_codeword
+            }
+            LowDegreeExtensionMode::Recompute { num_chunks } => {
+                let quotient_combination_weights = proof_stream.sample_scalars(num_quotients());
+                Self::quotient_codeword_in_chunks(
+                    &master_base_table,
+                    &master_ext_table,
+                    quotient_domain,
+                    quotient_combination_weights.into(),
+                    num_chunks,
+                    &challenges,
+                    handle,
+                    maybe_profiler,
+                )?
+            }
+        };
         assert_eq!(quotient_domain.length, quotient_codeword.len());
-        prof_stop!(maybe_profiler, "linearly combine quotient codewords");
 
         prof_start!(maybe_profiler, "commit to quotient codeword segments");
         prof_start!(maybe_profiler, "LDE", "LDE");
@@ -253,6 +635,9 @@ impl Stark {
         prof_start!(maybe_profiler, "hash rows of quotient segments", "hash");
         let interpret_xfe_as_bfes = |xfe: &XFieldElement| xfe.coefficients.to_vec();
         let hash_row = |row: ArrayView1<_>| {
+            if handle.is_cancelled() {
+                return Digest::default();
+            }
             let row_as_bfes = row.iter().map(interpret_xfe_as_bfes).concat();
             StarkHasher::hash_varlen(&row_as_bfes)
         };
@@ -261,6 +646,7 @@ impl Stark {
             .into_par_iter();
         let fri_domain_quotient_segment_codewords_digests =
             quotient_segments_rows.map(hash_row).collect::<Vec<_>>();
+        handle.check_cancellation()?;
         prof_stop!(maybe_profiler, "hash rows of quotient segments");
         prof_start!(maybe_profiler, "Merkle tree", "hash");
         let quot_merkle_tree: MerkleTree<StarkHasher> =
@@ -315,17 +701,6 @@ impl Stark {
             true => fri.domain,
             false => quotient_domain,
         };
-        let short_domain_base_codewords = match fri_domain_is_short_domain {
-            true => master_base_table.fri_domain_table(),
-            false => master_base_table.quotient_domain_table(),
-        };
-        let short_domain_ext_codewords = match fri_domain_is_short_domain {
-            true => master_ext_table.fri_domain_table(),
-            false => master_ext_table.quotient_domain_table(),
-        };
-        let short_domain_ext_codewords =
-            short_domain_ext_codewords.slice(s![.., ..NUM_EXT_COLUMNS]);
-
         let fri_to_quotient_domain_unit_distance = match fri_domain_is_short_domain {
             true => 1,
             false => fri.domain.length / quotient_domain.length,
@@ -333,15 +708,43 @@ impl Stark {
         let short_domain_quot_segment_codewords = fri_domain_quotient_segment_codewords
             .slice(s![..; fri_to_quotient_domain_unit_distance, ..]);
 
+        handle.enter_stage(ProvingStage::Deep)?;
         prof_start!(maybe_profiler, "linear combination");
-        prof_start!(maybe_profiler, "base", "CC");
-        let base_codeword =
-            Self::random_linear_sum_base_field(short_domain_base_codewords, base_weights);
-        prof_stop!(maybe_profiler, "base");
-        prof_start!(maybe_profiler, "ext", "CC");
-        let ext_codeword = Self::random_linear_sum(short_domain_ext_codewords, ext_weights);
-        prof_stop!(maybe_profiler, "ext");
-        let base_and_ext_codeword = base_codeword + ext_codeword;
+        let base_and_ext_codeword = match lde_mode {
+            LowDegreeExtensionMode::Memoize => {
+                let short_domain_base_codewords = match fri_domain_is_short_domain {
+                    true => master_base_table.fri_domain_table(),
+                    false => master_base_table.quotient_domain_table(),
+                };
+                let short_domain_ext_codewords = match fri_domain_is_short_domain {
+                    true => master_ext_table.fri_domain_table(),
+                    false => master_ext_table.quotient_domain_table(),
+                };
+                let short_domain_ext_codewords =
+                    short_domain_ext_codewords.slice(s![.., ..NUM_EXT_COLUMNS]);
+
+                prof_start!(maybe_profiler, "base", "CC");
+                let base_codeword =
+                    Self::random_linear_sum_base_field(short_domain_base_codewords, base_weights);
+                prof_stop!(maybe_profiler, "base");
+                prof_start!(maybe_profiler, "ext", "CC");
+                let ext_codeword = Self::random_linear_sum(short_domain_ext_codewords, ext_weights);
+                prof_stop!(maybe_profiler, "ext");
+                base_codeword + ext_codeword
+            }
+            LowDegreeExtensionMode::Recompute { .. } => {
+                prof_start!(maybe_profiler, "base&ext", "CC");
+                let base_and_ext_polynomial = Self::random_linear_sum_of_interpolation_polynomials(
+                    &master_base_table,
+                    &master_ext_table,
+                    base_weights,
+                    ext_weights,
+                );
+                let base_and_ext_codeword = short_domain.evaluate(&base_and_ext_polynomial);
+                prof_stop!(maybe_profiler, "base&ext");
+                Array1::from(base_and_ext_codeword)
+            }
+        };
 
         prof_start!(maybe_profiler, "quotient", "CC");
         let quotient_segments_codeword = Self::random_linear_sum(
@@ -436,19 +839,26 @@ impl Stark {
         prof_stop!(maybe_profiler, "combined DEEP polynomial");
 
         prof_start!(maybe_profiler, "FRI");
-        let revealed_current_row_indices = fri.prove(&fri_combination_codeword, &mut proof_stream);
+        let revealed_current_row_indices =
+            fri.prove_with_handle(&fri_combination_codeword, &mut proof_stream, handle)?;
         assert_eq!(
             parameters.num_combination_codeword_checks,
             revealed_current_row_indices.len()
         );
         prof_stop!(maybe_profiler, "FRI");
 
+        handle.enter_stage(ProvingStage::Openings)?;
         prof_start!(maybe_profiler, "open trace leafs");
         // Open leafs of zipped codewords at indicated positions
-        let revealed_base_elems = Self::get_revealed_elements(
-            master_base_table.fri_domain_table(),
-            &revealed_current_row_indices,
-        );
+        let revealed_base_elems = match lde_mode {
+            LowDegreeExtensionMode::Memoize => Self::get_revealed_elements(
+                master_base_table.fri_domain_table(),
+                &revealed_current_row_indices,
+            ),
+            LowDegreeExtensionMode::Recompute { .. } => {
+                master_base_table.recompute_fri_domain_rows(&revealed_current_row_indices)
+            }
+        };
         let base_authentication_structure =
             base_merkle_tree.get_authentication_structure(&revealed_current_row_indices);
         proof_stream.enqueue(ProofItem::MasterBaseTableRows(revealed_base_elems));
@@ -456,10 +866,15 @@ impl Stark {
             base_authentication_structure,
         ));
 
-        let revealed_ext_elems = Self::get_revealed_elements(
-            master_ext_table.fri_domain_table(),
-            &revealed_current_row_indices,
-        );
+        let revealed_ext_elems = match lde_mode {
+            LowDegreeExtensionMode::Memoize => Self::get_revealed_elements(
+                master_ext_table.fri_domain_table(),
+                &revealed_current_row_indices,
+            ),
+            LowDegreeExtensionMode::Recompute { .. } => {
+                master_ext_table.recompute_fri_domain_rows(&revealed_current_row_indices)
+            }
+        };
         let ext_authentication_structure =
             ext_merkle_tree.get_authentication_structure(&revealed_current_row_indices);
         proof_stream.enqueue(ProofItem::MasterExtTableRows(revealed_ext_elems));
@@ -485,7 +900,8 @@ impl Stark {
         ));
         prof_stop!(maybe_profiler, "open trace leafs");
 
-        proof_stream.into()
+        handle.enter_stage(ProvingStage::Finished)?;
+        Ok(proof_stream.into())
     }
 
     fn random_linear_sum_base_field(
@@ -550,6 +966,96 @@ impl Stark {
         (base_weights, ext_weights, quotient_segment_weights)
     }
 
+    /// The random linear combination of all quotients over the quotient domain, like computed
+    /// from the result of [`all_quotients`]. Instead of requiring the quotient-domain tables to be
+    /// memoized, they are recomputed from the interpolation polynomials in `num_chunks` many
+    /// [interleaved chunks](ArithmeticDomain::interleaved_chunk) of the quotient domain.
+    ///
+    /// Every chunk must contain at least as many rows as the trace domain, such that the row
+    /// following any row of the chunk is also part of that chunk. If necessary, the number of
+    /// chunks is reduced accordingly.
+    #[allow(clippy::too_many_arguments)]
+    fn quotient_codeword_in_chunks(
+        master_base_table: &MasterBaseTable,
+        master_ext_table: &MasterExtTable,
+        quotient_domain: ArithmeticDomain,
+        quotient_combination_weights: Array1<XFieldElement>,
+        num_chunks: usize,
+        challenges: &Challenges,
+        handle: &ProvingHandle,
+        maybe_profiler: &mut Option<TritonProfiler>,
+    ) -> Result<Array1<XFieldElement>, ProvingError> {
+        let trace_domain = master_base_table.trace_domain();
+        let max_num_chunks = quotient_domain.length / trace_domain.length;
+        let num_chunks = num_chunks.min(max_num_chunks);
+
+        let mut quotient_codeword = Array1::zeros(quotient_domain.length);
+        for chunk_index in 0..num_chunks {
+            let chunk_domain = quotient_domain.interleaved_chunk(chunk_index, num_chunks);
+
+            prof_start!(maybe_profiler, "quotient-domain codewords");
+            let base_chunk = master_base_table.evaluate_all_columns_on(chunk_domain);
+            let ext_chunk = master_ext_table.evaluate_all_columns_on(chunk_domain);
+            prof_stop!(maybe_profiler, "quotient-domain codewords");
+
+            prof_start!(maybe_profiler, "quotient codewords");
+            let quotient_chunk = all_quotients(
+                base_chunk.view(),
+                ext_chunk.view(),
+                trace_domain,
+                chunk_domain,
+                challenges,
+                handle,
+                maybe_profiler,
+            )?;
+            prof_stop!(maybe_profiler, "quotient codewords");
+
+            prof_start!(maybe_profiler, "linearly combine quotient codewords", "CC");
+            let weights = quotient_combination_weights.clone();
+            let chunk_codeword = Self::random_linear_sum(quotient_chunk.view(), weights);
+            quotient_codeword
+                .slice_mut(s![chunk_index..; num_chunks])
+                .assign(&chunk_codeword);
+            prof_stop!(maybe_profiler, "linearly combine quotient codewords");
+        }
+        Ok(quotient_codeword)
+    }
+
+    /// The random linear combination of the interpolation polynomials of all base and extension
+    /// columns, excluding the randomizer polynomials.
+    fn random_linear_sum_of_interpolation_polynomials(
+        master_base_table: &MasterBaseTable,
+        master_ext_table: &MasterExtTable,
+        base_weights: Array1<XFieldElement>,
+        ext_weights: Array1<XFieldElement>,
+    ) -> Polynomial<XFieldElement> {
+        let base_polynomials = master_base_table.interpolation_polynomials();
+        let ext_polynomials = master_ext_table.interpolation_polynomials();
+        let ext_polynomials = ext_polynomials.slice(s![..NUM_EXT_COLUMNS]);
+        assert_eq!(base_polynomials.len(), base_weights.len());
+        assert_eq!(ext_polynomials.len(), ext_weights.len());
+
+        fn coefficient<FF: FiniteField>(polynomial: &Polynomial<FF>, index: usize) -> FF {
+            let coefficients = &polynomial.coefficients;
+            coefficients.get(index).copied().unwrap_or_else(FF::zero)
+        }
+
+        let num_coefficients = master_base_table.randomized_trace_domain().length;
+        let coefficients = (0..num_coefficients)
+            .into_par_iter()
+            .map(|index| {
+                let base_part: XFieldElement = izip!(&base_polynomials, &base_weights)
+                    .map(|(polynomial, &weight)| weight * coefficient(polynomial, index))
+                    .sum();
+                let ext_part: XFieldElement = izip!(&ext_polynomials, &ext_weights)
+                    .map(|(polynomial, &weight)| weight * coefficient(polynomial, index))
+                    .sum();
+                base_part + ext_part
+            })
+            .collect();
+        Polynomial::new(coefficients)
+    }
+
     fn fri_domain_segment_polynomials(
         quotient_segment_polynomials: ArrayView1<Polynomial<XFieldElement>>,
         fri_domain: ArithmeticDomain,
@@ -632,6 +1138,8 @@ impl Stark {
             parameters.fri_expansion_factor,
             parameters.num_collinearity_checks,
         )
+        .with_folding_factor(parameters.fri_folding_factor)
+        .with_num_grinding_bits(parameters.num_grinding_bits)
     }
 
     fn get_revealed_elements<FF: FiniteField>(
@@ -710,24 +1218,140 @@ impl Stark {
         segments.try_into().unwrap()
     }
 
+    /// Verify many proofs at once. Each element of `instances` is a [`Claim`] together with the
+    /// [`Proof`] for it. The proofs are verified in parallel, and the FRI parameters are derived
+    /// only once per padded height.
+    ///
+    /// If any proof is invalid, fails with [`VerificationError::BatchProofFailure`], which
+    /// reports the index of the first invalid proof and why it is invalid.
+    pub fn verify_batch(
+        parameters: StarkParameters,
+        instances: &[(Claim, Proof)],
+    ) -> Result<(), VerificationError> {
+        let fris = Mutex::new(HashMap::new());
+        let derive_fri = |padded_height| {
+            let mut fris = fris.lock().unwrap();
+            let fri = fris
+                .entry(padded_height)
+                .or_insert_with(|| Self::derive_fri(parameters, padded_height));
+            fri.clone()
+        };
+
+        let config = VerifierConfig::default();
+        let failures = instances
+            .par_iter()
+            .enumerate()
+            .filter_map(|(index, (claim, proof))| {
+                let verify = || {
+                    let mut proof_stream = StarkProofStream::try_from(proof)?;
+                    Self::verify_with_fri(
+                        parameters,
+                        claim,
+                        &mut proof_stream,
+                        &config,
+                        derive_fri,
+                        &mut None,
+                    )
+                };
+                verify().err().map(|source| (index, source))
+            })
+            .collect::<Vec<_>>();
+
+        let Some((index, source)) = failures.into_iter().min_by_key(|&(index, _)| index) else {
+            return Ok(());
+        };
+        let source = Box::new(source);
+        Err(BatchProofFailure { index, source })
+    }
+
     pub fn verify(
         parameters: StarkParameters,
         claim: &Claim,
         proof: &Proof,
         maybe_profiler: &mut Option<TritonProfiler>,
+    ) -> Result<(), VerificationError> {
+        let config = VerifierConfig::default();
+        Self::verify_with_config(parameters, claim, proof, &config, maybe_profiler)
+    }
+
+    /// Like [`verify`](Self::verify), but uses the given verifier configuration.
+    pub fn verify_with_config(
+        parameters: StarkParameters,
+        claim: &Claim,
+        proof: &Proof,
+        config: &VerifierConfig,
+        maybe_profiler: &mut Option<TritonProfiler>,
     ) -> Result<(), VerificationError> {
         prof_start!(maybe_profiler, "deserialize");
         let mut proof_stream = StarkProofStream::try_from(proof)?;
         prof_stop!(maybe_profiler, "deserialize");
 
+        let derive_fri = |padded_height| Self::derive_fri(parameters, padded_height);
+        Self::verify_with_fri(
+            parameters,
+            claim,
+            &mut proof_stream,
+            config,
+            derive_fri,
+            maybe_profiler,
+        )
+    }
+
+    /// Like [`verify`](Self::verify), but reads the proof lazily from the given reader, in the
+    /// format produced by [`Proof::to_bytes`]. Each [`ProofItem`] is only read once the verifier
+    /// needs it, and verification stops at the first malformed or failing item without reading
+    /// the rest of the proof. See [`ProofReader`] for the limits on the size of items.
+    pub fn verify_from_reader(
+        parameters: StarkParameters,
+        claim: &Claim,
+        reader: impl Read,
+        maybe_profiler: &mut Option<TritonProfiler>,
+    ) -> Result<(), VerificationError> {
+        let config = VerifierConfig::default();
+        let derive_fri = |padded_height| Self::derive_fri(parameters, padded_height);
+        let mut proof_reader = ProofReader::<_, StarkHasher>::new(reader);
+        Self::verify_with_fri(
+            parameters,
+            claim,
+            &mut proof_reader,
+            &config,
+            derive_fri,
+            maybe_profiler,
+        )
+    }
+
+    /// Like [`verify_with_config`](Self::verify_with_config), but receives the proof's items from
+    /// the given proof stream and obtains the FRI parameters for the proof's padded height from
+    /// `derive_fri`.
+    fn verify_with_fri(
+        parameters: StarkParameters,
+        claim: &Claim,
+        proof_stream: &mut impl VerifierProofStream,
+        config: &VerifierConfig,
+        derive_fri: impl Fn(usize) -> Fri<StarkHasher>,
+        maybe_profiler: &mut Option<TritonProfiler>,
+    ) -> Result<(), VerificationError> {
+        let with_context = |error: VerificationError, context| match config.verbose_errors {
+            true => error.with_context(context),
+            false => error,
+        };
+
         prof_start!(maybe_profiler, "Fiat-Shamir: Claim", "hash");
         proof_stream.alter_fiat_shamir_state_with(claim);
         prof_stop!(maybe_profiler, "Fiat-Shamir: Claim");
 
         prof_start!(maybe_profiler, "derive additional parameters");
+        let proof_is_zero_knowledge = proof_stream.dequeue()?.as_zero_knowledge()?;
+        if proof_is_zero_knowledge != parameters.zero_knowledge {
+            let context = VerificationErrorContext::ZeroKnowledge {
+                expected: parameters.zero_knowledge,
+                received: proof_is_zero_knowledge,
+            };
+            return Err(with_context(ZeroKnowledgeMismatch, context));
+        }
         let log_2_padded_height = proof_stream.dequeue()?.as_log2_padded_height()?;
         let padded_height = 1 << log_2_padded_height;
-        let fri = Self::derive_fri(parameters, padded_height);
+        let fri = derive_fri(padded_height);
         let merkle_tree_height = fri.domain.length.ilog2() as usize;
         prof_stop!(maybe_profiler, "derive additional parameters");
 
@@ -832,7 +1456,11 @@ impl Stark {
         let sum_of_evaluated_out_of_domain_quotient_segments =
             powers_of_out_of_domain_point_curr_row.dot(&out_of_domain_curr_row_quot_segments);
         if out_of_domain_quotient_value != sum_of_evaluated_out_of_domain_quotient_segments {
-            return Err(OutOfDomainQuotientValueMismatch);
+            let context = VerificationErrorContext::OutOfDomainQuotientValue {
+                received: sum_of_evaluated_out_of_domain_quotient_segments,
+                computed: out_of_domain_quotient_value,
+            };
+            return Err(with_context(OutOfDomainQuotientValueMismatch, context));
         };
         prof_stop!(maybe_profiler, "verify quotient's segments");
 
@@ -873,7 +1501,8 @@ impl Stark {
 
         // verify low degree of combination polynomial with FRI
         prof_start!(maybe_profiler, "FRI");
-        let revealed_fri_indices_and_elements = fri.verify(&mut proof_stream, maybe_profiler)?;
+        let revealed_fri_indices_and_elements =
+            fri.verify_with_config(proof_stream, config, maybe_profiler)?;
         let (revealed_current_row_indices, revealed_fri_values): (Vec<_>, Vec<_>) =
             revealed_fri_indices_and_elements.into_iter().unzip();
         prof_stop!(maybe_profiler, "FRI");
@@ -897,7 +1526,11 @@ impl Stark {
             &leaf_digests_base,
             &base_authentication_structure,
         ) {
-            return Err(BaseCodewordAuthenticationFailure);
+            let context = VerificationErrorContext::MerkleAuthentication {
+                merkle_root: base_merkle_tree_root,
+                row_indices: revealed_current_row_indices,
+            };
+            return Err(with_context(BaseCodewordAuthenticationFailure, context));
         }
         prof_stop!(maybe_profiler, "Merkle verify (base tree)");
 
@@ -924,7 +1557,14 @@ impl Stark {
             &leaf_digests_ext,
             &ext_authentication_structure,
         ) {
-            return Err(ExtensionCodewordAuthenticationFailure);
+            let context = VerificationErrorContext::MerkleAuthentication {
+                merkle_root: extension_tree_merkle_root,
+                row_indices: revealed_current_row_indices,
+            };
+            return Err(with_context(
+                ExtensionCodewordAuthenticationFailure,
+                context,
+            ));
         }
         prof_stop!(maybe_profiler, "Merkle verify (extension tree)");
 
@@ -945,30 +1585,58 @@ impl Stark {
             &revealed_quotient_segments_digests,
             &revealed_quotient_authentication_structure,
         ) {
-            return Err(QuotientCodewordAuthenticationFailure);
+            let context = VerificationErrorContext::MerkleAuthentication {
+                merkle_root: quotient_codeword_merkle_root,
+                row_indices: revealed_current_row_indices,
+            };
+            return Err(with_context(QuotientCodewordAuthenticationFailure, context));
         }
         prof_stop!(maybe_profiler, "Merkle verify (combined quotient)");
         prof_stop!(maybe_profiler, "check leafs");
 
         prof_start!(maybe_profiler, "linear combination");
         if parameters.num_combination_codeword_checks != revealed_current_row_indices.len() {
-            return Err(IncorrectNumberOfRowIndices);
+            let context = VerificationErrorContext::NumberOfElements {
+                expected: parameters.num_combination_codeword_checks,
+                received: revealed_current_row_indices.len(),
+            };
+            return Err(with_context(IncorrectNumberOfRowIndices, context));
         };
         if parameters.num_combination_codeword_checks != revealed_fri_values.len() {
-            return Err(IncorrectNumberOfFRIValues);
+            let context = VerificationErrorContext::NumberOfElements {
+                expected: parameters.num_combination_codeword_checks,
+                received: revealed_fri_values.len(),
+            };
+            return Err(with_context(IncorrectNumberOfFRIValues, context));
         };
         if parameters.num_combination_codeword_checks != revealed_quotient_segments_elements.len() {
-            return Err(IncorrectNumberOfQuotientSegmentElements);
+            let context = VerificationErrorContext::NumberOfElements {
+                expected: parameters.num_combination_codeword_checks,
+                received: revealed_quotient_segments_elements.len(),
+            };
+            return Err(with_context(
+                IncorrectNumberOfQuotientSegmentElements,
+                context,
+            ));
         };
         if parameters.num_combination_codeword_checks != base_table_rows.len() {
-            return Err(IncorrectNumberOfBaseTableRows);
+            let context = VerificationErrorContext::NumberOfElements {
+                expected: parameters.num_combination_codeword_checks,
+                received: base_table_rows.len(),
+            };
+            return Err(with_context(IncorrectNumberOfBaseTableRows, context));
         };
         if parameters.num_combination_codeword_checks != ext_table_rows.len() {
-            return Err(IncorrectNumberOfExtTableRows);
+            let context = VerificationErrorContext::NumberOfElements {
+                expected: parameters.num_combination_codeword_checks,
+                received: ext_table_rows.len(),
+            };
+            return Err(with_context(IncorrectNumberOfExtTableRows, context));
         };
 
         prof_start!(maybe_profiler, "main loop");
-        for (row_idx, base_row, ext_row, quotient_segments_elements, fri_value) in izip!(
+        for (query, row_idx, base_row, ext_row, quotient_segments_elements, fri_value) in izip!(
+            0..,
             revealed_current_row_indices,
             base_table_rows,
             ext_table_rows,
@@ -1022,8 +1690,15 @@ impl Stark {
             ]);
             let deep_value = deep_codeword_weights.dot(&deep_value_components);
             let randomizer_codewords_contribution = randomizer_row.sum();
-            if fri_value != deep_value + randomizer_codewords_contribution {
-                return Err(CombinationCodewordMismatch);
+            let combination_codeword_value = deep_value + randomizer_codewords_contribution;
+            if fri_value != combination_codeword_value {
+                let context = VerificationErrorContext::CombinationCodeword {
+                    query,
+                    row_index: row_idx,
+                    received: fri_value,
+                    computed: combination_codeword_value,
+                };
+                return Err(with_context(CombinationCodewordMismatch, context));
             };
             prof_stop!(maybe_profiler, "combination codeword equality");
         }
@@ -1072,6 +1747,9 @@ impl Stark {
 
 #[cfg(test)]
 pub(crate) mod tests {
+    use std::sync::Arc;
+    use std::sync::Mutex;
+
     use crate::error::InstructionError;
     use assert2::assert;
     use assert2::check;
@@ -2221,6 +2899,242 @@ pub(crate) mod tests {
         println!("{report}");
     }
 
+    #[test]
+    fn proving_reports_progress_of_all_stages_in_order() {
+        let program = triton_program!(halt);
+        let (aet, public_output) = program.trace_execution([].into(), [].into()).unwrap();
+        let claim = construct_claim(&aet, vec![], public_output);
+        let parameters = stark_parameters_with_low_security_level();
+
+        let reports = Arc::new(Mutex::new(vec![]));
+        let reports_for_observer = reports.clone();
+        let handle = ProvingHandle::new()
+            .with_observer(move |progress| reports_for_observer.lock().unwrap().push(progress));
+        let proof = Stark::prove_with_handle(parameters, &claim, &aet, &handle, &mut None);
+        let_assert!(Ok(proof) = proof);
+        assert!(let Ok(()) = Stark::verify(parameters, &claim, &proof, &mut None));
+
+        let reports = reports.lock().unwrap();
+        let_assert!(Some(first_report) = reports.first());
+        let_assert!(Some(last_report) = reports.last());
+        assert!(ProvingStage::BaseTableCreation == first_report.stage);
+        assert!(ProvingStage::Finished == last_report.stage);
+
+        let fri_rounds = reports
+            .iter()
+            .filter(|progress| matches!(progress.stage, ProvingStage::FriRound(_)));
+        assert!(fri_rounds.count() > 1);
+        for (earlier, later) in reports.iter().tuple_windows() {
+            assert!(earlier.estimated_fraction_done <= later.estimated_fraction_done);
+        }
+    }
+
+    #[test]
+    fn prove_verify_with_recomputed_low_degree_extensions() {
+        let program_with_input = test_program_hash_nop_nop_lt();
+        let public_input = program_with_input.public_input();
+        let (aet, public_output) = program_with_input
+            .program
+            .trace_execution(public_input.clone(), program_with_input.non_determinism())
+            .unwrap();
+        let claim = construct_claim(&aet, public_input.individual_tokens, public_output);
+        let parameters = stark_parameters_with_low_security_level();
+
+        for num_chunks in [1, 8] {
+            let low_degree_extension_mode = LowDegreeExtensionMode::Recompute { num_chunks };
+            let config = ProverConfig {
+                low_degree_extension_mode,
+                ..Default::default()
+            };
+            let handle = ProvingHandle::new();
+            let proof =
+                Stark::prove_with_config(parameters, &claim, &aet, &config, &handle, &mut None);
+            let_assert!(Ok(proof) = proof);
+            assert!(let Ok(()) = Stark::verify(parameters, &claim, &proof, &mut None));
+        }
+    }
+
+    #[test]
+    fn proofs_with_same_randomness_seed_are_identical() {
+        let program_with_input = test_program_hash_nop_nop_lt();
+        let public_input = program_with_input.public_input();
+        let (aet, public_output) = program_with_input
+            .program
+            .trace_execution(public_input.clone(), program_with_input.non_determinism())
+            .unwrap();
+        let claim = construct_claim(&aet, public_input.individual_tokens, public_output);
+        let parameters = stark_parameters_with_low_security_level();
+
+        let prove_with_seed = |randomness_seed| {
+            let config = ProverConfig {
+                randomness_seed,
+                ..Default::default()
+            };
+            let handle = ProvingHandle::new();
+            Stark::prove_with_config(parameters, &claim, &aet, &config, &handle, &mut None).unwrap()
+        };
+
+        let proof = prove_with_seed(Some([42; 32]));
+        assert!(let Ok(()) = Stark::verify(parameters, &claim, &proof, &mut None));
+        assert!(proof == prove_with_seed(Some([42; 32])));
+        assert!(proof != prove_with_seed(Some([43; 32])));
+        assert!(prove_with_seed(None) != prove_with_seed(None));
+    }
+
+    #[test]
+    fn prove_verify_with_various_fri_folding_factors() {
+        let program_with_input = test_program_hash_nop_nop_lt();
+        let public_input = program_with_input.public_input();
+        let (aet, public_output) = program_with_input
+            .program
+            .trace_execution(public_input.clone(), program_with_input.non_determinism())
+            .unwrap();
+        let claim = construct_claim(&aet, public_input.individual_tokens, public_output);
+
+        for fri_folding_factor in [2, 4, 8, 16] {
+            let parameters = stark_parameters_with_low_security_level()
+                .with_fri_folding_factor(fri_folding_factor);
+            let proof = Stark::prove(parameters, &claim, &aet, &mut None);
+            assert!(let Ok(()) = Stark::verify(parameters, &claim, &proof, &mut None));
+        }
+    }
+
+    #[test]
+    fn prove_verify_with_proof_of_work() {
+        let program_with_input = test_program_hash_nop_nop_lt();
+        let public_input = program_with_input.public_input();
+        let (aet, public_output) = program_with_input
+            .program
+            .trace_execution(public_input.clone(), program_with_input.non_determinism())
+            .unwrap();
+        let claim = construct_claim(&aet, public_input.individual_tokens, public_output);
+
+        let num_grinding_bits = 8;
+        let parameters = stark_parameters_with_low_security_level();
+        let parameters_with_grinding = parameters.with_num_grinding_bits(num_grinding_bits);
+        let num_collinearity_checks = parameters.num_collinearity_checks;
+        assert!(parameters_with_grinding.num_collinearity_checks < num_collinearity_checks);
+
+        let proof = Stark::prove(parameters_with_grinding, &claim, &aet, &mut None);
+        let verdict = Stark::verify(parameters_with_grinding, &claim, &proof, &mut None);
+        assert!(let Ok(()) = verdict);
+        let_assert!(Err(_) = Stark::verify(parameters, &claim, &proof, &mut None));
+    }
+
+    #[test]
+    fn prove_verify_without_zero_knowledge() {
+        let program_with_input = test_program_hash_nop_nop_lt();
+        let public_input = program_with_input.public_input();
+        let (aet, public_output) = program_with_input
+            .program
+            .trace_execution(public_input.clone(), program_with_input.non_determinism())
+            .unwrap();
+        let claim = construct_claim(&aet, public_input.individual_tokens, public_output);
+        let zk_parameters = stark_parameters_with_low_security_level();
+        let non_zk_parameters = zk_parameters.without_zero_knowledge();
+
+        let zk_proof = Stark::prove(zk_parameters, &claim, &aet, &mut None);
+        let non_zk_proof = Stark::prove(non_zk_parameters, &claim, &aet, &mut None);
+        assert!(let Ok(()) = Stark::verify(non_zk_parameters, &claim, &non_zk_proof, &mut None));
+        assert!(non_zk_proof.0.len() < zk_proof.0.len());
+
+        let_assert!(Err(err) = Stark::verify(zk_parameters, &claim, &non_zk_proof, &mut None));
+        assert!(let ZeroKnowledgeMismatch = err);
+        let_assert!(Err(err) = Stark::verify(non_zk_parameters, &claim, &zk_proof, &mut None));
+        assert!(let ZeroKnowledgeMismatch = err);
+    }
+
+    #[test]
+    fn number_of_chunks_not_a_power_of_two_gives_proving_error() {
+        let program_with_input = test_program_hash_nop_nop_lt();
+        let public_input = program_with_input.public_input();
+        let (aet, public_output) = program_with_input
+            .program
+            .trace_execution(public_input.clone(), program_with_input.non_determinism())
+            .unwrap();
+        let claim = construct_claim(&aet, public_input.individual_tokens, public_output);
+        let parameters = stark_parameters_with_low_security_level();
+
+        for num_chunks in [0, 3, 12] {
+            let low_degree_extension_mode = LowDegreeExtensionMode::Recompute { num_chunks };
+            let config = ProverConfig {
+                low_degree_extension_mode,
+                ..Default::default()
+            };
+            let handle = ProvingHandle::new();
+            let proof =
+                Stark::prove_with_config(parameters, &claim, &aet, &config, &handle, &mut None);
+            let_assert!(Err(ProvingError::InvalidNumberOfChunks(n)) = proof);
+            assert!(num_chunks == n);
+        }
+    }
+
+    #[test]
+    fn prove_verify_with_memory_mapped_master_tables() {
+        let program_with_input = test_program_hash_nop_nop_lt();
+        let public_input = program_with_input.public_input();
+        let (aet, public_output) = program_with_input
+            .program
+            .trace_execution(public_input.clone(), program_with_input.non_determinism())
+            .unwrap();
+        let claim = construct_claim(&aet, public_input.individual_tokens, public_output);
+        let parameters = stark_parameters_with_low_security_level();
+
+        let scratch_directory = tempfile::tempdir().unwrap();
+        let config = ProverConfig {
+            scratch_directory: Some(scratch_directory.path().to_path_buf()),
+            ..Default::default()
+        };
+        let handle = ProvingHandle::new();
+        let proof = Stark::prove_with_config(parameters, &claim, &aet, &config, &handle, &mut None);
+        let_assert!(Ok(proof) = proof);
+        assert!(let Ok(()) = Stark::verify(parameters, &claim, &proof, &mut None));
+    }
+
+    #[test]
+    fn unusable_scratch_directory_gives_proving_error() {
+        let program = triton_program!(halt);
+        let (aet, public_output) = program.trace_execution([].into(), [].into()).unwrap();
+        let claim = construct_claim(&aet, vec![], public_output);
+        let parameters = stark_parameters_with_low_security_level();
+
+        let scratch_directory = tempfile::tempdir().unwrap();
+        let config = ProverConfig {
+            scratch_directory: Some(scratch_directory.path().join("nonexistent")),
+            ..Default::default()
+        };
+        let handle = ProvingHandle::new();
+        let proof = Stark::prove_with_config(parameters, &claim, &aet, &config, &handle, &mut None);
+        let_assert!(Err(ProvingError::ScratchDirectoryError(_)) = proof);
+    }
+
+    #[test]
+    fn cancelled_proof_generation_stops_at_next_stage() {
+        let program = triton_program!(halt);
+        let (aet, public_output) = program.trace_execution([].into(), [].into()).unwrap();
+        let claim = construct_claim(&aet, vec![], public_output);
+        let parameters = stark_parameters_with_low_security_level();
+
+        let reported_stages = Arc::new(Mutex::new(vec![]));
+        let reported_stages_for_observer = reported_stages.clone();
+        let handle = ProvingHandle::new();
+        let handle_for_observer = handle.clone();
+        let handle = handle.with_observer(move |progress| {
+            reported_stages_for_observer
+                .lock()
+                .unwrap()
+                .push(progress.stage);
+            if ProvingStage::Quotients == progress.stage {
+                handle_for_observer.cancel();
+            }
+        });
+
+        let proof = Stark::prove_with_handle(parameters, &claim, &aet, &handle, &mut None);
+        let_assert!(Err(ProvingError::Cancelled) = proof);
+        let reported_stages = reported_stages.lock().unwrap();
+        assert!(Some(&ProvingStage::Quotients) == reported_stages.last());
+    }
+
     #[test]
     fn prove_verify_fibonacci_100() {
         let stdin = vec![100].into();
@@ -2258,6 +3172,136 @@ pub(crate) mod tests {
         }
     }
 
+    fn fibonacci_batch(indices: &[u64]) -> Vec<(Claim, AlgebraicExecutionTrace)> {
+        indices
+            .iter()
+            .map(|&index| {
+                let public_input: PublicInput = vec![index].into();
+                let (aet, public_output) = FIBONACCI_SEQUENCE
+                    .trace_execution(public_input.clone(), [].into())
+                    .unwrap();
+                let claim = construct_claim(&aet, public_input.individual_tokens, public_output);
+                (claim, aet)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn batch_proving_gives_same_proofs_as_individual_proving() {
+        let parameters = stark_parameters_with_low_security_level();
+        let instances = fibonacci_batch(&[0, 7, 11, 100]);
+        let config = ProverConfig {
+            randomness_seed: Some([42; 32]),
+            ..Default::default()
+        };
+        let handle = ProvingHandle::new();
+
+        let_assert!(Ok(proofs) = Stark::prove_batch(parameters, &instances, &config, &handle));
+        assert!(instances.len() == proofs.len());
+        for ((claim, aet), proof) in instances.iter().zip_eq(&proofs) {
+            let individual_proof =
+                Stark::prove_with_config(parameters, claim, aet, &config, &handle, &mut None);
+            let_assert!(Ok(individual_proof) = individual_proof);
+            assert!(&individual_proof == proof);
+        }
+
+        let claims_and_proofs = instances
+            .into_iter()
+            .map(|(claim, _)| claim)
+            .zip_eq(proofs)
+            .collect_vec();
+        assert!(let Ok(()) = Stark::verify_batch(parameters, &claims_and_proofs));
+    }
+
+    #[test]
+    fn batch_proving_rejects_instances_of_different_programs() {
+        let parameters = stark_parameters_with_low_security_level();
+        let mut instances = fibonacci_batch(&[3, 5]);
+        instances[1].0.program_digest = Digest::default();
+
+        let config = ProverConfig::default();
+        let handle = ProvingHandle::new();
+        let proofs = Stark::prove_batch(parameters, &instances, &config, &handle);
+        let_assert!(Err(ProvingError::ProgramDigestMismatch) = proofs);
+    }
+
+    #[test]
+    fn batch_verification_reports_first_invalid_proof() {
+        let parameters = stark_parameters_with_low_security_level();
+        let instances = fibonacci_batch(&[1, 2, 3, 4]);
+        let config = ProverConfig::default();
+        let handle = ProvingHandle::new();
+        let_assert!(Ok(proofs) = Stark::prove_batch(parameters, &instances, &config, &handle));
+
+        let mut claims_and_proofs = instances
+            .into_iter()
+            .map(|(claim, _)| claim)
+            .zip_eq(proofs)
+            .collect_vec();
+        claims_and_proofs[1].0.output = vec![BFieldElement::new(1337)];
+        claims_and_proofs[3].0.output = vec![BFieldElement::new(1337)];
+
+        let verdict = Stark::verify_batch(parameters, &claims_and_proofs);
+        let_assert!(Err(BatchProofFailure { index, .. }) = verdict);
+        assert!(1 == index);
+    }
+
+    #[test]
+    fn verbose_verification_error_reports_rows_of_failed_authentication() {
+        let program = triton_program!(read_io 1 push 17 add write_io 1 halt);
+        let public_input = PublicInput::new(vec![BFieldElement::new(42)]);
+        let (parameters, claim, proof) =
+            prove_with_low_security_level(&program, public_input, [].into(), &mut None);
+
+        let_assert!(Ok(mut proof_stream) = StarkProofStream::try_from(&proof));
+        let_assert!(
+            Some(base_table_rows) = proof_stream.items.iter_mut().find_map(|item| {
+                match item {
+                    ProofItem::MasterBaseTableRows(rows) => Some(rows),
+                    _ => None,
+                }
+            })
+        );
+        base_table_rows[0][0].increment();
+        let proof = proof_stream.into();
+
+        let verdict = Stark::verify(parameters, &claim, &proof, &mut None);
+        let_assert!(Err(BaseCodewordAuthenticationFailure) = verdict);
+
+        let config = VerifierConfig {
+            verbose_errors: true,
+        };
+        let verdict = Stark::verify_with_config(parameters, &claim, &proof, &config, &mut None);
+        let_assert!(Err(WithContext { source, context }) = verdict);
+        assert!(let BaseCodewordAuthenticationFailure = *source);
+        let_assert!(VerificationErrorContext::MerkleAuthentication { row_indices, .. } = context);
+        assert!(parameters.num_combination_codeword_checks == row_indices.len());
+    }
+
+    #[test]
+    fn verbose_verification_error_reports_mismatching_zero_knowledge_mode() {
+        let program = triton_program!(halt);
+        let (parameters, claim, proof) =
+            prove_with_low_security_level(&program, [].into(), [].into(), &mut None);
+        let parameters = parameters.without_zero_knowledge();
+
+        let config = VerifierConfig {
+            verbose_errors: true,
+        };
+        let verdict = Stark::verify_with_config(parameters, &claim, &proof, &config, &mut None);
+        let_assert!(Err(err) = verdict);
+        assert!(err
+            .to_string()
+            .contains("expected zero-knowledge false, received true"));
+        let_assert!(WithContext { source, context } = err);
+        assert!(let ZeroKnowledgeMismatch = *source);
+        let expected_context = VerificationErrorContext::ZeroKnowledge {
+            expected: false,
+            received: true,
+        };
+        assert!(expected_context == context);
+    }
+
     #[test]
     fn constraints_evaluate_to_zero_on_many_u32_operations() {
         let many_u32_instructions =
@@ -15,5 +15,6 @@ pub mod op_stack_table;
 pub mod processor_table;
 pub mod program_table;
 pub mod ram_table;
+pub mod storage;
 pub mod table_column;
 pub mod u32_table;
@@ -3,6 +3,7 @@ use twenty_first::shared_math::x_field_element::XFieldElement;
 
 use crate::table::extension_table::Evaluable;
 use crate::table::extension_table::Quotientable;
+use crate::table::extension_table::TasmConstraintEvaluation;
 use crate::table::master_table::MasterExtTable;
 
 // This file is a placeholder for auto-generated code
@@ -12,3 +13,5 @@ impl Evaluable<BFieldElement> for MasterExtTable {}
 impl Evaluable<XFieldElement> for MasterExtTable {}
 
 impl Quotientable for MasterExtTable {}
+
+impl TasmConstraintEvaluation for MasterExtTable {}
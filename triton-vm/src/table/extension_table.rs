@@ -16,6 +16,7 @@ use twenty_first::shared_math::traits::FiniteField;
 use twenty_first::shared_math::x_field_element::XFieldElement;
 
 use crate::arithmetic_domain::ArithmeticDomain;
+use crate::instruction::LabelledInstruction;
 use crate::table::challenges::Challenges;
 
 const ERROR_MESSAGE_GENERATE_CONSTRAINTS: &str =
@@ -67,6 +68,43 @@ pub trait Evaluable<FF: FiniteField> {
     }
 }
 
+/// The locations in memory that the Triton assembly evaluating the AIR constraints relies on.
+/// See [`TasmConstraintEvaluation`].
+///
+/// The rows as well as the challenges are sequences of [`XFieldElement`]s. The `i`-th element of
+/// such a sequence is stored at the three consecutive addresses starting at `ptr + 3·i`, lowest
+/// coefficient first. Note that the base rows, too, consist of [`XFieldElement`]s since they
+/// are rows of the out-of-domain evaluation of the trace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TasmConstraintEvaluationMemoryLayout {
+    /// The start of a region of memory the Triton assembly is free to overwrite. Its beginning
+    /// holds the evaluated constraints once the code has run, followed by some scratch space.
+    pub free_mem_page_ptr: BFieldElement,
+    pub curr_base_row_ptr: BFieldElement,
+    pub curr_ext_row_ptr: BFieldElement,
+    pub next_base_row_ptr: BFieldElement,
+    pub next_ext_row_ptr: BFieldElement,
+    pub challenges_ptr: BFieldElement,
+}
+
+/// The counterpart of [`Evaluable`] in Triton assembly, used for recursive verification.
+pub trait TasmConstraintEvaluation {
+    /// Triton assembly evaluating all AIR constraints on the rows indicated by the memory layout.
+    /// The evaluated constraints are written to memory starting at
+    /// [`free_mem_page_ptr`](TasmConstraintEvaluationMemoryLayout::free_mem_page_ptr), one
+    /// [`XFieldElement`] after the other. Their order is that of the concatenation of the
+    /// initial, consistency, transition, and terminal constraints as produced by [`Evaluable`].
+    /// The op stack is left unchanged.
+    ///
+    /// The code for this method must be generated by running
+    /// `cargo run --bin constraint-evaluation-generator`
+    fn air_constraint_evaluation_tasm(
+        _mem_layout: TasmConstraintEvaluationMemoryLayout,
+    ) -> Vec<LabelledInstruction> {
+        panic!("{ERROR_MESSAGE_GENERATE_CONSTRAINTS}")
+    }
+}
+
 pub(crate) trait Quotientable: Evaluable<BFieldElement> {
     /// Compute the degrees of the quotients from all AIR constraints that apply to the table.
     fn all_degrees_with_origin(
@@ -1,5 +1,7 @@
 use std::ops::MulAssign;
 use std::ops::Range;
+use std::path::Path;
+use std::path::PathBuf;
 
 use itertools::Itertools;
 use ndarray::parallel::prelude::*;
@@ -10,9 +12,13 @@ use ndarray::ArrayView2;
 use ndarray::ArrayViewMut2;
 use ndarray::Zip;
 use num_traits::One;
+use num_traits::Zero;
 use rand::distributions::Standard;
 use rand::prelude::Distribution;
-use rand::random;
+use rand::thread_rng;
+use rand::Rng;
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
 use strum::Display;
 use strum::EnumCount;
 use strum::EnumIter;
@@ -30,9 +36,11 @@ use twenty_first::util_types::merkle_tree_maker::MerkleTreeMaker;
 
 use crate::aet::AlgebraicExecutionTrace;
 use crate::arithmetic_domain::ArithmeticDomain;
+use crate::error::ProvingError;
 use crate::profiler::prof_start;
 use crate::profiler::prof_stop;
 use crate::profiler::TritonProfiler;
+use crate::progress::ProvingHandle;
 use crate::stark::MTMaker;
 use crate::stark::StarkHasher;
 use crate::table::cascade_table::CascadeTable;
@@ -47,6 +55,8 @@ use crate::table::op_stack_table::OpStackTable;
 use crate::table::processor_table::ProcessorTable;
 use crate::table::program_table::ProgramTable;
 use crate::table::ram_table::RamTable;
+use crate::table::storage::Layout;
+use crate::table::storage::TableStorage;
 use crate::table::table_column::*;
 use crate::table::u32_table::U32Table;
 use crate::table::*;
@@ -148,6 +158,13 @@ pub const EXT_DEGREE_LOWERING_TABLE_END: usize =
 
 const NUM_TABLES_WITHOUT_DEGREE_LOWERING: usize = TableId::COUNT - 1;
 
+/// The number of rows of the low-degree extended table that are gathered into one contiguous block
+/// before hashing. See [`MasterTable::hash_all_fri_domain_rows`].
+const NUM_ROWS_PER_HASHING_BLOCK: usize = 1 << 10;
+
+/// The seed of the prover's randomness. See [`MasterBaseTable::with_randomness_seed`].
+pub type RandomnessSeed = [u8; 32];
+
 /// A `TableId` uniquely determines one of Triton VM's tables.
 #[derive(Debug, Copy, Clone, Display, EnumCount, EnumIter, PartialEq, Eq, Hash)]
 pub enum TableId {
@@ -217,6 +234,14 @@ where
     fn quotient_domain(&self) -> ArithmeticDomain;
     fn fri_domain(&self) -> ArithmeticDomain;
 
+    /// The directory holding the memory-mapped files backing this table, if any. Low-degree
+    /// extended tables are backed by files in the same directory. See [`TableStorage`].
+    fn scratch_directory(&self) -> Option<&Path>;
+
+    /// The seed for all randomness used by this table, if any. Without a seed, randomness is
+    /// drawn from the operating system. See [`MasterBaseTable::with_randomness_seed`].
+    fn randomness_seed(&self) -> Option<RandomnessSeed>;
+
     /// Presents underlying trace data, excluding trace randomizers and randomizer polynomials.
     fn trace_table(&self) -> ArrayView2<FF>;
 
@@ -241,18 +266,25 @@ where
     /// Set all rows _not_ part of the actual (padded) trace to random values.
     fn randomize_trace(&mut self) {
         let unit_distance = self.randomized_trace_domain().length / self.trace_domain().length;
-        (1..unit_distance).for_each(|offset| {
-            self.randomized_trace_table_mut()
-                .slice_mut(s![offset..; unit_distance, ..])
-                .par_mapv_inplace(|_| random::<FF>())
-        });
+        let seed = self.randomness_seed();
+        let is_trace_randomizer_row = |row_index| row_index % unit_distance != 0;
+        randomize_rows(
+            self.randomized_trace_table_mut(),
+            seed,
+            is_trace_randomizer_row,
+        );
     }
 
     /// Low-degree extend all columns of the randomized trace domain table. The resulting
     /// low-degree extended columns can be accessed using
     /// [`quotient_domain_table`](Self::quotient_domain_table) and
     /// [`fri_domain_table`](Self::fri_domain_table).
-    fn low_degree_extend_all_columns(&mut self) {
+    ///
+    /// Stops early if the given handle is [cancelled](ProvingHandle::cancel).
+    fn low_degree_extend_all_columns(
+        &mut self,
+        handle: &ProvingHandle,
+    ) -> Result<(), ProvingError> {
         let evaluation_domain = match self.fri_domain().length > self.quotient_domain().length {
             true => self.fri_domain(),
             false => self.quotient_domain(),
@@ -261,38 +293,108 @@ where
         let num_rows = evaluation_domain.length;
         let num_columns = self.randomized_trace_table().ncols();
         let mut interpolation_polynomials = Array1::zeros(num_columns);
-        let mut extended_columns = Array2::zeros([num_rows, num_columns]);
-        Zip::from(extended_columns.axis_iter_mut(Axis(1)))
+        let mut extended_columns = TableStorage::zeros(
+            num_rows,
+            num_columns,
+            Layout::ColumnMajor,
+            self.scratch_directory(),
+        )?;
+        Zip::from(extended_columns.view_mut().axis_iter_mut(Axis(1)))
             .and(self.randomized_trace_table().axis_iter(Axis(1)))
             .and(interpolation_polynomials.axis_iter_mut(Axis(0)))
             .par_for_each(|lde_column, trace_column, poly| {
+                if handle.is_cancelled() {
+                    return;
+                }
                 let trace_column = trace_column.as_slice().unwrap();
                 let interpolation_polynomial = randomized_trace_domain.interpolate(trace_column);
                 let lde_codeword = evaluation_domain.evaluate(&interpolation_polynomial);
                 Array1::from(lde_codeword).move_into(lde_column);
                 Array0::from_elem((), interpolation_polynomial).move_into(poly);
             });
+        handle.check_cancellation()?;
         self.memoize_low_degree_extended_table(extended_columns);
         self.memoize_interpolation_polynomials(interpolation_polynomials);
+        Ok(())
+    }
+
+    /// Compute the polynomials interpolating all columns of the randomized trace domain table
+    /// _without_ memoizing their low-degree extensions. This keeps the memory footprint small at
+    /// the cost of having to recompute the low-degree extended table whenever it is needed, for
+    /// example, through [`evaluate_all_columns_on`](Self::evaluate_all_columns_on) or
+    /// [`merkle_tree_in_chunks`](Self::merkle_tree_in_chunks).
+    ///
+    /// Stops early if the given handle is [cancelled](ProvingHandle::cancel).
+    fn interpolate_all_columns(&mut self, handle: &ProvingHandle) -> Result<(), ProvingError> {
+        let randomized_trace_domain = self.randomized_trace_domain();
+        let num_columns = self.randomized_trace_table().ncols();
+        let mut interpolation_polynomials = Array1::zeros(num_columns);
+        Zip::from(self.randomized_trace_table().axis_iter(Axis(1)))
+            .and(interpolation_polynomials.axis_iter_mut(Axis(0)))
+            .par_for_each(|trace_column, poly| {
+                if handle.is_cancelled() {
+                    return;
+                }
+                let trace_column = trace_column.as_slice().unwrap();
+                let interpolation_polynomial = randomized_trace_domain.interpolate(trace_column);
+                Array0::from_elem((), interpolation_polynomial).move_into(poly);
+            });
+        handle.check_cancellation()?;
+        self.memoize_interpolation_polynomials(interpolation_polynomials);
+        Ok(())
+    }
+
+    /// Evaluate the [interpolation polynomials](Self::interpolation_polynomials) of all columns,
+    /// including randomizer polynomials, on the given domain. The domain does not need to be
+    /// related to any of the table's domains.
+    fn evaluate_all_columns_on(&self, domain: ArithmeticDomain) -> Array2<FF> {
+        let interpolation_polynomials = self.interpolation_polynomials();
+        let mut codewords = Array2::zeros([domain.length, interpolation_polynomials.len()]);
+        Zip::from(codewords.axis_iter_mut(Axis(1)))
+            .and(interpolation_polynomials)
+            .par_for_each(|codeword, polynomial| {
+                Array1::from(domain.evaluate(polynomial)).move_into(codeword);
+            });
+        codewords
+    }
+
+    /// The rows of the FRI domain table with the given indices, recomputed from the
+    /// [interpolation polynomials](Self::interpolation_polynomials). Includes randomizer
+    /// polynomials.
+    fn recompute_fri_domain_rows(&self, row_indices: &[usize]) -> Vec<Vec<FF>> {
+        let fri_domain = self.fri_domain();
+        let interpolation_polynomials = self.interpolation_polynomials();
+        row_indices
+            .par_iter()
+            .map(|&row_index| {
+                let domain_value: FF = fri_domain.domain_value(row_index as u32).into();
+                interpolation_polynomials
+                    .iter()
+                    .map(|polynomial| polynomial.evaluate(&domain_value))
+                    .collect()
+            })
+            .collect()
     }
 
     /// Not intended for direct use, but through [`Self::low_degree_extend_all_columns`].
-    fn memoize_low_degree_extended_table(&mut self, low_degree_extended_columns: Array2<FF>);
+    fn memoize_low_degree_extended_table(&mut self, low_degree_extended_columns: TableStorage<FF>);
 
     /// Requires having called
     /// [`low_degree_extend_all_columns`](Self::low_degree_extend_all_columns) first.
     fn low_degree_extended_table(&self) -> ArrayView2<FF>;
 
     /// Memoize the polynomials interpolating the columns.
-    /// Not intended for direct use, but through [`Self::low_degree_extend_all_columns`].
+    /// Not intended for direct use, but through [`Self::low_degree_extend_all_columns`] or
+    /// [`Self::interpolate_all_columns`].
     fn memoize_interpolation_polynomials(
         &mut self,
         interpolation_polynomials: Array1<Polynomial<FF>>,
     );
 
     /// Requires having called
-    /// [`low_degree_extend_all_columns`](Self::low_degree_extend_all_columns) first.    
-    fn interpolation_polynomials(&self) -> ArrayView1<Polynomial<XFieldElement>>;
+    /// [`low_degree_extend_all_columns`](Self::low_degree_extend_all_columns) or
+    /// [`interpolate_all_columns`](Self::interpolate_all_columns) first.
+    fn interpolation_polynomials(&self) -> ArrayView1<Polynomial<FF>>;
 
     /// Get one row of the table at an arbitrary index. Notably, the index does not have to be in
     /// any of the domains. In other words, can be used to compute out-of-domain rows. Requires
@@ -302,22 +404,91 @@ where
 
     /// Compute a Merkle tree of the FRI domain table. Every row gives one leaf in the tree.
     /// The function [`hash_row`](Self::hash_one_row) is used to hash each row.
-    fn merkle_tree(&self, maybe_profiler: &mut Option<TritonProfiler>) -> MerkleTree<StarkHasher> {
+    /// Stops early if the given handle is [cancelled](ProvingHandle::cancel).
+    fn merkle_tree(
+        &self,
+        handle: &ProvingHandle,
+        maybe_profiler: &mut Option<TritonProfiler>,
+    ) -> Result<MerkleTree<StarkHasher>, ProvingError> {
+        prof_start!(maybe_profiler, "leafs");
+        let hashed_rows = self.hash_all_fri_domain_rows(handle);
+        prof_stop!(maybe_profiler, "leafs");
+        handle.check_cancellation()?;
+
+        prof_start!(maybe_profiler, "Merkle tree");
+        let merkle_tree = MTMaker::from_digests(&hashed_rows);
+        prof_stop!(maybe_profiler, "Merkle tree");
+
+        Ok(merkle_tree)
+    }
+
+    /// Like [`merkle_tree`](Self::merkle_tree), but does not require the low-degree extended table
+    /// to be memoized. Instead, the FRI domain table is recomputed from the
+    /// [interpolation polynomials](Self::interpolation_polynomials) in `num_chunks` many
+    /// [interleaved chunks](ArithmeticDomain::interleaved_chunk), only one of which is kept in
+    /// memory at any time. The number of chunks must be a power of 2 not exceeding the length of
+    /// the FRI domain.
+    fn merkle_tree_in_chunks(
+        &self,
+        num_chunks: usize,
+        handle: &ProvingHandle,
+        maybe_profiler: &mut Option<TritonProfiler>,
+    ) -> Result<MerkleTree<StarkHasher>, ProvingError> {
         prof_start!(maybe_profiler, "leafs");
-        let hashed_rows = self.hash_all_fri_domain_rows();
+        let hashed_rows = self.hash_all_fri_domain_rows_in_chunks(num_chunks, handle)?;
         prof_stop!(maybe_profiler, "leafs");
 
         prof_start!(maybe_profiler, "Merkle tree");
         let merkle_tree = MTMaker::from_digests(&hashed_rows);
         prof_stop!(maybe_profiler, "Merkle tree");
 
-        merkle_tree
+        Ok(merkle_tree)
     }
 
-    fn hash_all_fri_domain_rows(&self) -> Vec<Digest> {
+    /// Hash every row of the FRI domain table, recomputing the table in chunks. See
+    /// [`merkle_tree_in_chunks`](Self::merkle_tree_in_chunks).
+    fn hash_all_fri_domain_rows_in_chunks(
+        &self,
+        num_chunks: usize,
+        handle: &ProvingHandle,
+    ) -> Result<Vec<Digest>, ProvingError> {
+        let fri_domain = self.fri_domain();
+        let mut hashed_rows = vec![Digest::default(); fri_domain.length];
+        for chunk_index in 0..num_chunks {
+            handle.check_cancellation()?;
+            let chunk_domain = fri_domain.interleaved_chunk(chunk_index, num_chunks);
+            let chunk = self.evaluate_all_columns_on(chunk_domain);
+            let hashed_chunk_rows = chunk
+                .axis_iter(Axis(0))
+                .into_par_iter()
+                .map(Self::hash_one_row)
+                .collect::<Vec<_>>();
+            let row_indices = (chunk_index..).step_by(num_chunks);
+            for (row_index, hashed_row) in row_indices.zip(hashed_chunk_rows) {
+                hashed_rows[row_index] = hashed_row;
+            }
+        }
+        Ok(hashed_rows)
+    }
+
+    /// Hash every row of the FRI domain table. If the given handle is
+    /// [cancelled](ProvingHandle::cancel), the remaining rows are skipped and the result is
+    /// meaningless.
+    ///
+    /// The low-degree extended table is stored column-major. In order to access it in a
+    /// cache-friendly (and, if the table is [memory-mapped](TableStorage), page-friendly) way,
+    /// the rows are gathered in blocks of [`NUM_ROWS_PER_HASHING_BLOCK`] before hashing.
+    fn hash_all_fri_domain_rows(&self, handle: &ProvingHandle) -> Vec<Digest> {
         let fri_domain_table = self.fri_domain_table();
-        let all_rows = fri_domain_table.axis_iter(Axis(0)).into_par_iter();
-        all_rows.map(Self::hash_one_row).collect::<Vec<_>>()
+        let all_blocks = fri_domain_table.axis_chunks_iter(Axis(0), NUM_ROWS_PER_HASHING_BLOCK);
+        let hash_block = |block: ArrayView2<FF>| {
+            if handle.is_cancelled() {
+                return vec![Digest::default(); block.nrows()];
+            }
+            let block = block.as_standard_layout();
+            block.axis_iter(Axis(0)).map(Self::hash_one_row).collect()
+        };
+        all_blocks.into_par_iter().flat_map(hash_block).collect()
     }
 
     fn hash_one_row(row: ArrayView1<FF>) -> Digest;
@@ -340,9 +511,11 @@ pub struct MasterBaseTable {
     quotient_domain: ArithmeticDomain,
     fri_domain: ArithmeticDomain,
 
-    randomized_trace_table: Array2<BFieldElement>,
-    low_degree_extended_table: Option<Array2<BFieldElement>>,
-    interpolation_polynomials: Option<Array1<Polynomial<XFieldElement>>>,
+    scratch_directory: Option<PathBuf>,
+    randomness_seed: Option<RandomnessSeed>,
+    randomized_trace_table: TableStorage<BFieldElement>,
+    low_degree_extended_table: Option<TableStorage<BFieldElement>>,
+    interpolation_polynomials: Option<Array1<Polynomial<BFieldElement>>>,
 }
 
 pub struct MasterExtTable {
@@ -354,8 +527,10 @@ pub struct MasterExtTable {
     quotient_domain: ArithmeticDomain,
     fri_domain: ArithmeticDomain,
 
-    randomized_trace_table: Array2<XFieldElement>,
-    low_degree_extended_table: Option<Array2<XFieldElement>>,
+    scratch_directory: Option<PathBuf>,
+    randomness_seed: Option<RandomnessSeed>,
+    randomized_trace_table: TableStorage<XFieldElement>,
+    low_degree_extended_table: Option<TableStorage<XFieldElement>>,
     interpolation_polynomials: Option<Array1<Polynomial<XFieldElement>>>,
 }
 
@@ -376,15 +551,26 @@ impl MasterTable<BFieldElement> for MasterBaseTable {
         self.fri_domain
     }
 
+    fn scratch_directory(&self) -> Option<&Path> {
+        self.scratch_directory.as_deref()
+    }
+
+    fn randomness_seed(&self) -> Option<RandomnessSeed> {
+        self.randomness_seed
+    }
+
     fn trace_table(&self) -> ArrayView2<BFieldElement> {
         let unit_distance = self.randomized_trace_domain().length / self.trace_domain().length;
-        self.randomized_trace_table.slice(s![..; unit_distance, ..])
+        self.randomized_trace_table
+            .view()
+            .slice_move(s![..; unit_distance, ..])
     }
 
     fn trace_table_mut(&mut self) -> ArrayViewMut2<BFieldElement> {
         let unit_distance = self.randomized_trace_domain().length / self.trace_domain().length;
         self.randomized_trace_table
-            .slice_mut(s![..; unit_distance, ..])
+            .view_mut()
+            .slice_move(s![..; unit_distance, ..])
     }
 
     fn randomized_trace_table(&self) -> ArrayView2<BFieldElement> {
@@ -403,7 +589,9 @@ impl MasterTable<BFieldElement> for MasterBaseTable {
             return low_degree_extended_table.view();
         }
         let unit_distance = self.fri_domain().length / self.quotient_domain().length;
-        low_degree_extended_table.slice(s![..; unit_distance, ..])
+        low_degree_extended_table
+            .view()
+            .slice_move(s![..; unit_distance, ..])
     }
 
     fn fri_domain_table(&self) -> ArrayView2<BFieldElement> {
@@ -414,12 +602,14 @@ impl MasterTable<BFieldElement> for MasterBaseTable {
             return low_degree_extended_table.view();
         }
         let unit_distance = self.quotient_domain().length / self.fri_domain().length;
-        low_degree_extended_table.slice(s![..; unit_distance, ..])
+        low_degree_extended_table
+            .view()
+            .slice_move(s![..; unit_distance, ..])
     }
 
     fn memoize_low_degree_extended_table(
         &mut self,
-        low_degree_extended_columns: Array2<BFieldElement>,
+        low_degree_extended_columns: TableStorage<BFieldElement>,
     ) {
         self.low_degree_extended_table = Some(low_degree_extended_columns);
     }
@@ -435,15 +625,10 @@ impl MasterTable<BFieldElement> for MasterBaseTable {
         &mut self,
         interpolation_polynomials: Array1<Polynomial<BFieldElement>>,
     ) {
-        let interpolation_polynomials = interpolation_polynomials.map(|polynomial| {
-            let coefficients = polynomial.coefficients.iter();
-            let lifted_coefficients = coefficients.map(|b| b.lift()).collect_vec();
-            Polynomial::new(lifted_coefficients)
-        });
         self.interpolation_polynomials = Some(interpolation_polynomials);
     }
 
-    fn interpolation_polynomials(&self) -> ArrayView1<Polynomial<XFieldElement>> {
+    fn interpolation_polynomials(&self) -> ArrayView1<Polynomial<BFieldElement>> {
         let Some(interpolation_polynomials) = &self.interpolation_polynomials else {
             panic!("Interpolation polynomials must be computed first.");
         };
@@ -451,9 +636,13 @@ impl MasterTable<BFieldElement> for MasterBaseTable {
     }
 
     fn row(&self, row_index: XFieldElement) -> Array1<XFieldElement> {
+        let evaluate = |polynomial: &Polynomial<BFieldElement>| {
+            let coefficients = polynomial.coefficients.iter().rev();
+            coefficients.fold(XFieldElement::zero(), |acc, &coeff| acc * row_index + coeff)
+        };
         self.interpolation_polynomials()
             .into_par_iter()
-            .map(|polynomial| polynomial.evaluate(&row_index))
+            .map(evaluate)
             .collect::<Vec<_>>()
             .into()
     }
@@ -480,16 +669,26 @@ impl MasterTable<XFieldElement> for MasterExtTable {
         self.fri_domain
     }
 
+    fn scratch_directory(&self) -> Option<&Path> {
+        self.scratch_directory.as_deref()
+    }
+
+    fn randomness_seed(&self) -> Option<RandomnessSeed> {
+        self.randomness_seed
+    }
+
     fn trace_table(&self) -> ArrayView2<XFieldElement> {
         let unit_distance = self.randomized_trace_domain().length / self.trace_domain().length;
         self.randomized_trace_table
-            .slice(s![..; unit_distance, ..NUM_EXT_COLUMNS])
+            .view()
+            .slice_move(s![..; unit_distance, ..NUM_EXT_COLUMNS])
     }
 
     fn trace_table_mut(&mut self) -> ArrayViewMut2<XFieldElement> {
         let unit_distance = self.randomized_trace_domain().length / self.trace_domain().length;
         self.randomized_trace_table
-            .slice_mut(s![..; unit_distance, ..NUM_EXT_COLUMNS])
+            .view_mut()
+            .slice_move(s![..; unit_distance, ..NUM_EXT_COLUMNS])
     }
 
     fn randomized_trace_table(&self) -> ArrayView2<XFieldElement> {
@@ -508,7 +707,9 @@ impl MasterTable<XFieldElement> for MasterExtTable {
             return low_degree_extended_table.view();
         }
         let unit_distance = self.fri_domain().length / self.quotient_domain().length;
-        low_degree_extended_table.slice(s![..; unit_distance, ..])
+        low_degree_extended_table
+            .view()
+            .slice_move(s![..; unit_distance, ..])
     }
 
     fn fri_domain_table(&self) -> ArrayView2<XFieldElement> {
@@ -519,12 +720,14 @@ impl MasterTable<XFieldElement> for MasterExtTable {
             return low_degree_extended_table.view();
         }
         let unit_distance = self.quotient_domain().length / self.fri_domain().length;
-        low_degree_extended_table.slice(s![..; unit_distance, ..])
+        low_degree_extended_table
+            .view()
+            .slice_move(s![..; unit_distance, ..])
     }
 
     fn memoize_low_degree_extended_table(
         &mut self,
-        low_degree_extended_columns: Array2<XFieldElement>,
+        low_degree_extended_columns: TableStorage<XFieldElement>,
     ) {
         self.low_degree_extended_table = Some(low_degree_extended_columns);
     }
@@ -576,6 +779,27 @@ impl MasterBaseTable {
         quotient_domain: ArithmeticDomain,
         fri_domain: ArithmeticDomain,
     ) -> Self {
+        Self::new_with_scratch_directory(
+            aet,
+            num_trace_randomizers,
+            quotient_domain,
+            fri_domain,
+            None,
+        )
+        .expect("creating an in-memory master base table must succeed")
+    }
+
+    /// Like [`new`](Self::new), but if a scratch directory is given, the table is backed by a
+    /// memory-mapped file in that directory. The same holds for the table's low-degree extension
+    /// and for the [extension table](Self::try_extend) derived from it. This allows generating
+    /// proofs for traces that do not fit into memory.
+    pub fn new_with_scratch_directory(
+        aet: &AlgebraicExecutionTrace,
+        num_trace_randomizers: usize,
+        quotient_domain: ArithmeticDomain,
+        fri_domain: ArithmeticDomain,
+        scratch_directory: Option<&Path>,
+    ) -> std::io::Result<Self> {
         let padded_height = aet.padded_height();
         let trace_domain = ArithmeticDomain::of_length(padded_height);
 
@@ -585,7 +809,12 @@ impl MasterBaseTable {
 
         let num_rows = randomized_padded_trace_len;
         let num_columns = NUM_BASE_COLUMNS;
-        let randomized_trace_table = Array2::zeros([num_rows, num_columns].f());
+        let randomized_trace_table = TableStorage::zeros(
+            num_rows,
+            num_columns,
+            Layout::ColumnMajor,
+            scratch_directory,
+        )?;
 
         let mut master_base_table = Self {
             num_trace_randomizers,
@@ -600,6 +829,8 @@ impl MasterBaseTable {
             randomized_trace_domain,
             quotient_domain,
             fri_domain,
+            scratch_directory: scratch_directory.map(Path::to_path_buf),
+            randomness_seed: None,
             randomized_trace_table,
             low_degree_extended_table: None,
             interpolation_polynomials: None,
@@ -634,7 +865,17 @@ impl MasterBaseTable {
         // Filling the degree-lowering table only makes sense after padding has happened.
         // Hence, this table is omitted here.
 
-        master_base_table
+        Ok(master_base_table)
+    }
+
+    /// Use the given seed for [trace randomization](MasterTable::randomize_trace) of this table
+    /// and of the [extension table](Self::extend) derived from it, including the randomizer
+    /// polynomials. With a seed, the randomness is fully reproducible. Without one, it is drawn
+    /// from the operating system.
+    #[must_use]
+    pub fn with_randomness_seed(mut self, seed: Option<RandomnessSeed>) -> Self {
+        self.randomness_seed = seed;
+        self
     }
 
     /// Pad the trace to the next power of two using the various, table-specific padding rules.
@@ -650,7 +891,8 @@ impl MasterBaseTable {
         let unit_distance = self.randomized_trace_domain().length / self.trace_domain().length;
         let mut master_table_without_randomizers = self
             .randomized_trace_table
-            .slice_mut(s![..; unit_distance, ..]);
+            .view_mut()
+            .slice_move(s![..; unit_distance, ..]);
         let (program_table, mut rest) = master_table_without_randomizers.multi_slice_mut((
             s![.., ..ProgramBaseTableColumn::COUNT],
             s![.., ProgramBaseTableColumn::COUNT..],
@@ -745,18 +987,44 @@ impl MasterBaseTable {
     /// Create a `MasterExtTable` from a `MasterBaseTable` by `.extend()`ing each individual base
     /// table. The `.extend()` for each table is specific to that table, but always involves
     /// adding some number of columns.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table is backed by memory-mapped files and the scratch directory cannot be
+    /// used anymore. Use [`try_extend`](Self::try_extend) to handle this case.
     pub fn extend(
         &self,
         challenges: &Challenges,
         num_randomizer_polynomials: usize,
     ) -> MasterExtTable {
+        self.try_extend(challenges, num_randomizer_polynomials)
+            .expect("scratch directory must be usable for extending the master base table")
+    }
+
+    /// Like [`extend`](Self::extend), but returns an error if the extension table cannot be
+    /// created in this table's [scratch directory](MasterTable::scratch_directory).
+    pub fn try_extend(
+        &self,
+        challenges: &Challenges,
+        num_randomizer_polynomials: usize,
+    ) -> std::io::Result<MasterExtTable> {
         // randomizer polynomials
         let num_rows = self.randomized_trace_table().nrows();
         let num_columns = NUM_EXT_COLUMNS + num_randomizer_polynomials;
-        let mut randomized_trace_extension_table = Array2::zeros([num_rows, num_columns].f());
-        randomized_trace_extension_table
-            .slice_mut(s![.., NUM_EXT_COLUMNS..])
-            .par_mapv_inplace(|_| random::<XFieldElement>());
+        let mut randomized_trace_extension_table = TableStorage::zeros(
+            num_rows,
+            num_columns,
+            Layout::ColumnMajor,
+            self.scratch_directory(),
+        )?;
+        let randomness_seed = self.randomness_seed.map(derive_seed);
+        let randomizer_polynomials_seed = randomness_seed.map(derive_seed);
+        let mut randomized_trace_extension_table_view = randomized_trace_extension_table.view_mut();
+        let randomizer_polynomials =
+            randomized_trace_extension_table_view.slice_mut(s![.., NUM_EXT_COLUMNS..]);
+        randomize_rows(randomizer_polynomials, randomizer_polynomials_seed, |_| {
+            true
+        });
 
         let mut master_ext_table = MasterExtTable {
             num_trace_randomizers: self.num_trace_randomizers,
@@ -765,6 +1033,8 @@ impl MasterBaseTable {
             randomized_trace_domain: self.randomized_trace_domain(),
             quotient_domain: self.quotient_domain(),
             fri_domain: self.fri_domain(),
+            scratch_directory: self.scratch_directory.clone(),
+            randomness_seed,
             randomized_trace_table: randomized_trace_extension_table,
             low_degree_extended_table: None,
             interpolation_polynomials: None,
@@ -775,7 +1045,8 @@ impl MasterBaseTable {
         let unit_distance = self.randomized_trace_domain().length / self.trace_domain().length;
         let mut master_ext_table_without_randomizers = master_ext_table
             .randomized_trace_table
-            .slice_mut(s![..; unit_distance, ..NUM_EXT_COLUMNS]);
+            .view_mut()
+            .slice_move(s![..; unit_distance, ..NUM_EXT_COLUMNS]);
         let (program_table, mut rest) = master_ext_table_without_randomizers.multi_slice_mut((
             s![.., ..ProgramExtTableColumn::COUNT],
             s![.., ProgramExtTableColumn::COUNT..],
@@ -836,7 +1107,7 @@ impl MasterBaseTable {
             challenges,
         );
 
-        master_ext_table
+        Ok(master_ext_table)
     }
 
     fn all_extend_functions() -> [ExtendFunction; NUM_TABLES_WITHOUT_DEGREE_LOWERING] {
@@ -890,7 +1161,8 @@ impl MasterBaseTable {
         let column_indices = Self::column_indices_for_table(table_id);
         let unit_distance = self.randomized_trace_domain().length / self.trace_domain().length;
         self.randomized_trace_table
-            .slice(s![..; unit_distance, column_indices])
+            .view()
+            .slice_move(s![..; unit_distance, column_indices])
     }
 
     /// A mutable view of the specified table, without any randomizers.
@@ -898,12 +1170,26 @@ impl MasterBaseTable {
         let column_indices = Self::column_indices_for_table(table_id);
         let unit_distance = self.randomized_trace_domain().length / self.trace_domain().length;
         self.randomized_trace_table
-            .slice_mut(s![..; unit_distance, column_indices])
+            .view_mut()
+            .slice_move(s![..; unit_distance, column_indices])
     }
 }
 
 impl MasterExtTable {
+    /// The randomizer polynomials evaluated on the FRI domain. If only the
+    /// [interpolation polynomials](MasterTable::interpolate_all_columns) have been computed, the
+    /// codewords are recomputed from them.
     pub fn fri_domain_randomizer_polynomials(&self) -> Vec<Array1<XFieldElement>> {
+        if self.low_degree_extended_table.is_none() {
+            let fri_domain = self.fri_domain();
+            let randomizer_polynomials = self.interpolation_polynomials();
+            let randomizer_polynomials = randomizer_polynomials.slice(s![NUM_EXT_COLUMNS..]);
+            return randomizer_polynomials
+                .iter()
+                .map(|polynomial| fri_domain.evaluate(polynomial).into())
+                .collect();
+        }
+
         let fri_domain_table = self.fri_domain_table();
         let randomizer_polynomials = fri_domain_table.slice(s![.., NUM_EXT_COLUMNS..]);
         randomizer_polynomials
@@ -933,7 +1219,8 @@ impl MasterExtTable {
         let column_indices = Self::column_indices_for_table(table_id);
         let unit_distance = self.randomized_trace_domain().length / self.trace_domain().length;
         self.randomized_trace_table
-            .slice(s![..; unit_distance, column_indices])
+            .view()
+            .slice_move(s![..; unit_distance, column_indices])
     }
 
     /// A mutable view of the specified table, without any randomizers.
@@ -941,10 +1228,49 @@ impl MasterExtTable {
         let column_indices = Self::column_indices_for_table(table_id);
         let unit_distance = self.randomized_trace_domain().length / self.trace_domain().length;
         self.randomized_trace_table
-            .slice_mut(s![..; unit_distance, column_indices])
+            .view_mut()
+            .slice_move(s![..; unit_distance, column_indices])
     }
 }
 
+/// Overwrite the elements in all rows of the given table for which the predicate holds with random
+/// elements. With a seed, every column is filled from its own stream of a [`ChaCha12Rng`],
+/// making the result independent of the order in which the columns are processed in parallel.
+fn randomize_rows<FF>(
+    mut table: ArrayViewMut2<FF>,
+    seed: Option<RandomnessSeed>,
+    is_randomized_row: impl Fn(usize) -> bool + Sync,
+) where
+    FF: Send + Sync,
+    Standard: Distribution<FF>,
+{
+    let randomize_column = |mut column: ArrayViewMut1<FF>, rng: &mut dyn rand::RngCore| {
+        for (row_index, element) in column.indexed_iter_mut() {
+            if is_randomized_row(row_index) {
+                *element = rng.gen();
+            }
+        }
+    };
+    let columns = table.axis_iter_mut(Axis(1)).into_par_iter().enumerate();
+    columns.for_each(|(column_index, column)| match seed {
+        Some(seed) => randomize_column(column, &mut seeded_column_rng(seed, column_index)),
+        None => randomize_column(column, &mut thread_rng()),
+    });
+}
+
+fn seeded_column_rng(seed: RandomnessSeed, column_index: usize) -> ChaCha12Rng {
+    let mut rng = ChaCha12Rng::from_seed(seed);
+    rng.set_stream(column_index as u64);
+    rng
+}
+
+/// A new seed, independent of any randomness drawn from the given seed's column streams.
+fn derive_seed(seed: RandomnessSeed) -> RandomnessSeed {
+    let mut rng = ChaCha12Rng::from_seed(seed);
+    rng.set_stream(u64::MAX);
+    rng.gen()
+}
+
 pub(crate) fn all_degrees_with_origin(
     interpolant_degree: Degree,
     padded_height: usize,
@@ -1023,6 +1349,28 @@ pub fn terminal_quotient_zerofier_inverse(
     BFieldElement::batch_inversion(zerofier_codeword).into()
 }
 
+/// The inverses of the zerofiers of all four constraint categories, evaluated on the quotient
+/// domain. They only depend on the trace domain and the quotient domain, _i.e._, not on the trace
+/// itself. Proofs for traces of the same padded height can share them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZerofierInverses {
+    pub initial: Array1<BFieldElement>,
+    pub consistency: Array1<BFieldElement>,
+    pub transition: Array1<BFieldElement>,
+    pub terminal: Array1<BFieldElement>,
+}
+
+impl ZerofierInverses {
+    pub fn new(trace_domain: ArithmeticDomain, quotient_domain: ArithmeticDomain) -> Self {
+        Self {
+            initial: initial_quotient_zerofier_inverse(quotient_domain),
+            consistency: consistency_quotient_zerofier_inverse(trace_domain, quotient_domain),
+            transition: transition_quotient_zerofier_inverse(trace_domain, quotient_domain),
+            terminal: terminal_quotient_zerofier_inverse(trace_domain, quotient_domain),
+        }
+    }
+}
+
 /// Computes an array containing all quotients – the Master Quotient Table. Each column corresponds
 /// to a different quotient. The quotients are ordered by category – initial, consistency,
 /// transition, and then terminal. Within each category, the quotients follow the canonical order
@@ -1038,12 +1386,42 @@ pub fn all_quotients(
     trace_domain: ArithmeticDomain,
     quotient_domain: ArithmeticDomain,
     challenges: &Challenges,
+    handle: &ProvingHandle,
+    maybe_profiler: &mut Option<TritonProfiler>,
+) -> Result<Array2<XFieldElement>, ProvingError> {
+    prof_start!(maybe_profiler, "zerofier inverses");
+    let zerofier_inverses = ZerofierInverses::new(trace_domain, quotient_domain);
+    prof_stop!(maybe_profiler, "zerofier inverses");
+    all_quotients_with_zerofier_inverses(
+        quotient_domain_master_base_table,
+        quotient_domain_master_ext_table,
+        trace_domain,
+        quotient_domain,
+        &zerofier_inverses,
+        challenges,
+        handle,
+        maybe_profiler,
+    )
+}
+
+/// Like [`all_quotients`], but uses the given, pre-computed [`ZerofierInverses`], which must
+/// belong to the given trace domain and quotient domain.
+#[allow(clippy::too_many_arguments)]
+pub fn all_quotients_with_zerofier_inverses(
+    quotient_domain_master_base_table: ArrayView2<BFieldElement>,
+    quotient_domain_master_ext_table: ArrayView2<XFieldElement>,
+    trace_domain: ArithmeticDomain,
+    quotient_domain: ArithmeticDomain,
+    zerofier_inverses: &ZerofierInverses,
+    challenges: &Challenges,
+    handle: &ProvingHandle,
     maybe_profiler: &mut Option<TritonProfiler>,
-) -> Array2<XFieldElement> {
+) -> Result<Array2<XFieldElement>, ProvingError> {
     assert_eq!(
         quotient_domain.length,
         quotient_domain_master_base_table.nrows(),
     );
+    assert_eq!(quotient_domain.length, zerofier_inverses.initial.len());
     assert_eq!(
         quotient_domain.length,
         quotient_domain_master_ext_table.nrows()
@@ -1063,44 +1441,48 @@ pub fn all_quotients(
         quotient_domain_master_base_table,
         quotient_domain_master_ext_table,
         &mut quotient_table.slice_mut(s![.., ..init_section_end]),
-        initial_quotient_zerofier_inverse(quotient_domain).view(),
+        zerofier_inverses.initial.view(),
         challenges,
     );
     prof_stop!(maybe_profiler, "initial");
 
+    handle.check_cancellation()?;
     prof_start!(maybe_profiler, "consistency", "AIR");
     MasterExtTable::fill_consistency_quotients(
         quotient_domain_master_base_table,
         quotient_domain_master_ext_table,
         &mut quotient_table.slice_mut(s![.., init_section_end..cons_section_end]),
-        consistency_quotient_zerofier_inverse(trace_domain, quotient_domain).view(),
+        zerofier_inverses.consistency.view(),
         challenges,
     );
     prof_stop!(maybe_profiler, "consistency");
 
+    handle.check_cancellation()?;
     prof_start!(maybe_profiler, "transition", "AIR");
     MasterExtTable::fill_transition_quotients(
         quotient_domain_master_base_table,
         quotient_domain_master_ext_table,
         &mut quotient_table.slice_mut(s![.., cons_section_end..tran_section_end]),
-        transition_quotient_zerofier_inverse(trace_domain, quotient_domain).view(),
+        zerofier_inverses.transition.view(),
         challenges,
         trace_domain,
         quotient_domain,
     );
     prof_stop!(maybe_profiler, "transition");
 
+    handle.check_cancellation()?;
     prof_start!(maybe_profiler, "terminal", "AIR");
     MasterExtTable::fill_terminal_quotients(
         quotient_domain_master_base_table,
         quotient_domain_master_ext_table,
         &mut quotient_table.slice_mut(s![.., tran_section_end..term_section_end]),
-        terminal_quotient_zerofier_inverse(trace_domain, quotient_domain).view(),
+        zerofier_inverses.terminal.view(),
         challenges,
     );
     prof_stop!(maybe_profiler, "terminal");
 
-    unsafe { quotient_table.assume_init() }
+    handle.check_cancellation()?;
+    Ok(unsafe { quotient_table.assume_init() })
 }
 
 pub fn num_quotients() -> usize {
@@ -1510,6 +1892,42 @@ mod tests {
         }
     }
 
+    #[test]
+    fn recomputing_low_degree_extension_in_chunks_gives_memoized_results() {
+        let program = triton_program!(push 3 push 4 add push 5 mul pop 1 halt);
+        let program = ProgramAndInput::without_input(program);
+        let (_, _, mut memoizing_table) = master_base_table_for_low_security_level(program);
+        memoizing_table.pad();
+        memoizing_table.randomize_trace();
+        let mut recomputing_table = memoizing_table.clone();
+
+        let handle = ProvingHandle::new();
+        memoizing_table
+            .low_degree_extend_all_columns(&handle)
+            .unwrap();
+        recomputing_table.interpolate_all_columns(&handle).unwrap();
+
+        let memoized_merkle_tree = memoizing_table.merkle_tree(&handle, &mut None).unwrap();
+        for num_chunks in [1, 2, 8] {
+            let recomputed_merkle_tree = recomputing_table
+                .merkle_tree_in_chunks(num_chunks, &handle, &mut None)
+                .unwrap();
+            assert_eq!(
+                memoized_merkle_tree.get_root(),
+                recomputed_merkle_tree.get_root()
+            );
+        }
+
+        let last_row_index = memoizing_table.fri_domain().length - 1;
+        let row_indices = [0, 1, 7, last_row_index];
+        let memoized_rows = row_indices
+            .iter()
+            .map(|&i| memoizing_table.fri_domain_table().row(i).to_vec())
+            .collect_vec();
+        let recomputed_rows = recomputing_table.recompute_fri_domain_rows(&row_indices);
+        assert_eq!(memoized_rows, recomputed_rows);
+    }
+
     #[test]
     fn master_ext_table_mut() {
         let num_randomizer_polynomials = 3;
@@ -1531,7 +1949,9 @@ mod tests {
             randomized_trace_domain,
             quotient_domain,
             fri_domain,
-            randomized_trace_table,
+            scratch_directory: None,
+            randomness_seed: None,
+            randomized_trace_table: randomized_trace_table.into(),
             low_degree_extended_table: None,
             interpolation_polynomials: None,
         };
@@ -1557,13 +1977,13 @@ mod tests {
             .move_into(&mut master_table.table_mut(U32Table));
 
         let trace_domain_element = |column| {
-            let maybe_element = master_table.randomized_trace_table.get((0, column));
-            let xfe = maybe_element.unwrap().to_owned();
+            let randomized_trace_table = master_table.randomized_trace_table.view();
+            let xfe = randomized_trace_table.get((0, column)).unwrap().to_owned();
             xfe.unlift().unwrap().value()
         };
         let not_trace_domain_element = |column| {
-            let maybe_element = master_table.randomized_trace_table.get((1, column));
-            let xfe = maybe_element.unwrap().to_owned();
+            let randomized_trace_table = master_table.randomized_trace_table.view();
+            let xfe = randomized_trace_table.get((1, column)).unwrap().to_owned();
             xfe.unlift().unwrap().value()
         };
 
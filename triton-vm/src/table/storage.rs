@@ -0,0 +1,321 @@
+//! Storage for the data of the [master tables](crate::table::master_table::MasterTable).
+//!
+//! By default, all data lives on the heap. For traces that do not comfortably fit into memory, the
+//! data can instead be backed by memory-mapped files in a scratch directory. The operating system
+//! then pages the data in and out as needed. The files are unlinked right after creation, leaving
+//! nothing behind in the scratch directory, even if the process is terminated abruptly.
+//!
+//! The layout of the data is the same for both kinds of storage. All master tables are computed
+//! column by column and therefore use column-major layout. Where rows are needed, for example
+//! for hashing, they are gathered in blocks to keep memory accesses local.
+
+use std::io;
+use std::marker::PhantomData;
+use std::path::Path;
+use std::path::PathBuf;
+use std::ptr::NonNull;
+
+use ndarray::Array2;
+use ndarray::ArrayView2;
+use ndarray::ArrayViewMut2;
+use ndarray::ShapeBuilder;
+use num_traits::Zero;
+
+/// The order in which the elements of a table are laid out in memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Layout {
+    /// The elements of each row are contiguous. Suited for hashing rows.
+    RowMajor,
+
+    /// The elements of each column are contiguous. Suited for column-wise computations like
+    /// interpolation and low-degree extension.
+    ColumnMajor,
+}
+
+/// The data of a table, either on the heap or in a memory-mapped file.
+#[derive(Debug)]
+pub enum TableStorage<FF> {
+    InMemory(Array2<FF>),
+    MemoryMapped(MemoryMappedArray<FF>),
+}
+
+impl<FF> TableStorage<FF>
+where
+    FF: Copy + Zero + Send + Sync,
+{
+    /// A table of the given shape and layout filled with zeros. If a scratch directory is given,
+    /// the table is backed by a memory-mapped file in that directory. Otherwise, the table lives
+    /// on the heap, and creation cannot fail.
+    pub fn zeros(
+        num_rows: usize,
+        num_columns: usize,
+        layout: Layout,
+        scratch_directory: Option<&Path>,
+    ) -> io::Result<Self> {
+        let Some(scratch_directory) = scratch_directory else {
+            let shape = [num_rows, num_columns].set_f(layout == Layout::ColumnMajor);
+            return Ok(Self::InMemory(Array2::zeros(shape)));
+        };
+        let array = MemoryMappedArray::zeros(num_rows, num_columns, layout, scratch_directory)?;
+        Ok(Self::MemoryMapped(array))
+    }
+
+    pub fn view(&self) -> ArrayView2<'_, FF> {
+        match self {
+            Self::InMemory(array) => array.view(),
+            Self::MemoryMapped(array) => array.view(),
+        }
+    }
+
+    pub fn view_mut(&mut self) -> ArrayViewMut2<'_, FF> {
+        match self {
+            Self::InMemory(array) => array.view_mut(),
+            Self::MemoryMapped(array) => array.view_mut(),
+        }
+    }
+
+    pub fn is_memory_mapped(&self) -> bool {
+        matches!(self, Self::MemoryMapped(_))
+    }
+}
+
+impl<FF> Clone for TableStorage<FF>
+where
+    FF: Copy + Zero + Send + Sync,
+{
+    fn clone(&self) -> Self {
+        match self {
+            Self::InMemory(array) => Self::InMemory(array.clone()),
+            Self::MemoryMapped(array) => Self::MemoryMapped(array.clone()),
+        }
+    }
+}
+
+impl<FF> From<Array2<FF>> for TableStorage<FF> {
+    fn from(array: Array2<FF>) -> Self {
+        Self::InMemory(array)
+    }
+}
+
+/// A two-dimensional array backed by a memory-mapped file. The file is created in, and
+/// immediately unlinked from, the scratch directory; the mapping keeps the data alive until the
+/// array is dropped.
+#[derive(Debug)]
+pub struct MemoryMappedArray<FF> {
+    pointer: NonNull<FF>,
+    num_rows: usize,
+    num_columns: usize,
+    layout: Layout,
+    scratch_directory: PathBuf,
+    _owns_elements: PhantomData<FF>,
+}
+
+// SAFETY: The array exclusively owns its mapping, just like `Vec<FF>` exclusively owns its
+// allocation. Shared and mutable access is only possible through borrowing views.
+unsafe impl<FF: Send> Send for MemoryMappedArray<FF> {}
+unsafe impl<FF: Sync> Sync for MemoryMappedArray<FF> {}
+
+impl<FF> MemoryMappedArray<FF>
+where
+    FF: Copy + Zero + Send + Sync,
+{
+    pub fn zeros(
+        num_rows: usize,
+        num_columns: usize,
+        layout: Layout,
+        scratch_directory: &Path,
+    ) -> io::Result<Self> {
+        let num_elements = num_rows.checked_mul(num_columns).ok_or_else(too_large)?;
+        let num_bytes = num_elements
+            .checked_mul(std::mem::size_of::<FF>())
+            .ok_or_else(too_large)?;
+        let pointer = match num_bytes {
+            0 => NonNull::dangling(),
+            _ => map_scratch_file(scratch_directory, num_bytes)?.cast(),
+        };
+        debug_assert!(is_all_zero_bytes(&FF::zero()));
+
+        // A freshly created file reads as all zero bytes. Explicitly writing zeros would dirty
+        // every page and force writing the entire table to disk.
+        Ok(Self {
+            pointer,
+            num_rows,
+            num_columns,
+            layout,
+            scratch_directory: scratch_directory.to_path_buf(),
+            _owns_elements: PhantomData,
+        })
+    }
+
+    fn shape(&self) -> ndarray::Shape<ndarray::Ix2> {
+        [self.num_rows, self.num_columns].set_f(self.layout == Layout::ColumnMajor)
+    }
+
+    pub fn view(&self) -> ArrayView2<'_, FF> {
+        // SAFETY: The mapping holds exactly `num_rows · num_columns` properly aligned and
+        // initialized elements and lives as long as `self`.
+        unsafe { ArrayView2::from_shape_ptr(self.shape(), self.pointer.as_ptr()) }
+    }
+
+    pub fn view_mut(&mut self) -> ArrayViewMut2<'_, FF> {
+        // SAFETY: See `view`. Borrowing `self` mutably guarantees exclusive access.
+        unsafe { ArrayViewMut2::from_shape_ptr(self.shape(), self.pointer.as_ptr()) }
+    }
+}
+
+impl<FF> Clone for MemoryMappedArray<FF>
+where
+    FF: Copy + Zero + Send + Sync,
+{
+    /// Copies the data into a new file in the same scratch directory.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the scratch directory can no longer be used.
+    fn clone(&self) -> Self {
+        let mut clone = Self::zeros(
+            self.num_rows,
+            self.num_columns,
+            self.layout,
+            &self.scratch_directory,
+        )
+        .expect("scratch directory must be usable for cloning a memory-mapped table");
+        clone.view_mut().assign(&self.view());
+        clone
+    }
+}
+
+impl<FF> Drop for MemoryMappedArray<FF> {
+    fn drop(&mut self) {
+        let num_bytes = self.num_rows * self.num_columns * std::mem::size_of::<FF>();
+        unmap(self.pointer.cast(), num_bytes);
+    }
+}
+
+/// Whether the in-memory representation of the given element consists of zero bytes only.
+fn is_all_zero_bytes<T>(element: &T) -> bool {
+    let pointer = (element as *const T).cast::<u8>();
+    // SAFETY: Reading the `size_of::<T>()` bytes of a valid reference.
+    let bytes = unsafe { std::slice::from_raw_parts(pointer, std::mem::size_of::<T>()) };
+    bytes.iter().all(|&byte| byte == 0)
+}
+
+fn too_large() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, "table is too large")
+}
+
+/// Create a file of the given size in the scratch directory, map it into memory, and unlink it.
+#[cfg(unix)]
+fn map_scratch_file(scratch_directory: &Path, num_bytes: usize) -> io::Result<NonNull<u8>> {
+    use std::fs::OpenOptions;
+    use std::os::unix::io::AsRawFd;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+
+    static NUM_CREATED_FILES: AtomicUsize = AtomicUsize::new(0);
+
+    let file_index = NUM_CREATED_FILES.fetch_add(1, Ordering::Relaxed);
+    let file_name = format!("triton-vm-{}-{file_index}.table", std::process::id());
+    let file_path = scratch_directory.join(file_name);
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create_new(true)
+        .open(&file_path)?;
+    std::fs::remove_file(&file_path)?;
+    file.set_len(num_bytes as u64)?;
+
+    // SAFETY: Mapping a freshly created file that no one else has access to.
+    let pointer = unsafe {
+        libc::mmap(
+            std::ptr::null_mut(),
+            num_bytes,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_SHARED,
+            file.as_raw_fd(),
+            0,
+        )
+    };
+    if pointer == libc::MAP_FAILED {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(NonNull::new(pointer.cast()).expect("successful mapping must not be null"))
+}
+
+#[cfg(not(unix))]
+fn map_scratch_file(_: &Path, _: usize) -> io::Result<NonNull<u8>> {
+    let error_message = "memory-mapped tables are only supported on Unix-like systems";
+    Err(io::Error::new(io::ErrorKind::Unsupported, error_message))
+}
+
+#[cfg(unix)]
+fn unmap(pointer: NonNull<u8>, num_bytes: usize) {
+    if num_bytes == 0 {
+        return;
+    }
+    // SAFETY: The pointer and length stem from a successful call to `mmap`.
+    unsafe { libc::munmap(pointer.as_ptr().cast(), num_bytes) };
+}
+
+#[cfg(not(unix))]
+fn unmap(_: NonNull<u8>, _: usize) {}
+
+#[cfg(test)]
+mod tests {
+    use assert2::assert;
+    use assert2::let_assert;
+    use twenty_first::shared_math::b_field_element::BFieldElement;
+    use twenty_first::shared_math::x_field_element::XFieldElement;
+
+    use super::*;
+
+    #[test]
+    fn memory_mapped_table_behaves_like_in_memory_table() {
+        let scratch_directory = tempfile::tempdir().unwrap();
+        for layout in [Layout::RowMajor, Layout::ColumnMajor] {
+            let mut in_memory = TableStorage::zeros(13, 7, layout, None).unwrap();
+            let mut mapped =
+                TableStorage::zeros(13, 7, layout, Some(scratch_directory.path())).unwrap();
+            assert!(!in_memory.is_memory_mapped());
+            assert!(mapped.is_memory_mapped());
+
+            for (index, element) in in_memory.view_mut().iter_mut().enumerate() {
+                *element = BFieldElement::new(index as u64);
+            }
+            mapped.view_mut().assign(&in_memory.view());
+            assert!(in_memory.view() == mapped.view());
+            assert!(in_memory.view().strides() == mapped.view().strides());
+        }
+    }
+
+    #[test]
+    fn memory_mapped_table_leaves_no_files_behind() {
+        let scratch_directory = tempfile::tempdir().unwrap();
+        let scratch_path = Some(scratch_directory.path());
+        let table = TableStorage::<XFieldElement>::zeros(100, 3, Layout::RowMajor, scratch_path);
+        let table = table.unwrap();
+        let clone = table.clone();
+        assert!(table.view() == clone.view());
+
+        let num_files = std::fs::read_dir(scratch_directory.path()).unwrap().count();
+        assert!(0 == num_files);
+    }
+
+    #[test]
+    fn memory_mapped_table_can_be_empty() {
+        let scratch_directory = tempfile::tempdir().unwrap();
+        let scratch_path = Some(scratch_directory.path());
+        let table = TableStorage::<BFieldElement>::zeros(0, 3, Layout::ColumnMajor, scratch_path);
+        let_assert!(Ok(table) = table);
+        assert!(table.view().is_empty());
+    }
+
+    #[test]
+    fn nonexistent_scratch_directory_gives_error() {
+        let scratch_directory = tempfile::tempdir().unwrap();
+        let nonexistent_path = scratch_directory.path().join("nonexistent");
+        let scratch_path = Some(nonexistent_path.as_path());
+        let table = TableStorage::<BFieldElement>::zeros(1, 1, Layout::RowMajor, scratch_path);
+        assert!(let Err(_) = table);
+    }
+}
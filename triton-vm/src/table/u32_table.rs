@@ -9,6 +9,8 @@ use ndarray::ArrayViewMut2;
 use ndarray::Axis;
 use num_traits::One;
 use num_traits::Zero;
+use serde_derive::Deserialize;
+use serde_derive::Serialize;
 use std::cmp::max;
 use strum::EnumCount;
 use twenty_first::shared_math::b_field_element::BFieldElement;
@@ -41,7 +43,7 @@ pub const EXT_WIDTH: usize = U32ExtTableColumn::COUNT;
 pub const FULL_WIDTH: usize = BASE_WIDTH + EXT_WIDTH;
 
 /// An executed u32 instruction as well as its operands.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct U32TableEntry {
     pub instruction: Instruction,
     pub left_operand: BFieldElement,
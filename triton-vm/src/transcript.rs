@@ -0,0 +1,246 @@
+//! A record of all interactions with the Fiat-Shamir sponge of a [`ProofStream`], for debugging
+//! divergences between prover and verifier. See [`ProofStream::with_transcript_recording`].
+//!
+//! [`ProofStream`]: crate::proof_stream::ProofStream
+//! [`ProofStream::with_transcript_recording`]: crate::proof_stream::ProofStream::with_transcript_recording
+
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+
+use arbitrary::Arbitrary;
+use itertools::EitherOrBoth;
+use itertools::Itertools;
+use twenty_first::shared_math::digest::Digest;
+use twenty_first::shared_math::x_field_element::XFieldElement;
+
+/// All steps that altered or used the Fiat-Shamir sponge state of a
+/// [`ProofStream`](crate::proof_stream::ProofStream), in order.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Arbitrary)]
+pub struct Transcript {
+    pub steps: Vec<TranscriptStep>,
+}
+
+/// One interaction with the Fiat-Shamir sponge. Every step records a digest of the sponge state
+/// right after the step.
+#[derive(Debug, Clone, PartialEq, Eq, Arbitrary)]
+pub enum TranscriptStep {
+    /// An item was absorbed into the sponge. For [`ProofItem`](crate::proof_item::ProofItem)s,
+    /// the name is the variant's name, for all other items, it is the name of their type.
+    Absorb {
+        item_name: String,
+        encoded_len: usize,
+        sponge_state_digest: Digest,
+    },
+
+    /// Scalars were sampled from the sponge.
+    SampleScalars {
+        scalars: Vec<XFieldElement>,
+        sponge_state_digest: Digest,
+    },
+
+    /// Indices in the range `[0, upper_bound)` were sampled from the sponge.
+    SampleIndices {
+        upper_bound: usize,
+        indices: Vec<usize>,
+        sponge_state_digest: Digest,
+    },
+}
+
+/// The first step in which two [`Transcript`]s differ. A missing step means that the
+/// corresponding transcript ended before the divergence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranscriptDivergence {
+    pub step_index: usize,
+    pub step: Option<TranscriptStep>,
+    pub other_step: Option<TranscriptStep>,
+}
+
+impl Transcript {
+    /// Compare with another transcript, _e.g._, the prover's transcript with the verifier's.
+    /// Returns the first step in which the transcripts differ, or `None` if they are identical.
+    pub fn first_divergence(&self, other: &Self) -> Option<TranscriptDivergence> {
+        self.steps
+            .iter()
+            .zip_longest(&other.steps)
+            .enumerate()
+            .find_map(|(step_index, steps)| match steps {
+                EitherOrBoth::Both(step, other_step) if step == other_step => None,
+                EitherOrBoth::Both(step, other_step) => Some(TranscriptDivergence {
+                    step_index,
+                    step: Some(step.clone()),
+                    other_step: Some(other_step.clone()),
+                }),
+                EitherOrBoth::Left(step) => Some(TranscriptDivergence {
+                    step_index,
+                    step: Some(step.clone()),
+                    other_step: None,
+                }),
+                EitherOrBoth::Right(other_step) => Some(TranscriptDivergence {
+                    step_index,
+                    step: None,
+                    other_step: Some(other_step.clone()),
+                }),
+            })
+    }
+}
+
+impl TranscriptStep {
+    pub fn sponge_state_digest(&self) -> Digest {
+        match self {
+            TranscriptStep::Absorb {
+                sponge_state_digest,
+                ..
+            }
+            | TranscriptStep::SampleScalars {
+                sponge_state_digest,
+                ..
+            }
+            | TranscriptStep::SampleIndices {
+                sponge_state_digest,
+                ..
+            } => *sponge_state_digest,
+        }
+    }
+}
+
+impl Display for Transcript {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        for (step_index, step) in self.steps.iter().enumerate() {
+            writeln!(f, "{step_index:>4}: {step}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for TranscriptStep {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let sponge_state_digest = self.sponge_state_digest();
+        match self {
+            TranscriptStep::Absorb {
+                item_name,
+                encoded_len,
+                ..
+            } => write!(f, "absorb {item_name} of length {encoded_len}")?,
+            TranscriptStep::SampleScalars { scalars, .. } => {
+                write!(f, "sample {} scalars", scalars.len())?
+            }
+            TranscriptStep::SampleIndices {
+                upper_bound,
+                indices,
+                ..
+            } => write!(f, "sample {} indices below {upper_bound}", indices.len())?,
+        }
+        write!(f, " → sponge state {sponge_state_digest}")
+    }
+}
+
+impl Display for TranscriptDivergence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let display = |step: &Option<TranscriptStep>| match step {
+            Some(step) => step.to_string(),
+            None => "end of transcript".to_string(),
+        };
+        writeln!(f, "transcripts diverge at step {}:", self.step_index)?;
+        writeln!(f, "  {}", display(&self.step))?;
+        write!(f, "  {}", display(&self.other_step))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::assert;
+    use assert2::let_assert;
+    use twenty_first::shared_math::b_field_element::BFieldElement;
+    use twenty_first::shared_math::tip5::Tip5;
+
+    use crate::proof::Claim;
+    use crate::proof::Proof;
+    use crate::proof_item::ProofItem;
+    use crate::proof_stream::ProofStream;
+
+    use super::*;
+
+    fn claim() -> Claim {
+        Claim {
+            program_digest: Digest::default(),
+            input: vec![BFieldElement::new(42)],
+            output: vec![BFieldElement::new(59)],
+        }
+    }
+
+    fn prover_transcript() -> (Transcript, Proof) {
+        let mut proof_stream = ProofStream::<Tip5>::new().with_transcript_recording();
+        proof_stream.alter_fiat_shamir_state_with(&claim());
+        proof_stream.enqueue(ProofItem::MerkleRoot(Digest::default()));
+        proof_stream.sample_scalars(3);
+        proof_stream.enqueue(ProofItem::AuthenticationStructure(vec![]));
+        proof_stream.enqueue(ProofItem::OutOfDomainBaseRow(vec![]));
+        proof_stream.sample_indices(1 << 10, 5);
+
+        let_assert!(Some(transcript) = proof_stream.transcript.take());
+        (transcript, proof_stream.into())
+    }
+
+    fn verifier_transcript(proof: &Proof, claim: &Claim) -> Transcript {
+        let_assert!(Ok(proof_stream) = ProofStream::<Tip5>::try_from(proof));
+        let mut proof_stream = proof_stream.with_transcript_recording();
+        proof_stream.alter_fiat_shamir_state_with(claim);
+        let_assert!(Ok(_) = proof_stream.dequeue());
+        proof_stream.sample_scalars(3);
+        let_assert!(Ok(_) = proof_stream.dequeue());
+        let_assert!(Ok(_) = proof_stream.dequeue());
+        proof_stream.sample_indices(1 << 10, 5);
+
+        let_assert!(Some(transcript) = proof_stream.transcript);
+        transcript
+    }
+
+    #[test]
+    fn transcript_records_only_steps_touching_the_sponge() {
+        let (transcript, _) = prover_transcript();
+        let num_absorbed_items = transcript
+            .steps
+            .iter()
+            .filter(|step| matches!(step, TranscriptStep::Absorb { .. }))
+            .count();
+
+        // The authentication structure is not part of the Fiat-Shamir heuristic.
+        assert!(5 == transcript.steps.len());
+        assert!(3 == num_absorbed_items);
+        assert!(transcript.to_string().contains("MerkleRoot"));
+    }
+
+    #[test]
+    fn transcripts_of_honest_prover_and_verifier_agree() {
+        let (prover_transcript, proof) = prover_transcript();
+        let verifier_transcript = verifier_transcript(&proof, &claim());
+        assert!(let None = prover_transcript.first_divergence(&verifier_transcript));
+    }
+
+    #[test]
+    fn diverging_transcripts_point_to_first_mismatching_step() {
+        let (prover_transcript, proof) = prover_transcript();
+        let mut other_claim = claim();
+        other_claim.output.push(BFieldElement::new(1));
+        let verifier_transcript = verifier_transcript(&proof, &other_claim);
+
+        let_assert!(Some(divergence) = prover_transcript.first_divergence(&verifier_transcript));
+        assert!(0 == divergence.step_index);
+        assert!(divergence
+            .to_string()
+            .contains("transcripts diverge at step 0"));
+    }
+
+    #[test]
+    fn shorter_transcript_diverges_where_it_ends() {
+        let (transcript, _) = prover_transcript();
+        let mut shorter_transcript = transcript.clone();
+        shorter_transcript.steps.pop();
+
+        let_assert!(Some(divergence) = transcript.first_divergence(&shorter_transcript));
+        assert!(4 == divergence.step_index);
+        assert!(divergence.step.is_some());
+        assert!(divergence.other_step.is_none());
+    }
+}
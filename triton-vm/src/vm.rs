@@ -5,9 +5,11 @@ use std::fmt::Formatter;
 use std::fmt::Result as FmtResult;
 
 use arbitrary::Arbitrary;
+use itertools::Itertools;
 use ndarray::Array1;
 use num_traits::One;
 use num_traits::Zero;
+use serde::Serializer;
 use serde_derive::*;
 use twenty_first::shared_math::b_field_element::BFieldElement;
 use twenty_first::shared_math::b_field_element::BFIELD_ZERO;
@@ -18,10 +20,18 @@ use twenty_first::shared_math::traits::Inverse;
 use twenty_first::util_types::algebraic_hasher::Domain;
 use twenty_first::util_types::algebraic_hasher::SpongeHasher;
 
+use crate::checkpoint::decode_checkpoint;
+use crate::checkpoint::encode_checkpoint;
+use crate::checkpoint::CheckpointKind;
+use crate::error::CheckpointError;
 use crate::error::InstructionError;
 use crate::error::InstructionError::*;
+use crate::error::JournalError;
+use crate::error::JournalError::*;
 use crate::instruction::AnInstruction::*;
 use crate::instruction::Instruction;
+use crate::journal::ExecutionJournal;
+use crate::journal::StepStart;
 use crate::op_stack::OpStackElement::*;
 use crate::op_stack::*;
 use crate::program::*;
@@ -39,7 +49,7 @@ type Result<T> = std::result::Result<T, InstructionError>;
 /// The number of helper variable registers
 pub const NUM_HELPER_VARIABLE_REGISTERS: usize = 6;
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Arbitrary)]
+#[derive(Debug, Clone, Eq, Serialize, Deserialize, Arbitrary)]
 pub struct VMState {
     /// The **program memory** stores the instructions (and their arguments) of the program
     /// currently being executed by Triton VM. It is read-only.
@@ -58,6 +68,7 @@ pub struct VMState {
     pub secret_digests: VecDeque<Digest>,
 
     /// The read-write **random-access memory** allows Triton VM to store arbitrary data.
+    #[serde(serialize_with = "serialize_sorted_by_address")]
     pub ram: HashMap<BFieldElement, BFieldElement>,
 
     ram_calls: Vec<RamTableCall>,
@@ -83,6 +94,15 @@ pub struct VMState {
 
     /// Indicates whether the terminating instruction `halt` has been executed.
     pub halting: bool,
+
+    /// Records the changes of every step, if enabled. See [`enable_journal`][enable_journal].
+    /// Not part of the state's identity: it is ignored when comparing states and is not
+    /// serialized.
+    ///
+    /// [enable_journal]: Self::enable_journal
+    #[serde(skip)]
+    #[arbitrary(default)]
+    journal: Option<ExecutionJournal>,
 }
 
 /// A call from the main processor to one of the co-processors, including the trace for that
@@ -130,6 +150,7 @@ impl VMState {
             instruction_pointer: 0,
             sponge_state: Default::default(),
             halting: false,
+            journal: None,
         }
     }
 
@@ -198,6 +219,24 @@ impl VMState {
             return Err(OpStackTooShallow);
         }
 
+        if self.journal.is_none() {
+            return self.execute_instruction(current_instruction);
+        }
+        let step_start = StepStart::capture(self, current_instruction);
+        let maybe_co_processor_calls = self.execute_instruction(current_instruction);
+        let mut journal = self.journal.take().unwrap();
+        match maybe_co_processor_calls {
+            Ok(_) => journal.record_step(step_start, self),
+            Err(_) => journal.discard_pending_changes(),
+        }
+        self.journal = Some(journal);
+        maybe_co_processor_calls
+    }
+
+    fn execute_instruction(
+        &mut self,
+        current_instruction: Instruction,
+    ) -> Result<Vec<CoProcessorCall>> {
         self.start_recording_op_stack_calls();
         let mut co_processor_calls = match current_instruction {
             Pop(n) => self.pop(n)?,
@@ -431,7 +470,10 @@ impl VMState {
         };
         self.ram_calls.push(ram_table_call);
 
-        self.ram.insert(ram_pointer, ram_value);
+        let previous_value = self.ram.insert(ram_pointer, ram_value);
+        if let Some(journal) = &mut self.journal {
+            journal.record_ram_write(ram_pointer, previous_value);
+        }
     }
 
     fn hash(&mut self) -> Result<Vec<CoProcessorCall>> {
@@ -906,6 +948,118 @@ impl VMState {
         }
         Ok(())
     }
+
+    /// Start recording the changes of every subsequent step in an [`ExecutionJournal`], enabling
+    /// [`step_back`][step_back] and [`seek_to_cycle`][seek]. Has no effect if the journal is
+    /// already enabled.
+    ///
+    /// Cloning a state clones its journal, which takes time and memory proportional to the number
+    /// of recorded steps. To take cheap snapshots of a state, clone it before enabling the
+    /// journal, or [disable](Self::disable_journal) the journal on a clone that does not need it.
+    ///
+    /// [step_back]: Self::step_back
+    /// [seek]: Self::seek_to_cycle
+    pub fn enable_journal(&mut self) {
+        self.journal.get_or_insert_with(ExecutionJournal::default);
+    }
+
+    /// Stop recording changes and discard the journal.
+    pub fn disable_journal(&mut self) {
+        self.journal = None;
+    }
+
+    pub fn journal(&self) -> Option<&ExecutionJournal> {
+        self.journal.as_ref()
+    }
+
+    /// Undo the most recent step recorded in the [journal](Self::enable_journal).
+    pub fn step_back(&mut self) -> std::result::Result<(), JournalError> {
+        let journal = self.journal.as_mut().ok_or(JournalDisabled)?;
+        let step_delta = journal.pop_step().ok_or(NoStepToUndo)?;
+        step_delta.undo(self);
+        Ok(())
+    }
+
+    /// Move to the given cycle, stepping backwards through the [journal](Self::enable_journal)
+    /// or executing further steps as required. Cycles before the journal was enabled cannot be
+    /// reached.
+    pub fn seek_to_cycle(&mut self, cycle: u32) -> std::result::Result<(), JournalError> {
+        let Some(journal) = &self.journal else {
+            return Err(JournalDisabled);
+        };
+        let first_recorded_cycle = self.cycle_count as usize - journal.len();
+        if (cycle as usize) < first_recorded_cycle {
+            return Err(CycleNotInJournal(cycle));
+        }
+
+        while self.cycle_count > cycle {
+            self.step_back()?;
+        }
+        while self.cycle_count < cycle {
+            self.step()?;
+        }
+        Ok(())
+    }
+
+    /// Serialize the complete state into a versioned [checkpoint](crate::checkpoint). Execution
+    /// can be continued from the checkpoint using
+    /// [`resume_from_checkpoint`](Self::resume_from_checkpoint), possibly on a different machine.
+    pub fn checkpoint(&self) -> Vec<u8> {
+        encode_checkpoint(CheckpointKind::VMState, self)
+    }
+
+    /// Restore a state previously serialized using [`checkpoint`](Self::checkpoint).
+    pub fn resume_from_checkpoint(checkpoint: &[u8]) -> std::result::Result<Self, CheckpointError> {
+        decode_checkpoint(CheckpointKind::VMState, checkpoint)
+    }
+}
+
+impl PartialEq for VMState {
+    /// Compares everything but the [journal](VMState::enable_journal).
+    fn eq(&self, other: &Self) -> bool {
+        let Self {
+            program,
+            public_input,
+            public_output,
+            secret_individual_tokens,
+            secret_digests,
+            ram,
+            ram_calls,
+            op_stack,
+            jump_stack,
+            cycle_count,
+            instruction_pointer,
+            sponge_state,
+            halting,
+            journal: _,
+        } = self;
+
+        program == &other.program
+            && public_input == &other.public_input
+            && public_output == &other.public_output
+            && secret_individual_tokens == &other.secret_individual_tokens
+            && secret_digests == &other.secret_digests
+            && ram == &other.ram
+            && ram_calls == &other.ram_calls
+            && op_stack == &other.op_stack
+            && jump_stack == &other.jump_stack
+            && cycle_count == &other.cycle_count
+            && instruction_pointer == &other.instruction_pointer
+            && sponge_state == &other.sponge_state
+            && halting == &other.halting
+    }
+}
+
+/// Serialize the RAM sorted by address. This makes serialization, and in particular
+/// [checkpoints](VMState::checkpoint), of equal states identical, independent of the iteration
+/// order of the underlying [`HashMap`].
+fn serialize_sorted_by_address<S: Serializer>(
+    ram: &HashMap<BFieldElement, BFieldElement>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    let mut ram = ram.iter().collect_vec();
+    ram.sort_unstable_by_key(|(address, _)| address.value());
+    serializer.collect_map(ram)
 }
 
 impl Display for VMState {
@@ -2367,6 +2521,88 @@ pub(crate) mod tests {
         instruction_does_not_change_vm_state_when_crashing_vm(program, 0);
     }
 
+    fn states_without_journal(program_and_input: &ProgramAndInput) -> Vec<VMState> {
+        let ProgramAndInput { program, .. } = program_and_input;
+        let public_input = program_and_input.public_input();
+        let non_determinism = program_and_input.non_determinism();
+        let mut vm_state = VMState::new(program, public_input, non_determinism);
+
+        let mut states = vec![vm_state.clone()];
+        while !vm_state.halting {
+            vm_state.step().unwrap();
+            states.push(vm_state.clone());
+        }
+        states
+    }
+
+    #[test]
+    fn stepping_back_through_journal_restores_every_previous_state() {
+        let programs = [
+            test_program_for_call_recurse_return(),
+            test_program_for_write_mem_read_mem(),
+            test_program_for_divine(),
+            test_program_for_divine_sibling_switch(),
+            test_program_for_many_sponge_instructions(),
+            test_program_for_read_io_write_io(),
+            property_based_test_program_for_random_ram_access(),
+            ProgramAndInput::without_input(
+                CALCULATE_NEW_MMR_PEAKS_FROM_APPEND_WITH_SAFE_LISTS.clone(),
+            ),
+        ];
+        for program_and_input in programs {
+            let expected_states = states_without_journal(&program_and_input);
+            let mut vm_state = expected_states[0].clone();
+            vm_state.enable_journal();
+            vm_state.run().unwrap();
+            assert!(expected_states.len() - 1 == vm_state.journal().unwrap().len());
+
+            for expected_state in expected_states.iter().rev().skip(1) {
+                vm_state.step_back().unwrap();
+                assert!(expected_state == &vm_state);
+            }
+            let_assert!(Err(JournalError::NoStepToUndo) = vm_state.step_back());
+        }
+    }
+
+    #[test]
+    fn seeking_to_cycle_moves_back_and_forth() {
+        let program_and_input = test_program_for_many_sponge_instructions();
+        let expected_states = states_without_journal(&program_and_input);
+        let mut vm_state = expected_states[0].clone();
+        vm_state.enable_journal();
+
+        for cycle in [7, 2, 11, 11, 0, 5] {
+            vm_state.seek_to_cycle(cycle).unwrap();
+            assert!(expected_states[cycle as usize] == vm_state);
+        }
+    }
+
+    #[test]
+    fn journal_is_ignored_when_comparing_states() {
+        let program_and_input = test_program_for_many_sponge_instructions();
+        let mut state = states_without_journal(&program_and_input)[0].clone();
+        let mut state_with_journal = state.clone();
+        state_with_journal.enable_journal();
+
+        state.step().unwrap();
+        state_with_journal.step().unwrap();
+        assert!(1 == state_with_journal.journal().unwrap().len());
+        assert!(state == state_with_journal);
+    }
+
+    #[test]
+    fn cannot_seek_to_cycle_before_journal_was_enabled() {
+        let program_and_input = test_program_for_many_sponge_instructions();
+        let mut vm_state = states_without_journal(&program_and_input)[0].clone();
+        let_assert!(Err(JournalError::JournalDisabled) = vm_state.seek_to_cycle(0));
+
+        vm_state.step().unwrap();
+        vm_state.enable_journal();
+        vm_state.step().unwrap();
+        let_assert!(Err(JournalError::CycleNotInJournal(0)) = vm_state.seek_to_cycle(0));
+        let_assert!(Ok(()) = vm_state.seek_to_cycle(1));
+    }
+
     #[proptest]
     fn serialize_deserialize_vm_state_to_and_from_json_is_identity(
         #[strategy(arb())] vm_state: VMState,
@@ -2375,4 +2611,19 @@ pub(crate) mod tests {
         let deserialized = serde_json::from_str(&serialized).unwrap();
         prop_assert_eq!(vm_state, deserialized);
     }
+
+    #[test]
+    fn checkpoints_of_equal_states_are_identical() {
+        let program = triton_program!(halt);
+        let ram = (0..100).map(|i| (BFieldElement::new(i), BFieldElement::new(i * i)));
+        let ram_inserted_forwards: HashMap<_, _> = ram.clone().collect();
+        let ram_inserted_backwards: HashMap<_, _> = ram.rev().collect();
+
+        let mut state = VMState::new(&program, [].into(), [].into());
+        let mut other_state = state.clone();
+        state.ram = ram_inserted_forwards;
+        other_state.ram = ram_inserted_backwards;
+        assert!(state == other_state);
+        assert!(state.checkpoint() == other_state.checkpoint());
+    }
 }